@@ -56,8 +56,9 @@ install -Dm755 protonhax /usr/bin/protonhax
 install -Dm644 protonic.desktop /usr/share/applications/protonic.desktop
 install -Dm644 icon.png /usr/share/icons/hicolor/256x256/apps/protonic.png
 
-# Update icon cache
+# Update icon cache and register the protonic:// link handler
 gtk-update-icon-cache -f /usr/share/icons/hicolor 2>/dev/null || true
+update-desktop-database /usr/share/applications 2>/dev/null || true
 
 echo "Protonic installed successfully!"
 echo "You can now launch it from your application menu or by running 'protonic'"
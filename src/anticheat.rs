@@ -0,0 +1,56 @@
+//! Recognising games protected by Easy Anti-Cheat or BattlEye from the files they ship.
+//! Anti-cheat can see programs started inside the game's Wine session, so running
+//! companions there in an online match risks a ban.
+
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AntiCheat {
+    EasyAntiCheat,
+    BattlEye,
+}
+
+impl AntiCheat {
+    pub fn label(self) -> &'static str {
+        match self {
+            AntiCheat::EasyAntiCheat => "Easy Anti-Cheat",
+            AntiCheat::BattlEye => "BattlEye",
+        }
+    }
+}
+
+// Lowercase file and folder names the anti-cheat runtimes install next to the game
+const MARKERS: [(&str, AntiCheat); 6] = [
+    ("easyanticheat", AntiCheat::EasyAntiCheat),
+    ("easyanticheat_eos", AntiCheat::EasyAntiCheat),
+    ("start_protected_game.exe", AntiCheat::EasyAntiCheat),
+    ("battleye", AntiCheat::BattlEye),
+    ("beservice.exe", AntiCheat::BattlEye),
+    ("beservice_x64.exe", AntiCheat::BattlEye),
+];
+
+fn marker(name: &str) -> Option<AntiCheat> {
+    let name = name.to_lowercase();
+    MARKERS
+        .iter()
+        .find(|(marker, _)| *marker == name)
+        .map(|(_, anti_cheat)| *anti_cheat)
+}
+
+/// The anti-cheat a game installed in `game_dir` uses, looking at the folder itself and
+/// the folders directly inside it (where many games keep their binaries)
+pub fn detect(game_dir: &Path) -> Option<AntiCheat> {
+    let entries: Vec<_> = fs::read_dir(game_dir).ok()?.flatten().collect();
+    entries
+        .iter()
+        .find_map(|entry| marker(&entry.file_name().to_string_lossy()))
+        .or_else(|| {
+            entries
+                .iter()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| fs::read_dir(entry.path()).ok())
+                .flat_map(|dir| dir.flatten())
+                .find_map(|entry| marker(&entry.file_name().to_string_lossy()))
+        })
+}
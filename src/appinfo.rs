@@ -0,0 +1,287 @@
+//! Reader for Steam's binary app metadata cache (appcache/appinfo.vdf).
+//! Lets us show developer, genres and platforms without touching the network.
+
+use crate::vdf::Node;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+// Format versions Steam has used for appinfo.vdf; v29 moved keys into a string table
+const MAGIC_V27: u32 = 0x07564427;
+const MAGIC_V28: u32 = 0x07564428;
+const MAGIC_V29: u32 = 0x07564429;
+
+// Binary KeyValues type tags
+const TYPE_SECTION: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT32: u8 = 0x02;
+const TYPE_FLOAT32: u8 = 0x03;
+const TYPE_POINTER: u8 = 0x04;
+const TYPE_WIDE_STRING: u8 = 0x05;
+const TYPE_COLOR: u8 = 0x06;
+const TYPE_UINT64: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+const TYPE_INT64: u8 = 0x0A;
+const TYPE_END_ALT: u8 = 0x0B;
+
+/// Store metadata for one app, as far as the local cache knows it
+#[derive(Debug, Clone, Default)]
+pub struct AppMetadata {
+    pub developer: Option<String>,
+    pub genres: Vec<String>,
+    /// Platforms from the "oslist" field, e.g. ["windows", "linux"]
+    pub os_list: Vec<String>,
+}
+
+impl AppMetadata {
+    /// Whether the game only ships a Windows build (so it always runs through Proton)
+    pub fn is_windows_only(&self) -> bool {
+        self.os_list.iter().all(|os| os == "windows") && !self.os_list.is_empty()
+    }
+
+    pub fn has_native_linux(&self) -> bool {
+        self.os_list.iter().any(|os| os == "linux")
+    }
+
+    /// One-line summary for the details panel, e.g. "Valve · Action · windows, linux"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(developer) = &self.developer {
+            parts.push(developer.clone());
+        }
+        if !self.genres.is_empty() {
+            parts.push(self.genres.join(", "));
+        }
+        if !self.os_list.is_empty() {
+            parts.push(self.os_list.join(", "));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Read metadata for the given app IDs from `<steam>/appcache/appinfo.vdf`.
+/// Entries for other apps are skipped without being parsed.
+pub fn load(steam_path: &Path, wanted: &HashSet<u32>) -> Result<HashMap<u32, AppMetadata>, String> {
+    let path = steam_path.join("appcache").join("appinfo.vdf");
+    let data = fs::read(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let mut reader = Reader {
+        data: &data,
+        pos: 0,
+    };
+
+    let magic = reader.u32()?;
+    if magic != MAGIC_V27 && magic != MAGIC_V28 && magic != MAGIC_V29 {
+        return Err(format!("Unsupported appinfo.vdf version {:#x}", magic));
+    }
+    let _universe = reader.u32()?;
+
+    let string_table = if magic == MAGIC_V29 {
+        let offset = reader.u64()? as usize;
+        Some(read_string_table(&data, offset)?)
+    } else {
+        None
+    };
+
+    let mut metadata = HashMap::new();
+    loop {
+        let app_id = reader.u32()?;
+        if app_id == 0 {
+            break;
+        }
+        // Size counts everything after the size field itself
+        let size = reader.u32()? as usize;
+        let entry_end = reader.pos + size;
+        if !wanted.contains(&app_id) {
+            reader.pos = entry_end;
+            continue;
+        }
+
+        // info state, last updated, PICS token, SHA-1, change number
+        reader.skip(4 + 4 + 8 + 20 + 4)?;
+        if magic != MAGIC_V27 {
+            // SHA-1 of the binary data
+            reader.skip(20)?;
+        }
+        let mut kv = KvReader {
+            reader: Reader {
+                data: &data[..entry_end.min(data.len())],
+                pos: reader.pos,
+            },
+            string_table: string_table.as_deref(),
+        };
+        match kv.section() {
+            Ok(root) => {
+                metadata.insert(app_id, extract(&root));
+            }
+            Err(e) => println!("Warning: Skipping appinfo for {}: {}", app_id, e),
+        }
+        reader.pos = entry_end;
+    }
+    Ok(metadata)
+}
+
+fn read_string_table(data: &[u8], offset: usize) -> Result<Vec<String>, String> {
+    let mut reader = Reader { data, pos: offset };
+    let count = reader.u32()?;
+    (0..count).map(|_| reader.cstring()).collect()
+}
+
+/// Pick the fields we care about out of an app's KeyValues tree
+fn extract(root: &Node) -> AppMetadata {
+    let common = root.get_path(&["appinfo", "common"]);
+    let value = |node: Option<&Node>| match node {
+        Some(Node::Value(v)) if !v.is_empty() => Some(v.clone()),
+        _ => None,
+    };
+
+    // Newer entries list developers under common/associations, older ones under extended
+    let developer = common
+        .and_then(|c| c.get("associations"))
+        .and_then(|associations| match associations {
+            Node::Section(pairs) => pairs.iter().find_map(|(_, entry)| {
+                (value(entry.get("type")).as_deref() == Some("developer"))
+                    .then(|| value(entry.get("name")))
+                    .flatten()
+            }),
+            Node::Value(_) => None,
+        })
+        .or_else(|| value(root.get_path(&["appinfo", "extended", "developer"])));
+
+    let genres = match common.and_then(|c| c.get("genres")) {
+        Some(Node::Section(pairs)) => pairs
+            .iter()
+            .filter_map(|(_, id)| value(Some(id)))
+            .map(|id| genre_name(&id))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let os_list = value(common.and_then(|c| c.get("oslist")))
+        .map(|list| {
+            list.split(',')
+                .map(|os| os.trim().to_lowercase())
+                .filter(|os| !os.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    AppMetadata {
+        developer,
+        genres,
+        os_list,
+    }
+}
+
+/// Steam stores genres as numeric IDs; map the common ones to their store names
+fn genre_name(id: &str) -> String {
+    match id {
+        "1" => "Action",
+        "2" => "Strategy",
+        "3" => "RPG",
+        "4" => "Casual",
+        "9" => "Racing",
+        "18" => "Sports",
+        "23" => "Indie",
+        "25" => "Adventure",
+        "28" => "Simulation",
+        "29" => "Massively Multiplayer",
+        "37" => "Free to Play",
+        "70" => "Early Access",
+        other => return format!("Genre {}", other),
+    }
+    .to_string()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let end = self.pos + len;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| format!("Unexpected end of data at offset {}", self.pos))?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), String> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Null-terminated UTF-8 (lossy) string
+    fn cstring(&mut self) -> Result<String, String> {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| "Unterminated string".to_string())?;
+        let text = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(text)
+    }
+}
+
+/// Binary KeyValues reader producing the same tree as the text parser
+struct KvReader<'a> {
+    reader: Reader<'a>,
+    string_table: Option<&'a [String]>,
+}
+
+impl KvReader<'_> {
+    fn key(&mut self) -> Result<String, String> {
+        match self.string_table {
+            Some(table) => {
+                let index = self.reader.u32()? as usize;
+                table
+                    .get(index)
+                    .cloned()
+                    .ok_or_else(|| format!("Bad string table index {}", index))
+            }
+            None => self.reader.cstring(),
+        }
+    }
+
+    fn section(&mut self) -> Result<Node, String> {
+        let mut pairs = Vec::new();
+        loop {
+            let tag = self.reader.u8()?;
+            if tag == TYPE_END || tag == TYPE_END_ALT {
+                return Ok(Node::Section(pairs));
+            }
+            let key = self.key()?;
+            let value = match tag {
+                TYPE_SECTION => self.section()?,
+                TYPE_STRING => Node::Value(self.reader.cstring()?),
+                TYPE_INT32 | TYPE_POINTER | TYPE_COLOR => {
+                    Node::Value((self.reader.u32()? as i32).to_string())
+                }
+                TYPE_FLOAT32 => Node::Value(
+                    f32::from_le_bytes(self.reader.take(4)?.try_into().unwrap()).to_string(),
+                ),
+                TYPE_UINT64 => Node::Value(self.reader.u64()?.to_string()),
+                TYPE_INT64 => Node::Value((self.reader.u64()? as i64).to_string()),
+                TYPE_WIDE_STRING => {
+                    return Err("Wide strings are not supported".to_string());
+                }
+                other => return Err(format!("Unknown value type {:#x}", other)),
+            };
+            pairs.push((key, value));
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, cpal};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufReader, Cursor};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Embed our ogg audio files at compile time
+const AUDIO_LAUNCH_GAME: &[u8] = include_bytes!("../audio/LaunchGame.ogg");
+const AUDIO_LAUNCH_PROGRAM: &[u8] = include_bytes!("../audio/LaunchProgram.ogg");
+
+// How many sounds can wait for playback before new ones are dropped
+const QUEUE_SIZE: usize = 4;
+
+// Every sound pack directory needs one of these to be picked up
+const PACK_MANIFEST: &str = "pack.toml";
+
+/// Name shown in the UI for the embedded sounds
+pub const BUILTIN_PACK: &str = "Built-in";
+
+/// Name shown in the UI for the system's default output device
+pub const DEFAULT_DEVICE: &str = "System default";
+
+/// Things Protonic can make a noise about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEvent {
+    LaunchGame,
+    LaunchProgram,
+}
+
+impl SoundEvent {
+    const ALL: [SoundEvent; 2] = [SoundEvent::LaunchGame, SoundEvent::LaunchProgram];
+
+    fn builtin_data(self) -> &'static [u8] {
+        match self {
+            SoundEvent::LaunchGame => AUDIO_LAUNCH_GAME,
+            SoundEvent::LaunchProgram => AUDIO_LAUNCH_PROGRAM,
+        }
+    }
+
+    /// Key used for this event in a sound pack manifest
+    fn manifest_key(self) -> &'static str {
+        match self {
+            SoundEvent::LaunchGame => "launch_game",
+            SoundEvent::LaunchProgram => "launch_program",
+        }
+    }
+
+    /// Repeats of the same event within this window are skipped
+    fn cooldown(self) -> Option<Duration> {
+        match self {
+            SoundEvent::LaunchGame => None,
+            SoundEvent::LaunchProgram => Some(Duration::from_millis(1500)),
+        }
+    }
+}
+
+/// Manifest of a sound pack, e.g. ~/.local/share/protonic/sounds/retro/pack.toml:
+///
+/// ```toml
+/// [sounds]
+/// launch_game = "start.ogg"
+/// launch_program = "go.ogg"
+/// ```
+#[derive(Deserialize)]
+struct PackManifest {
+    #[serde(default)]
+    sounds: HashMap<String, String>,
+}
+
+/// Sounds loaded from a pack; events it doesn't cover use the built-in audio
+type PackSounds = HashMap<SoundEvent, Arc<[u8]>>;
+
+/// Directory holding user sound packs, one sub-directory per pack
+pub fn sound_packs_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join("sounds"))
+}
+
+/// Names of all installed sound packs, sorted
+pub fn available_sound_packs() -> Vec<String> {
+    let mut packs = Vec::new();
+    if let Some(Ok(entries)) = sound_packs_dir().map(fs::read_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.join(PACK_MANIFEST).is_file() {
+                packs.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    packs.sort();
+    packs
+}
+
+/// Read a pack's manifest and the ogg files it points to
+fn load_sound_pack(name: &str) -> Result<PackSounds, String> {
+    let pack_dir = sound_packs_dir()
+        .ok_or_else(|| "Could not find the data directory".to_string())?
+        .join(name);
+    let manifest = fs::read_to_string(pack_dir.join(PACK_MANIFEST))
+        .map_err(|e| format!("Failed to read {}: {}", PACK_MANIFEST, e))?;
+    let manifest: PackManifest =
+        toml::from_str(&manifest).map_err(|e| format!("Invalid {}: {}", PACK_MANIFEST, e))?;
+
+    let mut sounds = PackSounds::new();
+    for event in SoundEvent::ALL {
+        if let Some(file) = manifest.sounds.get(event.manifest_key()) {
+            let data = fs::read(pack_dir.join(file))
+                .map_err(|e| format!("Failed to read {}: {}", file, e))?;
+            sounds.insert(event, Arc::from(data));
+        }
+    }
+    Ok(sounds)
+}
+
+/// Check that an audio output device can be opened
+pub fn output_device_available() -> bool {
+    OutputStream::try_default().is_ok()
+}
+
+/// Names of the output devices sounds can be played on
+pub fn output_devices() -> Vec<String> {
+    let Ok(devices) = cpal::default_host().output_devices() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = devices.filter_map(|device| device.name().ok()).collect();
+    names.dedup();
+    names
+}
+
+/// Open the output device with this name, or the default one when the name is empty
+/// or the device is gone
+fn open_output(name: &str) -> Option<(OutputStream, OutputStreamHandle)> {
+    if !name.is_empty() {
+        let device = cpal::default_host()
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| {
+                devices.find(|device| device.name().is_ok_and(|device_name| device_name == name))
+            });
+        match device.map(|device| OutputStream::try_from_device(&device)) {
+            Some(Ok(output)) => return Some(output),
+            Some(Err(e)) => println!(
+                "Warning: Could not open audio device '{}': {}, using the default",
+                name, e
+            ),
+            None => println!(
+                "Warning: Audio device '{}' not found, using the default",
+                name
+            ),
+        }
+    }
+    OutputStream::try_default().ok()
+}
+
+/// Shared audio player. Sounds are queued and played one after another on a
+/// single background thread so back-to-back events never overlap.
+#[derive(Clone)]
+pub struct AudioService {
+    sender: SyncSender<SoundEvent>,
+    pack: Arc<Mutex<PackSounds>>,
+    // Output device name, empty for the default
+    device: Arc<Mutex<String>>,
+}
+
+impl AudioService {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+        let pack = Arc::new(Mutex::new(PackSounds::new()));
+        let pack_playback = Arc::clone(&pack);
+        let device = Arc::new(Mutex::new(String::new()));
+        let device_playback = Arc::clone(&device);
+        thread::spawn(move || playback_loop(receiver, pack_playback, device_playback));
+        Self {
+            sender,
+            pack,
+            device,
+        }
+    }
+
+    /// Queue a sound. If the queue is already full the sound is dropped.
+    pub fn play(&self, event: SoundEvent) {
+        let _ = self.sender.try_send(event);
+    }
+
+    /// Switch to a sound pack by name; empty or unknown names use the built-in sounds
+    pub fn set_sound_pack(&self, name: &str) {
+        let sounds = if name.is_empty() || name == BUILTIN_PACK {
+            PackSounds::new()
+        } else {
+            match load_sound_pack(name) {
+                Ok(sounds) => sounds,
+                Err(e) => {
+                    println!("Warning: Could not load sound pack '{}': {}", name, e);
+                    PackSounds::new()
+                }
+            }
+        };
+        *self.pack.lock().unwrap() = sounds;
+    }
+
+    /// Play sounds on the output device with this name; empty for the default.
+    /// Takes effect with the next sound.
+    pub fn set_output_device(&self, name: &str) {
+        *self.device.lock().unwrap() = name.to_string();
+    }
+}
+
+fn playback_loop(
+    receiver: Receiver<SoundEvent>,
+    pack: Arc<Mutex<PackSounds>>,
+    device: Arc<Mutex<String>>,
+) {
+    // The stream must be kept alive for playback, so it lives on this thread
+    let mut output: Option<(OutputStream, OutputStreamHandle)> = None;
+    // Device the stream was opened for
+    let mut output_device = String::new();
+    let mut last_played: HashMap<SoundEvent, Instant> = HashMap::new();
+
+    for event in receiver {
+        if let (Some(cooldown), Some(last)) = (event.cooldown(), last_played.get(&event))
+            && last.elapsed() < cooldown
+        {
+            continue;
+        }
+
+        let wanted = device.lock().unwrap().clone();
+        if output.is_none() || wanted != output_device {
+            output = open_output(&wanted);
+            output_device = wanted;
+        }
+        let Some((_, stream_handle)) = &output else {
+            continue;
+        };
+
+        let data: Arc<[u8]> = pack
+            .lock()
+            .unwrap()
+            .get(&event)
+            .cloned()
+            .unwrap_or_else(|| Arc::from(event.builtin_data()));
+
+        if let Ok(sink) = Sink::try_new(stream_handle) {
+            let cursor = Cursor::new(data);
+            if let Ok(source) = Decoder::new(BufReader::new(cursor)) {
+                sink.append(source);
+                sink.sleep_until_end();
+            }
+        }
+        last_played.insert(event, Instant::now());
+    }
+}
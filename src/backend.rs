@@ -0,0 +1,108 @@
+//! How programs get into a game's prefix: through protonhax (which the game has to be
+//! started with, via its launch options), or natively with the game's own Proton
+//! (see proton.rs), which works without anything in the launch options.
+
+use crate::{proton, protonhax};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Protonhax,
+    Native,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 2] = [Backend::Protonhax, Backend::Native];
+
+    /// Name shown in the backend picker
+    pub fn label(self) -> &'static str {
+        match self {
+            Backend::Protonhax => "protonhax",
+            Backend::Native => "Native (game's Proton)",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Backend {
+        Backend::ALL
+            .into_iter()
+            .find(|backend| backend.label() == label)
+            .unwrap_or_default()
+    }
+
+    /// Whether the game is running, as far as this backend can tell
+    pub fn is_running(self, app_id: &str) -> bool {
+        match self {
+            Backend::Protonhax => protonhax::active_sessions().iter().any(|id| id == app_id),
+            Backend::Native => proton::running_games().contains(app_id),
+        }
+    }
+
+    /// When the game's current run started, None if it isn't running
+    pub fn session_started(self, app_id: &str) -> Option<SystemTime> {
+        match self {
+            Backend::Protonhax => protonhax::session_started(app_id),
+            Backend::Native => proton::session_started(app_id),
+        }
+    }
+}
+
+/// Starts programs inside one game's prefix
+#[derive(Clone)]
+pub enum Injector {
+    Protonhax,
+    Native(proton::Runtime),
+}
+
+impl Injector {
+    /// `prefix` is the game's compatdata directory, which the native backend needs
+    pub fn new(backend: Backend, app_id: &str, prefix: Option<&Path>) -> Result<Injector, String> {
+        match backend {
+            Backend::Protonhax => Ok(Injector::Protonhax),
+            Backend::Native => {
+                let prefix = prefix.ok_or_else(|| {
+                    format!(
+                        "Game {} is not installed, its prefix can't be found",
+                        app_id
+                    )
+                })?;
+                proton::Runtime::find(app_id, prefix).map(Injector::Native)
+            }
+        }
+    }
+
+    /// Run a Windows program in the game's prefix
+    pub fn run_command(&self, app_id: &str, exe: &str) -> Command {
+        match self {
+            Injector::Protonhax => protonhax::run_command(app_id, exe),
+            Injector::Native(runtime) => runtime.run_command(app_id, exe),
+        }
+    }
+
+    /// A Wine command prompt in the game's prefix
+    pub fn cmd_command(&self, app_id: &str) -> Command {
+        match self {
+            Injector::Protonhax => protonhax::cmd_command(app_id),
+            Injector::Native(runtime) => runtime.cmd_command(app_id),
+        }
+    }
+}
+
+/// Running games: every protonhax session, and the games in `native` that are running
+/// without one
+pub fn running_games(native: &BTreeSet<String>) -> Vec<String> {
+    let mut running: BTreeSet<String> = protonhax::active_sessions().into_iter().collect();
+    if !native.is_empty() {
+        running.extend(
+            proton::running_games()
+                .into_iter()
+                .filter(|app_id| native.contains(app_id)),
+        );
+    }
+    running.into_iter().collect()
+}
@@ -0,0 +1,176 @@
+//! Export and import of every game's Steam launch options, for moving to a fresh Steam install,
+//! and the snapshots of Steam's config files taken before Protonic writes to them.
+
+use crate::vdf::{self, Node};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Older snapshots are deleted once there are more than this
+const KEEP_SNAPSHOTS: usize = 30;
+
+/// File layout of a launch options backup
+#[derive(Serialize, Deserialize, Default)]
+struct Backup {
+    /// App ID -> LaunchOptions
+    launch_options: BTreeMap<String, String>,
+}
+
+/// Write the LaunchOptions of all games that have any to `path`. Returns how many were saved.
+pub fn export_launch_options(path: &Path) -> Result<usize, String> {
+    let localconfig_path = crate::get_localconfig_path()
+        .ok_or_else(|| "Could not find Steam localconfig.vdf".to_string())?;
+    let content = fs::read_to_string(&localconfig_path)
+        .map_err(|e| format!("Failed to read localconfig.vdf: {}", e))?;
+    let root = vdf::parse(&content)?;
+
+    let mut backup = Backup::default();
+    if let Some(Node::Section(apps)) = root.get_path(&crate::LOCALCONFIG_APPS_PATH) {
+        for (app_id, section) in apps {
+            if let Some(Node::Value(options)) = section.get("LaunchOptions")
+                && !options.is_empty()
+            {
+                backup
+                    .launch_options
+                    .insert(app_id.clone(), options.clone());
+            }
+        }
+    }
+
+    let text = toml::to_string_pretty(&backup).map_err(|e| e.to_string())?;
+    fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(backup.launch_options.len())
+}
+
+/// Restore launch options from a backup. Every change goes through the journal,
+/// so it can be reverted from the history window. Returns (restored, failed app IDs).
+pub fn import_launch_options(path: &Path) -> Result<(usize, Vec<String>), String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let backup: Backup = toml::from_str(&text).map_err(|e| format!("Invalid backup: {}", e))?;
+
+    let mut restored = 0;
+    let mut failed = Vec::new();
+    for (app_id, options) in backup.launch_options {
+        if crate::read_launch_options(&app_id).as_deref() == Some(options.as_str()) {
+            continue;
+        }
+        match crate::set_launch_options(&app_id, &options, "import") {
+            Ok(()) => restored += 1,
+            Err(e) => {
+                println!(
+                    "Warning: Could not restore launch options for {}: {}",
+                    app_id, e
+                );
+                failed.push(app_id);
+            }
+        }
+    }
+    Ok((restored, failed))
+}
+
+/// Account ID of a file in an account's userdata, e.g. "12345" for
+/// userdata/12345/config/localconfig.vdf
+fn account_of(file: &Path) -> Option<String> {
+    let account_dir = file.parent()?.parent()?;
+    if account_dir.parent()?.file_name()? != "userdata" {
+        return None;
+    }
+    Some(account_dir.file_name()?.to_string_lossy().into_owned())
+}
+
+/// Snapshots live next to the config, e.g. ~/.config/protonic/backups. Those of an
+/// account's files (localconfig.vdf, shortcuts.vdf) go into a folder per account, so
+/// restoring never puts one account's file over another's.
+fn snapshot_dir(file: &Path) -> Option<PathBuf> {
+    let dir = dirs::config_dir()?
+        .join(crate::config_name())
+        .join("backups");
+    Some(match account_of(file) {
+        Some(account) => dir.join(account),
+        None => dir,
+    })
+}
+
+/// Snapshots of `file`: localconfig.vdf (`kind` "localconfig"), shortcuts.vdf
+/// ("shortcuts") or config.vdf ("config"), oldest first. Their names start with a
+/// millisecond timestamp, so sorting by name sorts by age.
+fn snapshots(file: &Path, kind: &str) -> Vec<PathBuf> {
+    let Some(entries) = snapshot_dir(file).and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let suffix = format!("-{}.vdf", kind);
+    let mut snapshots: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.to_string_lossy().ends_with(&suffix))
+        .collect();
+    snapshots.sort();
+    snapshots
+}
+
+/// Copy a Steam config file into the backups folder before it gets rewritten
+fn snapshot(file: &Path, kind: &str) -> Result<PathBuf, String> {
+    let dir =
+        snapshot_dir(file).ok_or_else(|| "Could not find the config directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let snapshot = dir.join(format!("{}-{}.vdf", millis, kind));
+    fs::copy(file, &snapshot).map_err(|e| format!("Failed to back up {}.vdf: {}", kind, e))?;
+
+    let snapshots = snapshots(file, kind);
+    let excess = snapshots.len().saturating_sub(KEEP_SNAPSHOTS);
+    for old in &snapshots[..excess] {
+        let _ = fs::remove_file(old);
+    }
+    Ok(snapshot)
+}
+
+/// Copy localconfig.vdf into the backups folder before it gets rewritten
+pub fn snapshot_localconfig(localconfig: &Path) -> Result<PathBuf, String> {
+    snapshot(localconfig, "localconfig")
+}
+
+/// Copy shortcuts.vdf (non-Steam games) into the backups folder before it gets rewritten
+pub fn snapshot_shortcuts(shortcuts: &Path) -> Result<PathBuf, String> {
+    snapshot(shortcuts, "shortcuts")
+}
+
+/// Copy config.vdf (Steam's compatibility tool settings) into the backups folder before
+/// it gets rewritten
+pub fn snapshot_config(config: &Path) -> Result<PathBuf, String> {
+    snapshot(config, "config")
+}
+
+/// Put a snapshot back in place of the file it was taken of
+pub fn restore_snapshot(snapshot: &Path, file: &Path) -> Result<(), String> {
+    fs::copy(snapshot, file)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to restore {}: {}", snapshot.display(), e))
+}
+
+/// Undo Protonic's last write to the current account's localconfig.vdf. The snapshot is used up, so
+/// restoring again steps further back. Returns when the snapshot was taken.
+pub fn restore_previous_localconfig() -> Result<u64, String> {
+    let localconfig = crate::get_localconfig_path()
+        .ok_or_else(|| "Could not find Steam localconfig.vdf".to_string())?;
+    let snapshot = snapshots(&localconfig, "localconfig")
+        .pop()
+        .ok_or_else(|| "There are no earlier versions of localconfig.vdf".to_string())?;
+    let content = fs::read_to_string(&snapshot)
+        .map_err(|e| format!("Failed to read {}: {}", snapshot.display(), e))?;
+    vdf::parse(&content).map_err(|e| format!("{} is damaged: {}", snapshot.display(), e))?;
+
+    restore_snapshot(&snapshot, &localconfig)?;
+    let _ = fs::remove_file(&snapshot);
+    let millis: u64 = snapshot
+        .file_name()
+        .and_then(|name| name.to_string_lossy().split('-').next()?.parse().ok())
+        .unwrap_or(0);
+    Ok(millis / 1000)
+}
@@ -0,0 +1,251 @@
+//! The command line, and `protonic launch`: start a game and its programs from a script
+//! or a Steam shortcut without opening the window. Status goes to stdout.
+
+use crate::launch::Launch;
+use crate::process::ProcessTracker;
+use crate::{AppConfig, history, names, notify, process, store, winetricks};
+use clap::{Args, Parser, Subcommand};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::Duration;
+
+// The protonic binary's command line; `--help` shows the doc comments below
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Use a separate setup with its own settings in ~/.config/protonic-<NAME>/
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Start with hotkeys, audio, auto-configure and integrations off
+    #[arg(long)]
+    pub safe_mode: bool,
+    /// Open the window with this game selected
+    #[arg(long, value_name = "APP_ID", value_parser = app_id)]
+    pub game: Option<String>,
+    /// Also launch the game given with --game
+    #[arg(long, requires = "game")]
+    pub launch: bool,
+    /// A protonic://select/<app id> or protonic://launch/<app id> link
+    pub link: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Check the setup for common problems and exit
+    Doctor,
+    /// Start a game and its programs without opening the window
+    Launch(LaunchArgs),
+}
+
+#[derive(Args)]
+pub struct LaunchArgs {
+    /// Steam app ID of the game
+    #[arg(required_unless_present = "name", conflicts_with = "name", value_parser = app_id)]
+    app_id: Option<String>,
+    /// The game's name, or a part of it only one game has
+    #[arg(long)]
+    name: Option<String>,
+    /// Start the programs even in a game that uses anti-cheat
+    #[arg(long)]
+    allow_anticheat: bool,
+}
+
+/// An app ID is all digits
+fn app_id(text: &str) -> Result<String, String> {
+    if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) {
+        Ok(text.to_string())
+    } else {
+        Err(format!("'{}' isn't an app ID", text))
+    }
+}
+
+/// App ID of the installed game called `name`, or the only one whose name contains it
+fn find_game<'a>(games: &'a BTreeMap<String, String>, name: &str) -> Result<&'a String, String> {
+    if let Some((_, id)) = games.iter().find(|(game, _)| names::is_same(game, name)) {
+        return Ok(id);
+    }
+    let matching: Vec<(&String, &String)> = games
+        .iter()
+        .filter(|(game, _)| names::matches(game, name))
+        .collect();
+    match matching.as_slice() {
+        [(_, id)] => Ok(id),
+        [] => Err(format!("No installed game matches '{}'", name)),
+        _ => Err(format!(
+            "'{}' matches several games: {}",
+            name,
+            matching
+                .iter()
+                .map(|(game, _)| game.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Run `protonic launch` and return the process exit code
+pub fn launch(cfg: AppConfig, args: &LaunchArgs) -> i32 {
+    let allow_anticheat = args.allow_anticheat;
+    let (games, installed) = crate::scan_libraries();
+    let app_id = match (&args.app_id, &args.name) {
+        (Some(id), _) => id.clone(),
+        (None, Some(name)) => match find_game(&games, name) {
+            Ok(id) => id.clone(),
+            Err(e) => {
+                println!("Error: {}", e);
+                return 1;
+            }
+        },
+        // clap makes sure one of them is given
+        (None, None) => return 2,
+    };
+    let game_name = games
+        .iter()
+        .find(|(_, id)| **id == app_id)
+        .map(|(name, _)| name.clone())
+        .unwrap_or_else(|| format!("App {}", app_id));
+    let launch = match Launch::prepare(&cfg, &app_id, &game_name, installed.get(&app_id), true) {
+        Ok(launch) => launch,
+        Err(e) => {
+            println!("Error: {}", e);
+            return 1;
+        }
+    };
+    println!("{} ({})", game_name, app_id);
+
+    // There's nobody to ask at the injection point, so the override has to come up front
+    if let Some(anti_cheat) = launch.anti_cheat {
+        if !allow_anticheat {
+            println!(
+                "Error: {} uses {}, which can see programs started inside the game and may ban \
+                 the account. Add --allow-anticheat to start them anyway.",
+                game_name,
+                anti_cheat.label()
+            );
+            return 1;
+        }
+        println!(
+            "Warning: Starting programs in {} despite {}, as requested",
+            game_name,
+            anti_cheat.label()
+        );
+    }
+
+    // Installing verbs needs a terminal and a second launch, so only point it out here
+    if let Some(prefix) = &launch.prefix {
+        let required: Vec<String> = launch
+            .programs
+            .iter()
+            .flat_map(|program| program.verbs.iter().cloned())
+            .collect();
+        let missing = winetricks::missing_verbs(prefix, &required);
+        if !missing.is_empty() {
+            println!(
+                "Warning: {} isn't installed in the game's prefix, install it from the Protonic window",
+                missing.join(", ")
+            );
+        }
+    }
+
+    let notifications = crate::notifications_allowed(&cfg, &app_id);
+    if cfg.auto_configure {
+        let template = crate::launch_template(&cfg, &app_id);
+        if crate::launch_options_pending(&app_id, &template) && crate::steam::is_running() {
+            println!(
+                "Warning: Steam is running and writes its config back when it exits, which can undo \
+                 the launch options change. Close Steam first to keep it"
+            );
+        }
+        match crate::configure_launch_options(
+            &app_id,
+            &template,
+            cfg.tunables.auto_configure_cooldown_secs,
+        ) {
+            Ok(msg) => {
+                println!("{}", msg);
+                if notifications && msg != crate::ALREADY_CONFIGURED {
+                    notify::send(&game_name, &msg, None);
+                }
+            }
+            Err(e) => {
+                println!("Warning: Could not configure launch options: {}", e);
+                if notifications {
+                    notify::send(&game_name, &format!("Launch options: {}", e), None);
+                }
+            }
+        }
+    }
+
+    let icon = crate::steam::locate()
+        .ok()
+        .and_then(|steam| store::local_icon(steam.path(), &app_id));
+    if notifications {
+        notify::send(
+            &format!("Launching {}", game_name),
+            &launch.instructions(),
+            icon.as_deref(),
+        );
+    }
+    if let Some(note) = launch.deploy_files()
+        && notifications
+    {
+        notify::send(&game_name, &note, None);
+    }
+    if !launch.has_programs() {
+        crate::launch::start_game(&app_id);
+        return 0;
+    }
+
+    let tracker = ProcessTracker::default();
+    tracker.start_reaper(|report| {
+        println!("{}", report.describe());
+    });
+    // The anti-cheat override was given up front
+    launch.start_before_game(&tracker);
+    crate::launch::start_game(&app_id);
+    let failed = Cell::new(false);
+    launch.run_schedule(&tracker, &|| true, &|_| {}, &|summary| {
+        let (Ok(message) | Err(message)) = &summary;
+        // Errors were already printed
+        match &summary {
+            Ok(_) => println!("{}", message),
+            Err(_) => failed.set(true),
+        }
+        if notifications {
+            notify::send(&game_name, message, icon.as_deref());
+        }
+    });
+
+    // Stay around while the game runs, so the recorder and refocusing keep working
+    let poll = Duration::from_millis(cfg.tunables.session_refresh_ms);
+    let mut watcher = history::SessionWatcher::default();
+    loop {
+        let running = launch.backend.is_running(&app_id);
+        // Only this game, the window records the others if it's open
+        watcher.update(if running {
+            std::slice::from_ref(&app_id)
+        } else {
+            &[]
+        });
+        if !running {
+            break;
+        }
+        thread::sleep(poll);
+    }
+    println!("Game {} exited", app_id);
+    if (cfg.kill_on_exit || cfg.stop_with_game)
+        && tracker.kill_app(&app_id, Duration::from_millis(cfg.tunables.kill_grace_ms))
+    {
+        process::run_cleanup_hooks(
+            &cfg.game_configs
+                .get(&app_id)
+                .map(|game_cfg| game_cfg.cleanup_hooks.clone())
+                .unwrap_or_default(),
+        );
+    }
+
+    if failed.get() { 1 } else { 0 }
+}
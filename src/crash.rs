@@ -0,0 +1,156 @@
+use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+use std::backtrace::Backtrace;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::panic;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Marker pointing at a crash report the user hasn't been asked about yet
+const PENDING_MARKER: &str = "pending";
+
+// Browsers and GitHub cope with long URLs, but not unlimited ones
+const MAX_ISSUE_BODY: usize = 6000;
+
+/// Where crash reports are written
+fn crash_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join("crashes"))
+}
+
+/// Install a panic hook that writes a crash report to disk before the usual panic output
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+
+        match write_report(&message, &location) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Could not write crash report: {}", e),
+        }
+        default_hook(info);
+    }));
+}
+
+fn write_report(message: &str, location: &str) -> Result<PathBuf, String> {
+    let dir = crash_dir().ok_or_else(|| "Could not find the data directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let report = format!(
+        "Protonic crash report\n\
+         Version: {}\n\
+         Time (unix): {}\n\
+         Thread: {}\n\
+         Message: {}\n\
+         Location: {}\n\
+         Config hash: {}\n\
+         \n\
+         Backtrace:\n{}\n\
+         \n\
+         Recent log, newest first:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        timestamp,
+        thread::current().name().unwrap_or("unnamed"),
+        message,
+        location,
+        config_hash(),
+        Backtrace::force_capture(),
+        crate::logs::recent(),
+    );
+
+    let path = dir.join(format!("crash-{}.txt", timestamp));
+    fs::write(&path, report).map_err(|e| e.to_string())?;
+    fs::write(dir.join(PENDING_MARKER), path.to_string_lossy().as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Hash of the config file, so reports from identical setups can be matched
+/// up without including the user's paths
+fn config_hash() -> String {
+    confy::get_configuration_file_path(crate::config_name(), None)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .map(|content| {
+            let mut hasher = DefaultHasher::new();
+            content.hash(&mut hasher);
+            format!("{:016x}", hasher.finish())
+        })
+        .unwrap_or_else(|| "none".to_string())
+}
+
+/// If the last run crashed, offer to open a pre-filled GitHub issue with the report
+pub fn offer_pending_report() {
+    let Some(dir) = crash_dir() else {
+        return;
+    };
+    let marker = dir.join(PENDING_MARKER);
+    let Ok(report_path) = fs::read_to_string(&marker) else {
+        return;
+    };
+    // Only ask once per crash
+    let _ = fs::remove_file(&marker);
+    let Ok(report) = fs::read_to_string(report_path.trim()) else {
+        return;
+    };
+
+    let answer = MessageDialog::new()
+        .set_level(MessageLevel::Error)
+        .set_title("Protonic crashed last time")
+        .set_description(format!(
+            "A crash report was saved to {}.\n\nOpen a GitHub issue with the report attached?",
+            report_path.trim()
+        ))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    if answer != MessageDialogResult::Yes {
+        return;
+    }
+
+    let mut body: String = report.chars().take(MAX_ISSUE_BODY).collect();
+    if body.len() < report.len() {
+        body.push_str("\n[report truncated, full file attached separately]");
+    }
+    let title = report
+        .lines()
+        .find_map(|l| l.strip_prefix("Message: "))
+        .unwrap_or("crash");
+    let url = format!(
+        "{}/issues/new?title={}&body={}",
+        env!("CARGO_PKG_REPOSITORY"),
+        url_encode(&format!("Crash: {}", title)),
+        url_encode(&format!("```\n{}\n```", body))
+    );
+    if let Ok(child) = crate::logs::quiet(Command::new("xdg-open").arg(url)).spawn() {
+        crate::process::reap_in_background(child);
+    }
+}
+
+/// Percent-encode everything but unreserved URL characters
+fn url_encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
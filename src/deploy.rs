@@ -0,0 +1,262 @@
+//! Companions that are files rather than programs: DLL proxies (d3d11.dll, dinput8.dll),
+//! ASI loaders and their plugins, copied into the game's folder or its prefix before the
+//! game starts. Files they replace are backed up first, and what was copied is listed in
+//! ~/.local/share/protonic/deployed/<app id>/ with its SHA-256, so it can all be taken out
+//! again, and a game update that overwrote a proxy DLL is noticed and repaired.
+
+use crate::{tools, winpath};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A file or folder copied into the game
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct FileDrop {
+    // File or folder on this computer
+    pub source: String,
+    // A Windows path in the prefix (C:\...), or a folder relative to the game's folder,
+    // empty for the game's folder itself
+    pub destination: String,
+}
+
+/// A file Protonic put in place, and where the one it replaced was backed up
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Deployed {
+    target: PathBuf,
+    backup: Option<PathBuf>,
+    // Of the file as copied, empty in lists written before hashes were kept
+    #[serde(default)]
+    sha256: String,
+}
+
+impl Deployed {
+    /// Whether the file is still as Protonic left it
+    fn intact(&self) -> bool {
+        self.sha256.is_empty() || tools::sha256(&self.target).is_ok_and(|hash| hash == self.sha256)
+    }
+}
+
+fn deployed_dir(app_id: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join("deployed").join(app_id))
+}
+
+fn load(app_id: &str) -> Vec<Deployed> {
+    deployed_dir(app_id)
+        .and_then(|dir| fs::read_to_string(dir.join("manifest.json")).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(app_id: &str, deployed: &[Deployed]) -> Result<(), String> {
+    let dir =
+        deployed_dir(app_id).ok_or_else(|| "Could not find the data directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let json = serde_json::to_string_pretty(deployed).map_err(|e| e.to_string())?;
+    fs::write(dir.join("manifest.json"), json).map_err(|e| e.to_string())
+}
+
+/// How many files are deployed for the game now
+pub fn deployed_count(app_id: &str) -> usize {
+    load(app_id).len()
+}
+
+/// Deployed files that are missing or were changed since, e.g. by a game update
+pub fn changed(app_id: &str) -> Vec<PathBuf> {
+    load(app_id)
+        .into_iter()
+        .filter(|entry| !entry.intact())
+        .map(|entry| entry.target)
+        .collect()
+}
+
+/// The folder a drop's destination points at
+fn target_dir(
+    destination: &str,
+    prefix: Option<&Path>,
+    install_dir: Option<&Path>,
+) -> Result<PathBuf, String> {
+    let destination = destination.trim();
+    if destination
+        .split(['\\', '/'])
+        .any(|component| component == "..")
+    {
+        return Err(format!("'{}' can't go up with ..", destination));
+    }
+    if let Some(prefix) = prefix
+        && let Some(dir) = winpath::to_host(destination, prefix)
+    {
+        return Ok(dir);
+    }
+    if destination.starts_with('/') {
+        return Ok(PathBuf::from(destination));
+    }
+    if destination.chars().nth(1) == Some(':') {
+        return Err(format!(
+            "'{}' isn't in the game's prefix, start the game once so it's created",
+            destination
+        ));
+    }
+    let install_dir = install_dir
+        .ok_or_else(|| "The game isn't installed, so its folder can't be found".to_string())?;
+    Ok(destination
+        .split(['\\', '/'])
+        .filter(|component| !component.is_empty())
+        .fold(install_dir.to_path_buf(), |dir, component| {
+            dir.join(component)
+        }))
+}
+
+/// Every file under `source` with where it goes under `target`
+fn files_to_copy(source: &Path, target: &Path, files: &mut Vec<(PathBuf, PathBuf)>) {
+    if source.is_dir() {
+        let Ok(entries) = fs::read_dir(source) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            files_to_copy(&entry.path(), &target.join(entry.file_name()), files);
+        }
+    } else {
+        files.push((source.to_path_buf(), target.to_path_buf()));
+    }
+}
+
+fn same_contents(a: &Path, b: &Path) -> bool {
+    match (fs::read(a), fs::read(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Copy the game's files into place, backing up whatever they replace. Files already
+/// in place are left alone. Returns how many were copied.
+pub fn deploy(
+    app_id: &str,
+    drops: &[FileDrop],
+    prefix: Option<&Path>,
+    install_dir: Option<&Path>,
+) -> Result<usize, String> {
+    let backup_dir = deployed_dir(app_id)
+        .ok_or_else(|| "Could not find the data directory".to_string())?
+        .join("backup");
+    let mut deployed = load(app_id);
+    let mut files = Vec::new();
+    for drop in drops {
+        let source = Path::new(&drop.source);
+        let name = source
+            .components()
+            .next_back()
+            .and_then(|component| match component {
+                Component::Normal(name) => Some(name.to_owned()),
+                _ => None,
+            })
+            .ok_or_else(|| format!("'{}' is not a file or folder", drop.source))?;
+        if !source.exists() {
+            return Err(format!("{} doesn't exist", drop.source));
+        }
+        let target = target_dir(&drop.destination, prefix, install_dir)?.join(name);
+        files_to_copy(source, &target, &mut files);
+    }
+
+    let mut copied = 0;
+    let mut result = Ok(());
+    for (source, target) in files {
+        if same_contents(&source, &target) {
+            continue;
+        }
+        let index = deployed.iter().position(|entry| entry.target == target);
+        // Our earlier copy, unless something (a game update) has replaced it since
+        let ours = index.is_some_and(|index| deployed[index].intact());
+        let step = (|| {
+            // Back up what the game came with, but not our own earlier copy
+            let backup = if target.exists() && !ours {
+                fs::create_dir_all(&backup_dir).map_err(|e| e.to_string())?;
+                let file_name = target.file_name().unwrap_or_default().to_string_lossy();
+                let backup = backup_dir.join(format!(
+                    "{}-{}-{}",
+                    crate::journal::now(),
+                    deployed.len(),
+                    file_name
+                ));
+                fs::copy(&target, &backup)
+                    .map_err(|e| format!("Failed to back up {}: {}", target.display(), e))?;
+                Some(backup)
+            } else {
+                None
+            };
+            if let Some(dir) = target.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            fs::copy(&source, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+            Ok::<_, String>((backup, tools::sha256(&target)?))
+        })();
+        match step {
+            Ok((backup, sha256)) => {
+                match index {
+                    Some(index) => {
+                        let entry = &mut deployed[index];
+                        // The file the update put there is what removing should restore
+                        if let Some(old) = backup.and_then(|backup| entry.backup.replace(backup)) {
+                            let _ = fs::remove_file(old);
+                        }
+                        entry.sha256 = sha256;
+                    }
+                    None => deployed.push(Deployed {
+                        target: target.clone(),
+                        backup,
+                        sha256,
+                    }),
+                }
+                println!("Copied {} to {}", source.display(), target.display());
+                copied += 1;
+            }
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    // Whatever was copied before a failure can still be removed
+    save(app_id, &deployed)?;
+    result.map(|()| copied)
+}
+
+/// Take the game's deployed files out again and put back the ones they replaced.
+/// Returns how many were removed.
+pub fn remove(app_id: &str) -> Result<usize, String> {
+    let mut remaining = Vec::new();
+    let mut removed = 0;
+    let mut first_error = None;
+    for entry in load(app_id) {
+        let restored = match &entry.backup {
+            Some(backup) => fs::copy(backup, &entry.target).map(|_| ()),
+            None => match fs::remove_file(&entry.target) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        };
+        match restored {
+            Ok(()) => {
+                if let Some(backup) = &entry.backup {
+                    let _ = fs::remove_file(backup);
+                }
+                removed += 1;
+            }
+            Err(e) => {
+                first_error.get_or_insert(format!(
+                    "Failed to remove {}: {}",
+                    entry.target.display(),
+                    e
+                ));
+                remaining.push(entry);
+            }
+        }
+    }
+    save(app_id, &remaining)?;
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(removed),
+    }
+}
@@ -0,0 +1,97 @@
+use crate::keyboard::Keyboard;
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// The kind of graphical session Protonic is running in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayServer {
+    X11,
+    Wayland { xwayland: bool },
+    Unknown,
+}
+
+/// Work out the display server from the usual session environment variables
+pub fn detect_display_server() -> DisplayServer {
+    let session_type = env::var("XDG_SESSION_TYPE").unwrap_or_default();
+    let has_wayland = env::var_os("WAYLAND_DISPLAY").is_some() || session_type == "wayland";
+    let has_x11 = env::var_os("DISPLAY").is_some();
+
+    if has_wayland {
+        DisplayServer::Wayland { xwayland: has_x11 }
+    } else if has_x11 || session_type == "x11" {
+        DisplayServer::X11
+    } else {
+        DisplayServer::Unknown
+    }
+}
+
+/// Put text on the clipboard: with wl-copy under Wayland, else with xclip or xsel
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let wayland = matches!(detect_display_server(), DisplayServer::Wayland { .. });
+    let candidates: [(&str, &[&str]); 3] = [
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+    let (program, args) = candidates
+        .iter()
+        .filter(|(program, _)| wayland || *program != "wl-copy")
+        .find(|(program, _)| crate::command_in_path(program))
+        .ok_or_else(|| {
+            if wayland {
+                "Install wl-clipboard to copy to the clipboard".to_string()
+            } else {
+                "Install xclip or xsel to copy to the clipboard".to_string()
+            }
+        })?;
+    let mut child = Command::new(program)
+        .args(*args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    // Closing stdin tells it the text is complete; it keeps serving the clipboard itself
+    let written = child
+        .stdin
+        .take()
+        .map(|mut stdin| stdin.write_all(text.as_bytes()));
+    crate::process::reap_in_background(child);
+    match written {
+        Some(Err(e)) => Err(format!("Failed to write to {}: {}", program, e)),
+        _ => Ok(()),
+    }
+}
+
+/// Whether the keyboard can be polled at all: through X11, or under Wayland through
+/// /dev/input
+pub fn can_query_keys() -> bool {
+    Keyboard::open().is_some()
+}
+
+/// Check whether global key capture will work in this session.
+/// Returns a message describing the limitation if it won't (or only partly will).
+pub fn hotkey_limitation(server: DisplayServer) -> Option<String> {
+    let keyboard = Keyboard::open();
+    if keyboard.as_ref().is_some_and(Keyboard::is_global) {
+        return None;
+    }
+
+    match server {
+        DisplayServer::Wayland { xwayland: true } if keyboard.is_some() => Some(
+            "Wayland session detected: the hotkey is only seen while an X11/XWayland window (like most \
+             Proton games) has focus. If nothing happens, click into the game window and try again, \
+             or allow reading /dev/input so it works everywhere."
+                .to_string(),
+        ),
+        DisplayServer::Wayland { .. } => Some(
+            "Wayland session without XWayland: the hotkey can only be captured with read access \
+             to /dev/input, so your executable(s) won't launch until that's set up."
+                .to_string(),
+        ),
+        _ => Some(
+            "No X11 display available: the hotkey can't be captured in this session, \
+             so your executable(s) won't launch."
+                .to_string(),
+        ),
+    }
+}
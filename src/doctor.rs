@@ -0,0 +1,118 @@
+use crate::{audio, display};
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+enum Outcome {
+    Pass(String),
+    Warn(String),
+    Fail(String),
+}
+
+type Check = fn() -> Outcome;
+
+/// `protonic doctor`: run every diagnostic non-interactively, print the results
+/// and return the process exit code (non-zero if anything failed)
+pub fn run() -> i32 {
+    let checks: [(&str, Check); 5] = [
+        ("Steam installation", check_steam),
+        ("Steam userdata", check_userdata),
+        ("protonhax", check_runner),
+        ("Hotkey capture", check_hotkeys),
+        ("Audio output", check_audio),
+    ];
+
+    let mut failed = 0;
+    for (name, check) in checks {
+        let (label, detail) = match check() {
+            Outcome::Pass(detail) => ("ok", detail),
+            Outcome::Warn(detail) => ("warn", detail),
+            Outcome::Fail(detail) => {
+                failed += 1;
+                ("FAIL", detail)
+            }
+        };
+        println!("[{:>4}] {}: {}", label, name, detail);
+    }
+
+    if failed == 0 {
+        println!("All checks passed");
+        0
+    } else {
+        println!("{} check(s) failed", failed);
+        1
+    }
+}
+
+fn check_steam() -> Outcome {
+    let installations = crate::steam::installations();
+    match crate::steam::locate() {
+        // Which one is used is a setting, and the doctor runs without the config
+        Ok(_) if installations.len() > 1 => Outcome::Pass(format!(
+            "found {} (pick one in the settings)",
+            installations
+                .iter()
+                .map(|installation| installation.label())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        Ok(steam_dir) => Outcome::Pass(format!("found at {}", steam_dir.path().display())),
+        Err(e) => Outcome::Fail(format!("Steam could not be located ({})", e)),
+    }
+}
+
+fn check_userdata() -> Outcome {
+    let Some(localconfig) = crate::get_localconfig_path() else {
+        return Outcome::Fail("localconfig.vdf not found in Steam's userdata".to_string());
+    };
+    if is_writable(&localconfig) {
+        Outcome::Pass(format!("{} is writable", localconfig.display()))
+    } else {
+        Outcome::Fail(format!("{} is not writable", localconfig.display()))
+    }
+}
+
+fn check_runner() -> Outcome {
+    if crate::command_in_path("protonhax") {
+        Outcome::Pass("found in PATH".to_string())
+    } else if crate::proton::any_installed() {
+        // Games set to the native backend run programs with their own Proton instead
+        Outcome::Warn(format!(
+            "not found in PATH, only games using the native backend can run programs. {}",
+            crate::protonhax::package_hint()
+        ))
+    } else {
+        Outcome::Fail(format!(
+            "not found in PATH and no Proton installed for the native backend. \
+             Protonic's window can install it. {}",
+            crate::protonhax::package_hint()
+        ))
+    }
+}
+
+fn check_hotkeys() -> Outcome {
+    let server = display::detect_display_server();
+    if !display::can_query_keys() {
+        return Outcome::Fail(format!("keyboard can't be read ({:?} session)", server));
+    }
+    match display::hotkey_limitation(server) {
+        Some(warning) => Outcome::Warn(warning),
+        None => Outcome::Pass(format!("usable ({:?} session)", server)),
+    }
+}
+
+fn check_audio() -> Outcome {
+    if audio::output_device_available() {
+        Outcome::Pass("default output device available".to_string())
+    } else {
+        Outcome::Fail("no audio output device could be opened".to_string())
+    }
+}
+
+fn is_writable(path: &Path) -> bool {
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: access() only inspects the path
+    unsafe { libc::access(c_path.as_ptr(), libc::W_OK) == 0 }
+}
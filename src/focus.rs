@@ -0,0 +1,48 @@
+//! Giving focus back to the game after companion programs steal it, through xdotool
+//! (X11/XWayland windows only, which covers Proton games).
+
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// ID of the window that has focus right now
+pub fn active_window() -> Option<String> {
+    let output = Command::new("xdotool")
+        .arg("getactivewindow")
+        .output()
+        .ok()?;
+    let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !id.is_empty()).then_some(id)
+}
+
+fn activate(window: &str) -> bool {
+    Command::new("xdotool")
+        .arg("windowactivate")
+        .arg(window)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Keep `window` focused for `duration` (tools usually show their window within a few
+/// seconds): whenever another window (a trainer, an overlay's settings...) takes
+/// focus, hand it back to the game
+pub fn keep_focused(window: String, duration: Duration) {
+    thread::spawn(move || {
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline {
+            match active_window() {
+                Some(active) if active != window => {
+                    println!("Window {} took focus, returning it to the game", active);
+                    if !activate(&window) {
+                        println!("Warning: Could not refocus the game window");
+                        return;
+                    }
+                }
+                _ => {}
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
@@ -0,0 +1,128 @@
+//! A controller button (or combination like "Select+RB") that starts a game's programs
+//! alongside the keyboard hotkey, for playing from the couch. Buttons are named after an
+//! Xbox pad; gilrs maps other controllers onto the same layout.
+
+use gilrs::{Button, Gilrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Capturing gives up after this long without a button press
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+const CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// Buttons a trigger can use, in the order a combination is written: the ones usually held
+// as modifiers first
+const BUTTONS: [(&str, Button); 17] = [
+    ("Select", Button::Select),
+    ("Start", Button::Start),
+    ("Guide", Button::Mode),
+    ("LB", Button::LeftTrigger),
+    ("RB", Button::RightTrigger),
+    ("LT", Button::LeftTrigger2),
+    ("RT", Button::RightTrigger2),
+    ("LS", Button::LeftThumb),
+    ("RS", Button::RightThumb),
+    ("A", Button::South),
+    ("B", Button::East),
+    ("X", Button::West),
+    ("Y", Button::North),
+    ("Up", Button::DPadUp),
+    ("Down", Button::DPadDown),
+    ("Left", Button::DPadLeft),
+    ("Right", Button::DPadRight),
+];
+
+// Held on their own these are too easy to press in-game, so a trigger needs another button
+const MODIFIERS: [Button; 3] = [Button::Select, Button::Start, Button::Mode];
+
+/// Buttons of a trigger like "Select+RB"; empty when the game has none
+pub fn parse(text: &str) -> Result<Vec<Button>, String> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    text.split('+')
+        .map(|part| {
+            BUTTONS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(part.trim()))
+                .map(|(_, button)| *button)
+                .ok_or_else(|| format!("Unknown controller button '{}'", part.trim()))
+        })
+        .collect()
+}
+
+/// Name of a button combination, in BUTTONS order
+pub fn format(buttons: &[Button]) -> String {
+    BUTTONS
+        .iter()
+        .filter(|(_, button)| buttons.contains(button))
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Whether every button of the combination is held
+pub fn is_pressed(held: &[Button], combo: &[Button]) -> bool {
+    !combo.is_empty() && combo.iter().all(|button| held.contains(button))
+}
+
+/// Every connected controller, read together
+pub struct Gamepads {
+    gilrs: Gilrs,
+}
+
+impl Gamepads {
+    /// None if controllers can't be read in this session
+    pub fn open() -> Option<Gamepads> {
+        Gilrs::new().ok().map(|gilrs| Gamepads { gilrs })
+    }
+
+    /// Buttons held right now on any controller
+    pub fn held(&mut self) -> Vec<Button> {
+        // gilrs only updates the controllers' state while its events are read
+        while self.gilrs.next_event().is_some() {}
+        let mut held = Vec::new();
+        for (_, gamepad) in self.gilrs.gamepads() {
+            for (_, button) in &BUTTONS {
+                if gamepad.is_pressed(*button) && !held.contains(button) {
+                    held.push(*button);
+                }
+            }
+        }
+        held
+    }
+}
+
+/// Wait for the user to press a button combination and let go of it. Returns None on
+/// timeout, or when only Select, Start or Guide was pressed.
+pub fn capture(gamepads: &mut Gamepads) -> Option<Vec<Button>> {
+    let deadline = Instant::now() + CAPTURE_TIMEOUT;
+    while !gamepads.held().is_empty() {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(CAPTURE_POLL_INTERVAL);
+    }
+
+    let mut combo: Vec<Button> = Vec::new();
+    loop {
+        let held = gamepads.held();
+        if held.is_empty() && !combo.is_empty() {
+            break;
+        }
+        if combo.is_empty() && Instant::now() >= deadline {
+            return None;
+        }
+        for button in held {
+            if !combo.contains(&button) {
+                combo.push(button);
+            }
+        }
+        thread::sleep(CAPTURE_POLL_INTERVAL);
+    }
+
+    combo
+        .iter()
+        .any(|button| !MODIFIERS.contains(button))
+        .then_some(combo)
+}
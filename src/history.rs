@@ -0,0 +1,258 @@
+//! Opt-in usage history: when games ran, which programs Protonic started in them and
+//! which of those crashed, one JSON object per line. It never leaves the machine; the
+//! usage dashboard is computed from this file alone.
+
+use crate::{journal, protonhax};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, UNIX_EPOCH};
+
+const HISTORY_FILE: &str = "sessions.jsonl";
+const WEEK_SECS: u64 = 7 * 24 * 3600;
+// How many weeks the dashboard goes back
+const WEEKS: usize = 8;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    GameStarted,
+    GameExited,
+    ProgramStarted {
+        name: String,
+    },
+    // Exited with an error code or was killed by a signal it didn't get from us
+    ProgramCrashed {
+        name: String,
+    },
+    // What the user wrote down when a session ended, and how long that session was
+    SessionNote {
+        note: String,
+        session_secs: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub app_id: String,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+// Off until the user turns the history on
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join(HISTORY_FILE))
+}
+
+fn append(entry: &Entry) -> Result<(), String> {
+    let path = history_path().ok_or_else(|| "Could not find the data directory".to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+fn record_at(timestamp: u64, app_id: &str, event: Event) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let entry = Entry {
+        timestamp,
+        app_id: app_id.to_string(),
+        event,
+    };
+    if let Err(e) = append(&entry) {
+        println!("Warning: Could not write the usage history: {}", e);
+    }
+}
+
+/// Add an event to the history, if it's turned on
+pub fn record(app_id: &str, event: Event) {
+    record_at(journal::now(), app_id, event);
+}
+
+/// Save a session note. Notes are written even with the history off, since the user
+/// asked for them.
+pub fn add_note(app_id: &str, note: &str, session: Option<Duration>) -> Result<(), String> {
+    append(&Entry {
+        timestamp: journal::now(),
+        app_id: app_id.to_string(),
+        event: Event::SessionNote {
+            note: note.to_string(),
+            session_secs: session.map(|length| length.as_secs()),
+        },
+    })
+}
+
+/// A game's session notes, newest first, with when they were written
+pub fn notes(app_id: &str) -> Vec<(u64, String, Option<Duration>)> {
+    let mut notes: Vec<(u64, String, Option<Duration>)> = load()
+        .into_iter()
+        .filter(|entry| entry.app_id == app_id)
+        .filter_map(|entry| match entry.event {
+            Event::SessionNote { note, session_secs } => {
+                Some((entry.timestamp, note, session_secs.map(Duration::from_secs)))
+            }
+            _ => None,
+        })
+        .collect();
+    notes.reverse();
+    notes
+}
+
+/// Everything recorded, oldest first. Unreadable lines are skipped.
+pub fn load() -> Vec<Entry> {
+    let Some(content) = history_path().and_then(|p| fs::read_to_string(p).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Delete the history file
+pub fn clear() -> Result<(), String> {
+    let Some(path) = history_path() else {
+        return Ok(());
+    };
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(format!("Could not delete {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Turns the running games seen on each poll into GameStarted/GameExited events
+#[derive(Default)]
+pub struct SessionWatcher {
+    // App ID and when its session started
+    running: HashMap<String, u64>,
+}
+
+impl SessionWatcher {
+    /// Returns the games that exited since the last poll, with how long they ran
+    pub fn update(&mut self, active: &[String]) -> Vec<(String, Duration)> {
+        for app_id in active {
+            if self.running.contains_key(app_id) {
+                continue;
+            }
+            // The session may have been up before Protonic started; summarize() drops
+            // the repeated start if so
+            let started = protonhax::session_started(app_id)
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_secs())
+                .unwrap_or_else(journal::now);
+            record_at(started, app_id, Event::GameStarted);
+            self.running.insert(app_id.clone(), started);
+        }
+        let now = journal::now();
+        let mut exited = Vec::new();
+        self.running.retain(|app_id, started| {
+            if active.contains(app_id) {
+                return true;
+            }
+            record(app_id, Event::GameExited);
+            exited.push((
+                app_id.clone(),
+                Duration::from_secs(now.saturating_sub(*started)),
+            ));
+            false
+        });
+        exited
+    }
+}
+
+/// What the usage dashboard shows
+#[derive(Debug, Default)]
+pub struct Summary {
+    // Game sessions started per week, this week first
+    pub per_week: Vec<usize>,
+    // Over sessions Protonic saw both ends of
+    pub average_session: Option<Duration>,
+    // Program name and how often it was started (or crashed), most first
+    pub programs: Vec<(String, usize)>,
+    pub crashes: Vec<(String, usize)>,
+}
+
+fn ranked(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+pub fn summarize(entries: &[Entry], now: u64) -> Summary {
+    let mut per_week = vec![0; WEEKS];
+    let mut open: HashMap<&str, u64> = HashMap::new();
+    let mut lengths: Vec<u64> = Vec::new();
+    let mut programs: HashMap<String, usize> = HashMap::new();
+    let mut crashes: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        match &entry.event {
+            Event::GameStarted => {
+                // Seen again after a Protonic restart: same session, same start time
+                if open.insert(&entry.app_id, entry.timestamp) == Some(entry.timestamp) {
+                    continue;
+                }
+                let week = (now.saturating_sub(entry.timestamp) / WEEK_SECS) as usize;
+                if let Some(count) = per_week.get_mut(week) {
+                    *count += 1;
+                }
+            }
+            Event::GameExited => {
+                if let Some(started) = open.remove(entry.app_id.as_str()) {
+                    lengths.push(entry.timestamp.saturating_sub(started));
+                }
+            }
+            Event::ProgramStarted { name } => *programs.entry(name.clone()).or_default() += 1,
+            Event::ProgramCrashed { name } => *crashes.entry(name.clone()).or_default() += 1,
+            Event::SessionNote { .. } => {}
+        }
+    }
+
+    Summary {
+        per_week,
+        average_session: (!lengths.is_empty())
+            .then(|| Duration::from_secs(lengths.iter().sum::<u64>() / lengths.len() as u64)),
+        programs: ranked(programs),
+        crashes: ranked(crashes),
+    }
+}
+
+/// "This week", "Last week", "3 weeks ago"
+pub fn week_label(weeks_ago: usize) -> String {
+    match weeks_ago {
+        0 => "This week".to_string(),
+        1 => "Last week".to_string(),
+        n => format!("{} weeks ago", n),
+    }
+}
+
+/// Session length for the dashboard, e.g. "1 h 25 min"
+pub fn format_length(length: Duration) -> String {
+    let minutes = length.as_secs() / 60;
+    if minutes < 60 {
+        format!("{} min", minutes)
+    } else {
+        format!("{} h {} min", minutes / 60, minutes % 60)
+    }
+}
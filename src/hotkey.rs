@@ -0,0 +1,100 @@
+//! The key (or key combination) that starts a game's programs, e.g. "F1" or "LControl+F9".
+//! Keys use device_query's names, also when they're read from /dev/input (see keyboard.rs).
+
+use crate::keyboard::Keyboard;
+use device_query::Keycode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Used by games that haven't picked their own hotkey
+pub const DEFAULT: &str = "F1";
+
+// Capturing gives up after this long without a key press
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+const CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+const MODIFIERS: [Keycode; 8] = [
+    Keycode::LControl,
+    Keycode::RControl,
+    Keycode::LShift,
+    Keycode::RShift,
+    Keycode::LAlt,
+    Keycode::RAlt,
+    Keycode::LMeta,
+    Keycode::RMeta,
+];
+
+/// What a game's hotkey setting shows as, empty meaning the default
+pub fn label(text: &str) -> &str {
+    if text.trim().is_empty() {
+        DEFAULT
+    } else {
+        text.trim()
+    }
+}
+
+/// Keys of a hotkey like "LControl+F9"
+pub fn parse(text: &str) -> Result<Vec<Keycode>, String> {
+    label(text)
+        .split('+')
+        .map(|name| {
+            name.trim()
+                .parse::<Keycode>()
+                .map_err(|_| format!("Unknown key '{}'", name.trim()))
+        })
+        .collect()
+}
+
+/// Name of a key combination, modifiers first
+pub fn format(keys: &[Keycode]) -> String {
+    let mut keys = keys.to_vec();
+    keys.sort_by_key(|key| !MODIFIERS.contains(key));
+    keys.dedup();
+    keys.iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Whether every key of the combination is held
+pub fn is_pressed(held: &[Keycode], combo: &[Keycode]) -> bool {
+    !combo.is_empty() && combo.iter().all(|key| held.contains(key))
+}
+
+/// Wait for the user to press a key combination and let go of it. Returns None
+/// on Escape, on timeout, or when only modifiers were pressed.
+pub fn capture(keyboard: &mut Keyboard) -> Option<Vec<Keycode>> {
+    // Let go of whatever was held when capturing started (e.g. the mouse-click's modifiers)
+    let deadline = Instant::now() + CAPTURE_TIMEOUT;
+    while !keyboard.get_keys().is_empty() {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(CAPTURE_POLL_INTERVAL);
+    }
+
+    let mut combo: Vec<Keycode> = Vec::new();
+    loop {
+        let held = keyboard.get_keys();
+        if held.contains(&Keycode::Escape) {
+            return None;
+        }
+        if held.is_empty() && !combo.is_empty() {
+            break;
+        }
+        if combo.is_empty() && Instant::now() >= deadline {
+            return None;
+        }
+        for key in held {
+            if !combo.contains(&key) {
+                combo.push(key);
+            }
+        }
+        thread::sleep(CAPTURE_POLL_INTERVAL);
+    }
+
+    combo
+        .iter()
+        .any(|key| !MODIFIERS.contains(key))
+        .then_some(combo)
+}
@@ -0,0 +1,72 @@
+use std::env;
+use std::fs;
+use std::process::Command;
+
+const INPUT_DIR: &str = "/dev/input";
+
+/// Check whether we can read at least one event device in /dev/input
+pub fn input_devices_readable() -> bool {
+    let Ok(entries) = fs::read_dir(INPUT_DIR) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        entry.file_name().to_string_lossy().starts_with("event")
+            && fs::File::open(entry.path()).is_ok()
+    })
+}
+
+/// Check whether the current user is already in the "input" group.
+/// Group changes only apply after logging in again, so this can be true while
+/// the devices are still unreadable.
+pub fn user_in_input_group() -> bool {
+    Command::new("id")
+        .arg("-nG")
+        .output()
+        .map(|out| {
+            String::from_utf8_lossy(&out.stdout)
+                .split_whitespace()
+                .any(|group| group == "input")
+        })
+        .unwrap_or(false)
+}
+
+/// Step-by-step instructions for granting access to /dev/input manually
+pub fn setup_instructions() -> String {
+    if user_in_input_group() {
+        return "You're already in the 'input' group. Log out and back in (or reboot) \
+                for the new permissions to apply."
+            .to_string();
+    }
+    let user = env::var("USER").unwrap_or_else(|_| "$USER".to_string());
+    format!(
+        "Protonic can't read keyboard events from {}. Either run \
+         'sudo usermod -aG input {}' and log in again, or add a udev rule such as \
+         'KERNEL==\"event*\", SUBSYSTEM==\"input\", TAG+=\"uaccess\"' to \
+         /etc/udev/rules.d/70-protonic.rules.",
+        INPUT_DIR, user
+    )
+}
+
+/// One-time setup: add the current user to the "input" group through polkit (pkexec)
+pub fn grant_input_access() -> Result<String, String> {
+    let user = env::var("USER").map_err(|_| "Could not determine the current user".to_string())?;
+    if !crate::command_in_path("pkexec") {
+        return Err(
+            "pkexec (polkit) is not installed; follow the manual steps instead".to_string(),
+        );
+    }
+
+    let status = Command::new("pkexec")
+        .arg("usermod")
+        .arg("-aG")
+        .arg("input")
+        .arg(&user)
+        .status()
+        .map_err(|e| format!("Failed to run pkexec: {}", e))?;
+
+    if status.success() {
+        Ok("Added to the 'input' group. Log out and back in to finish setup.".to_string())
+    } else {
+        Err("Permission change was cancelled or failed".to_string())
+    }
+}
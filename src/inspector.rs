@@ -0,0 +1,77 @@
+use crate::VdfRow;
+use crate::vdf::Node;
+use std::collections::HashSet;
+
+/// State behind the VDF inspector window: the tree being shown and which
+/// sections are expanded. Sections are identified by their child-index path.
+#[derive(Default)]
+pub struct Inspector {
+    root: Option<Node>,
+    expanded: HashSet<Vec<usize>>,
+    // Path of every visible row, in display order, so clicks can be mapped back
+    visible: Vec<Vec<usize>>,
+}
+
+impl Inspector {
+    /// Show a new tree, with every section collapsed
+    pub fn set_root(&mut self, root: Option<Node>) {
+        self.root = root;
+        self.expanded.clear();
+    }
+
+    /// Expand or collapse the section shown at `index`
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(path) = self.visible.get(index).cloned()
+            && !self.expanded.remove(&path)
+        {
+            self.expanded.insert(path);
+        }
+    }
+
+    /// Flatten the expanded part of the tree into rows for the UI
+    pub fn rows(&mut self) -> Vec<VdfRow> {
+        let mut rows = Vec::new();
+        self.visible.clear();
+        if let Some(Node::Section(pairs)) = &self.root {
+            flatten(
+                pairs,
+                &mut Vec::new(),
+                &self.expanded,
+                &mut rows,
+                &mut self.visible,
+            );
+        }
+        rows
+    }
+}
+
+fn flatten(
+    pairs: &[(String, Node)],
+    path: &mut Vec<usize>,
+    expanded: &HashSet<Vec<usize>>,
+    rows: &mut Vec<VdfRow>,
+    visible: &mut Vec<Vec<usize>>,
+) {
+    for (index, (key, node)) in pairs.iter().enumerate() {
+        path.push(index);
+        let is_expanded = expanded.contains(path);
+        rows.push(VdfRow {
+            depth: (path.len() - 1) as i32,
+            key: key.clone().into(),
+            value: match node {
+                Node::Value(value) => value.clone().into(),
+                Node::Section(_) => Default::default(),
+            },
+            is_section: matches!(node, Node::Section(_)),
+            expanded: is_expanded,
+        });
+        visible.push(path.clone());
+
+        if let Node::Section(children) = node
+            && is_expanded
+        {
+            flatten(children, path, expanded, rows, visible);
+        }
+        path.pop();
+    }
+}
@@ -0,0 +1,79 @@
+//! Journal of every change Protonic makes to localconfig.vdf, so each one can be reviewed and undone.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const JOURNAL_FILE: &str = "localconfig-journal.jsonl";
+
+/// One LaunchOptions change
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub app_id: String,
+    /// None when the game had no LaunchOptions key before
+    pub old_value: Option<String>,
+    pub new_value: String,
+    /// What made the change, e.g. "auto-configure" or "revert"
+    pub reason: String,
+}
+
+fn journal_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join(JOURNAL_FILE))
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Append a change to the journal (one JSON object per line)
+pub fn record(entry: &Entry) -> Result<(), String> {
+    let path = journal_path().ok_or_else(|| "Could not find the data directory".to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+/// All recorded changes, oldest first. Unreadable lines are skipped.
+pub fn load() -> Vec<Entry> {
+    let Some(content) = journal_path().and_then(|p| fs::read_to_string(p).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// When Protonic last changed this game's launch options
+pub fn last_change(app_id: &str) -> Option<u64> {
+    load()
+        .iter()
+        .rev()
+        .find(|entry| entry.app_id == app_id)
+        .map(|entry| entry.timestamp)
+}
+
+/// Rough "how long ago" for the history view
+pub fn format_age(timestamp: u64) -> String {
+    let secs = now().saturating_sub(timestamp);
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", secs / 60),
+        3600..86400 => format!("{} h ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86400),
+    }
+}
@@ -0,0 +1,229 @@
+//! Which keys are held, for the hotkey. On X11 device_query asks the X server, but a
+//! Wayland compositor only shares keys with the focused window, so there the keyboards'
+//! event devices in /dev/input are polled instead (which needs read access to them, see
+//! input_access.rs). The X11 way is kept as a fallback under XWayland.
+
+use crate::display::{self, DisplayServer};
+use device_query::{DeviceQuery, DeviceState, Keycode};
+use std::fs::{self, File, OpenOptions};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::time::{Duration, Instant};
+
+const INPUT_DIR: &str = "/dev/input";
+// Keyboards plugged in after opening are picked up this often
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+// From linux/input.h and input-event-codes.h
+const EV_KEY: u64 = 0x01;
+const KEY_MAX: usize = 0x2ff;
+const KEY_A: usize = 30;
+const KEY_BYTES: usize = KEY_MAX / 8 + 1;
+// Which keys are held
+const EVIOCGKEY: u64 = eviocg(0x18, KEY_BYTES);
+// Which keys the device has
+const EVIOCGBIT_KEY: u64 = eviocg(0x20 + EV_KEY, KEY_BYTES);
+
+// Linux key codes of the keys a hotkey can use
+const KEYS: [(usize, Keycode); 93] = [
+    (1, Keycode::Escape),
+    (2, Keycode::Key1),
+    (3, Keycode::Key2),
+    (4, Keycode::Key3),
+    (5, Keycode::Key4),
+    (6, Keycode::Key5),
+    (7, Keycode::Key6),
+    (8, Keycode::Key7),
+    (9, Keycode::Key8),
+    (10, Keycode::Key9),
+    (11, Keycode::Key0),
+    (12, Keycode::Minus),
+    (13, Keycode::Equal),
+    (14, Keycode::Backspace),
+    (15, Keycode::Tab),
+    (16, Keycode::Q),
+    (17, Keycode::W),
+    (18, Keycode::E),
+    (19, Keycode::R),
+    (20, Keycode::T),
+    (21, Keycode::Y),
+    (22, Keycode::U),
+    (23, Keycode::I),
+    (24, Keycode::O),
+    (25, Keycode::P),
+    (26, Keycode::LeftBracket),
+    (27, Keycode::RightBracket),
+    (28, Keycode::Enter),
+    (29, Keycode::LControl),
+    (30, Keycode::A),
+    (31, Keycode::S),
+    (32, Keycode::D),
+    (33, Keycode::F),
+    (34, Keycode::G),
+    (35, Keycode::H),
+    (36, Keycode::J),
+    (37, Keycode::K),
+    (38, Keycode::L),
+    (39, Keycode::Semicolon),
+    (40, Keycode::Apostrophe),
+    (41, Keycode::Grave),
+    (42, Keycode::LShift),
+    (43, Keycode::BackSlash),
+    (44, Keycode::Z),
+    (45, Keycode::X),
+    (46, Keycode::C),
+    (47, Keycode::V),
+    (48, Keycode::B),
+    (49, Keycode::N),
+    (50, Keycode::M),
+    (51, Keycode::Comma),
+    (52, Keycode::Dot),
+    (53, Keycode::Slash),
+    (54, Keycode::RShift),
+    (56, Keycode::LAlt),
+    (57, Keycode::Space),
+    (58, Keycode::CapsLock),
+    (59, Keycode::F1),
+    (60, Keycode::F2),
+    (61, Keycode::F3),
+    (62, Keycode::F4),
+    (63, Keycode::F5),
+    (64, Keycode::F6),
+    (65, Keycode::F7),
+    (66, Keycode::F8),
+    (67, Keycode::F9),
+    (68, Keycode::F10),
+    (71, Keycode::Numpad7),
+    (72, Keycode::Numpad8),
+    (73, Keycode::Numpad9),
+    (75, Keycode::Numpad4),
+    (76, Keycode::Numpad5),
+    (77, Keycode::Numpad6),
+    (79, Keycode::Numpad1),
+    (80, Keycode::Numpad2),
+    (81, Keycode::Numpad3),
+    (82, Keycode::Numpad0),
+    (87, Keycode::F11),
+    (88, Keycode::F12),
+    (97, Keycode::RControl),
+    (100, Keycode::RAlt),
+    (102, Keycode::Home),
+    (103, Keycode::Up),
+    (104, Keycode::PageUp),
+    (105, Keycode::Left),
+    (106, Keycode::Right),
+    (107, Keycode::End),
+    (108, Keycode::Down),
+    (109, Keycode::PageDown),
+    (110, Keycode::Insert),
+    (111, Keycode::Delete),
+    (125, Keycode::LMeta),
+    (126, Keycode::RMeta),
+];
+
+/// _IOC(_IOC_READ, 'E', nr, size)
+const fn eviocg(nr: u64, size: usize) -> u64 {
+    (2 << 30) | ((size as u64) << 16) | ((b'E' as u64) << 8) | nr
+}
+
+/// Read one of the device's key bitmaps
+fn key_bits(device: &File, request: u64) -> Option<[u8; KEY_BYTES]> {
+    let mut bits = [0u8; KEY_BYTES];
+    // SAFETY: the ioctl writes at most KEY_BYTES (encoded in the request) into bits
+    let result = unsafe { libc::ioctl(device.as_raw_fd(), request as _, bits.as_mut_ptr()) };
+    (result >= 0).then_some(bits)
+}
+
+fn is_set(bits: &[u8; KEY_BYTES], code: usize) -> bool {
+    bits[code / 8] & (1 << (code % 8)) != 0
+}
+
+/// The event devices that have letter keys, opened without blocking
+fn open_keyboards() -> Vec<File> {
+    let Ok(entries) = fs::read_dir(INPUT_DIR) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+        .filter_map(|entry| {
+            OpenOptions::new()
+                .read(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open(entry.path())
+                .ok()
+        })
+        .filter(|device| key_bits(device, EVIOCGBIT_KEY).is_some_and(|has| is_set(&has, KEY_A)))
+        .collect()
+}
+
+/// Keyboards read straight from /dev/input
+pub struct Evdev {
+    devices: Vec<File>,
+    scanned: Instant,
+}
+
+impl Evdev {
+    fn open() -> Option<Evdev> {
+        let devices = open_keyboards();
+        (!devices.is_empty()).then(|| Evdev {
+            devices,
+            scanned: Instant::now(),
+        })
+    }
+
+    fn get_keys(&mut self) -> Vec<Keycode> {
+        if self.scanned.elapsed() >= RESCAN_INTERVAL {
+            self.devices = open_keyboards();
+            self.scanned = Instant::now();
+        }
+        let mut held = Vec::new();
+        for device in &self.devices {
+            let Some(state) = key_bits(device, EVIOCGKEY) else {
+                continue;
+            };
+            for (code, key) in &KEYS {
+                if is_set(&state, *code) && !held.contains(key) {
+                    held.push(*key);
+                }
+            }
+        }
+        held
+    }
+}
+
+pub enum Keyboard {
+    X11(DeviceState),
+    Evdev(Evdev),
+}
+
+impl Keyboard {
+    /// The way to read keys that works in this session, None if there is none.
+    /// Under Wayland that's /dev/input when it's readable, under X11 always the X server.
+    pub fn open() -> Option<Keyboard> {
+        if matches!(
+            display::detect_display_server(),
+            DisplayServer::Wayland { .. } | DisplayServer::Unknown
+        ) && let Some(evdev) = Evdev::open()
+        {
+            return Some(Keyboard::Evdev(evdev));
+        }
+        DeviceState::checked_new().map(Keyboard::X11)
+    }
+
+    /// Keys held right now
+    pub fn get_keys(&mut self) -> Vec<Keycode> {
+        match self {
+            Keyboard::X11(device_state) => device_state.get_keys(),
+            Keyboard::Evdev(evdev) => evdev.get_keys(),
+        }
+    }
+
+    /// Whether keys are seen whatever window has focus
+    pub fn is_global(&self) -> bool {
+        match self {
+            Keyboard::X11(_) => display::detect_display_server() == DisplayServer::X11,
+            Keyboard::Evdev(_) => true,
+        }
+    }
+}
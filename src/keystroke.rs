@@ -0,0 +1,138 @@
+//! Sending keystrokes and text to the focused window, through xdotool on X11/XWayland
+//! or ydotool on Wayland.
+
+use crate::display::{self, DisplayServer};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Xdotool,
+    Ydotool,
+}
+
+/// Pick the tool that can reach the focused window. Proton games run under XWayland,
+/// so xdotool is preferred whenever there's an X display.
+fn backend() -> Result<Backend, String> {
+    let has_xdotool = crate::command_in_path("xdotool");
+    let has_ydotool = crate::command_in_path("ydotool");
+    match display::detect_display_server() {
+        DisplayServer::X11 | DisplayServer::Wayland { xwayland: true } if has_xdotool => {
+            Ok(Backend::Xdotool)
+        }
+        DisplayServer::Wayland { .. } if has_ydotool => Ok(Backend::Ydotool),
+        DisplayServer::X11 => Err("Sending keys needs xdotool".to_string()),
+        DisplayServer::Wayland { .. } => {
+            Err("Sending keys needs xdotool (XWayland) or ydotool".to_string())
+        }
+        DisplayServer::Unknown => Err("No display to send keys to".to_string()),
+    }
+}
+
+/// Linux input event code for an xdotool-style key name, for ydotool
+fn key_code(name: &str) -> Option<u16> {
+    let lower = name.to_ascii_lowercase();
+    let code = match lower.as_str() {
+        "escape" | "esc" => 1,
+        "minus" => 12,
+        "equal" => 13,
+        "backspace" => 14,
+        "tab" => 15,
+        "return" | "enter" => 28,
+        "ctrl" | "control" | "control_l" => 29,
+        "shift" | "shift_l" => 42,
+        "alt" | "alt_l" => 56,
+        "space" => 57,
+        "super" | "super_l" | "meta" => 125,
+        "home" => 102,
+        "up" => 103,
+        "prior" | "page_up" => 104,
+        "left" => 105,
+        "right" => 106,
+        "end" => 107,
+        "down" => 108,
+        "next" | "page_down" => 109,
+        "insert" => 110,
+        "delete" => 111,
+        "f11" => 87,
+        "f12" => 88,
+        _ => {
+            if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u16>().ok())
+                && (1..=10).contains(&number)
+            {
+                return Some(58 + number);
+            }
+            let mut chars = lower.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            // Rows of a US keyboard, with the code of their first key
+            let rows = [
+                ("1234567890", 2),
+                ("qwertyuiop", 16),
+                ("asdfghjkl", 30),
+                ("zxcvbnm", 44),
+            ];
+            return rows
+                .iter()
+                .find_map(|(row, first)| row.find(c).map(|index| first + index as u16));
+        }
+    };
+    Some(code)
+}
+
+/// Press a key combination like "Return" or "ctrl+shift+s"
+pub fn send_keys(combo: &str) -> Result<(), String> {
+    let mut command = match backend()? {
+        Backend::Xdotool => {
+            let mut command = Command::new("xdotool");
+            command.arg("key").arg("--clearmodifiers").arg(combo);
+            command
+        }
+        Backend::Ydotool => {
+            // ydotool wants raw key codes: press everything in order, release in reverse
+            let codes = combo
+                .split('+')
+                .map(|name| key_code(name.trim()).ok_or_else(|| format!("Unknown key '{}'", name)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut command = Command::new("ydotool");
+            command.arg("key");
+            command.args(codes.iter().map(|code| format!("{}:1", code)));
+            command.args(codes.iter().rev().map(|code| format!("{}:0", code)));
+            command
+        }
+    };
+    run(&mut command)
+}
+
+/// Type text into the focused window
+pub fn type_text(text: &str) -> Result<(), String> {
+    let mut command = match backend()? {
+        Backend::Xdotool => {
+            let mut command = Command::new("xdotool");
+            command
+                .arg("type")
+                .arg("--clearmodifiers")
+                .arg("--")
+                .arg(text);
+            command
+        }
+        Backend::Ydotool => {
+            let mut command = Command::new("ydotool");
+            command.arg("type").arg("--").arg(text);
+            command
+        }
+    };
+    run(&mut command)
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .status()
+        .map_err(|e| format!("Could not run {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} failed ({})", program, status))
+    }
+}
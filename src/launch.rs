@@ -0,0 +1,507 @@
+//! Starting a game and its programs, shared by the window's Launch button and
+//! `protonic launch`. Each program has a stage: before the game (through the game's
+//! Proton, as there's no session yet), on the hotkey (or the game's session, for
+//! auto-launch) together with the recorder or recipe, or a number of seconds after the
+//! game's session appears.
+
+use crate::anticheat::{self, AntiCheat};
+use crate::backend::Injector;
+use crate::gamepad::{self, Gamepads};
+use crate::keyboard::Keyboard;
+use crate::process::ProcessTracker;
+use crate::winpath;
+use crate::{AppConfig, ExeEntry, InstalledGame, exe_display_name, focus, hotkey, join_args};
+use crate::{
+    backend, deploy, process, recipe, recorder, registry, remap, shortcuts, steam, tools, tunables,
+};
+use device_query::Keycode;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// When a program starts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    BeforeGame,
+    #[default]
+    OnTrigger,
+    // The program's start_after_secs after the game's session appears
+    AfterStart,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 3] = [Stage::BeforeGame, Stage::OnTrigger, Stage::AfterStart];
+
+    /// Name shown in the program list
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::BeforeGame => "Before game",
+            Stage::OnTrigger => "On hotkey",
+            Stage::AfterStart => "After game starts",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Stage {
+        Stage::ALL
+            .into_iter()
+            .find(|stage| stage.label() == label)
+            .unwrap_or_default()
+    }
+}
+
+/// What to start for a game, read from its config with tool references resolved
+pub struct Launch {
+    pub app_id: String,
+    pub game_name: String,
+    pub programs: Vec<ExeEntry>,
+    pub recipe: Vec<recipe::GuardedStep>,
+    pub registry: Vec<registry::Tweak>,
+    pub files: Vec<deploy::FileDrop>,
+    pub hotkey: Vec<Keycode>,
+    pub hotkey_name: String,
+    // Controller buttons that also start the programs, empty for none
+    pub gamepad: Vec<gilrs::Button>,
+    // Start once the game's session has been up this long, None to only wait for the hotkey
+    pub auto_launch: Option<Duration>,
+    pub refocus: bool,
+    pub recorder: recorder::Recorder,
+    pub backend: backend::Backend,
+    pub program_delay: Duration,
+    pub prefix: Option<PathBuf>,
+    pub install_dir: Option<PathBuf>,
+    // Programs only start in the game's session once the user has agreed to the risk
+    pub anti_cheat: Option<AntiCheat>,
+    pub tunables: tunables::Tunables,
+}
+
+impl Launch {
+    /// With `default_program_set`, runs the game's default program set rather than the one
+    /// last picked in the window
+    pub fn prepare(
+        cfg: &AppConfig,
+        app_id: &str,
+        game_name: &str,
+        game: Option<&InstalledGame>,
+        default_program_set: bool,
+    ) -> Result<Launch, String> {
+        let mut game_cfg = cfg.game_configs.get(app_id).cloned().unwrap_or_default();
+        if default_program_set {
+            game_cfg.use_default_program_set();
+        }
+        if game_cfg.programs.is_empty() && game_cfg.recipe.is_empty() && game_cfg.files.is_empty() {
+            return Err("No executable selected!".to_string());
+        }
+        let prefix = game.map(|game| crate::prefix_path(&game.library, app_id));
+
+        // Programs from the tool library are stored as "tool:<name>", paths may come from
+        // another computer, and paths inside the prefix may have changed case since they
+        // were picked
+        let find = |path: &str| locate(&remap::apply(&cfg.path_remaps, path), prefix.as_deref());
+        let programs = crate::resolve_programs(&cfg.tools, &game_cfg.programs)?
+            .into_iter()
+            .map(|program| ExeEntry {
+                path: find(&program.path),
+                working_dir: program
+                    .working_dir
+                    .as_ref()
+                    .map(|dir| PathBuf::from(find(&dir.to_string_lossy()))),
+                ..program
+            })
+            .collect();
+        let mut recipe = game_cfg.recipe;
+        recipe::resolve_programs(&mut recipe, &|path: &str| {
+            tools::resolve_program(&cfg.tools, path).map(|path| find(&path))
+        })?;
+
+        let hotkey = match hotkey::parse(&game_cfg.hotkey) {
+            Ok(keys) => keys,
+            Err(e) => {
+                println!("Warning: {}, using {} as the hotkey", e, hotkey::DEFAULT);
+                hotkey::parse(hotkey::DEFAULT).unwrap_or_default()
+            }
+        };
+
+        let gamepad = gamepad::parse(&game_cfg.gamepad_trigger).unwrap_or_else(|e| {
+            println!("Warning: {}, only the hotkey will start the programs", e);
+            Vec::new()
+        });
+
+        Ok(Launch {
+            app_id: app_id.to_string(),
+            game_name: game_name.to_string(),
+            programs,
+            recipe,
+            registry: game_cfg.registry,
+            files: game_cfg.files,
+            hotkey_name: hotkey::format(&hotkey),
+            hotkey,
+            gamepad,
+            auto_launch: game_cfg
+                .auto_launch
+                .then_some(Duration::from_secs(game_cfg.auto_launch_delay_secs)),
+            refocus: game_cfg.refocus_game,
+            recorder: game_cfg.recorder,
+            backend: game_cfg.backend,
+            program_delay: Duration::from_millis(
+                game_cfg
+                    .program_delay_ms
+                    .unwrap_or(cfg.tunables.program_delay_ms),
+            ),
+            prefix,
+            install_dir: game.map(|game| game.install_dir.clone()),
+            anti_cheat: game.and_then(|game| anticheat::detect(&game.install_dir)),
+            tunables: cfg.tunables,
+        })
+    }
+
+    /// Whether there's anything to start in the game, rather than only files to copy
+    pub fn has_programs(&self) -> bool {
+        !self.programs.is_empty() || !self.recipe.is_empty()
+    }
+
+    /// Copy the game's files into place; they have to be there before it starts. Copies
+    /// that were changed since (usually by a game update) are put back, which is returned
+    /// as a message for the notification.
+    pub fn deploy_files(&self) -> Option<String> {
+        if self.files.is_empty() {
+            return None;
+        }
+        let changed = deploy::changed(&self.app_id);
+        for path in &changed {
+            println!(
+                "Warning: {} was changed or removed since Protonic copied it, repairing",
+                path.display()
+            );
+        }
+        match deploy::deploy(
+            &self.app_id,
+            &self.files,
+            self.prefix.as_deref(),
+            self.install_dir.as_deref(),
+        ) {
+            Ok(0) => None,
+            Ok(count) => {
+                println!("Copied {} files into {}", count, self.game_name);
+                (!changed.is_empty()).then(|| {
+                    format!(
+                        "Repaired {} copied files that had been replaced, e.g. by an update",
+                        changed.len()
+                    )
+                })
+            }
+            Err(e) => {
+                println!("Warning: Could not copy the game's files: {}", e);
+                Some(format!("Could not copy the game's files: {}", e))
+            }
+        }
+    }
+
+    pub fn has_stage(&self, stage: Stage) -> bool {
+        self.stage(stage).next().is_some()
+    }
+
+    /// The programs started at `stage`, in list order
+    fn stage(&self, stage: Stage) -> impl Iterator<Item = &ExeEntry> {
+        self.programs
+            .iter()
+            .filter(move |program| program.stage == stage)
+    }
+
+    /// Whether anything waits for the hotkey; a recipe replaces the hotkey's programs
+    fn waits_for_trigger(&self) -> bool {
+        !self.recipe.is_empty() || self.has_stage(Stage::OnTrigger)
+    }
+
+    /// The program's own delay, else the delay between programs (none before the first
+    /// one of a stage)
+    fn pause_before(&self, program: &ExeEntry, first: bool) -> Duration {
+        match program.delay_ms {
+            Some(ms) => Duration::from_millis(ms),
+            None if first => Duration::ZERO,
+            None => self.program_delay,
+        }
+    }
+
+    /// Something to start programs in the prefix with, once the game's registry values
+    /// are set there
+    fn injector(&self, backend: backend::Backend) -> Result<Injector, String> {
+        let injector = Injector::new(backend, &self.app_id, self.prefix.as_deref())?;
+        match registry::apply(
+            &self.registry,
+            &injector,
+            &self.app_id,
+            self.prefix.as_deref(),
+        ) {
+            Ok(0) => {}
+            Ok(count) => println!("Set {} registry values in the prefix", count),
+            Err(e) => println!("Warning: Could not set registry values: {}", e),
+        }
+        Ok(injector)
+    }
+
+    fn spawn(&self, program: &ExeEntry, injector: &Injector, tracker: &ProcessTracker) {
+        println!("Launching: {} {}", program.path, join_args(&program.args));
+        let _ = tracker.spawn(
+            &mut program.command(injector, &self.app_id),
+            &exe_display_name(&program.path),
+            &self.app_id,
+        );
+    }
+
+    /// Start the before-game programs, one after another, before Steam is asked to start
+    /// the game. There's no protonhax session yet, so they always run through the game's
+    /// own Proton, which needs a prefix from an earlier launch.
+    pub fn start_before_game(&self, tracker: &ProcessTracker) {
+        let programs: Vec<&ExeEntry> = self.stage(Stage::BeforeGame).collect();
+        if programs.is_empty() {
+            return;
+        }
+        let injector = match self.injector(backend::Backend::Native) {
+            Ok(injector) => injector,
+            Err(e) => {
+                println!(
+                    "Warning: Not starting the programs set to run before the game: {}",
+                    e
+                );
+                return;
+            }
+        };
+        for (index, program) in programs.into_iter().enumerate() {
+            thread::sleep(self.pause_before(program, index == 0));
+            self.spawn(program, &injector, tracker);
+        }
+    }
+
+    /// What the launch notification tells the user to do next
+    pub fn instructions(&self) -> String {
+        if !self.waits_for_trigger() {
+            "Your programs start once the game is running".to_string()
+        } else if self.auto_launch.is_some() {
+            format!(
+                "Your programs start once the game is running (or press {})",
+                self.trigger_name()
+            )
+        } else {
+            format!(
+                "Press {} in-game to start your programs",
+                self.trigger_name()
+            )
+        }
+    }
+
+    /// The hotkey, and the controller buttons when the game has them
+    pub fn trigger_name(&self) -> String {
+        if self.gamepad.is_empty() {
+            self.hotkey_name.clone()
+        } else {
+            format!(
+                "{} or {} on a controller",
+                self.hotkey_name,
+                gamepad::format(&self.gamepad)
+            )
+        }
+    }
+
+    /// Run the rest of the plan while the game starts: the hotkey's programs (or recipe)
+    /// when the hotkey is pressed or the game has been up for the auto-launch delay, and
+    /// each after-start program once the game has been up for its seconds. `allow` is
+    /// asked before anything starts in the game, and `report` gets a summary for the
+    /// notification as each starts. Returns once everything has been started.
+    pub fn run_schedule(
+        &self,
+        tracker: &ProcessTracker,
+        allow: &dyn Fn() -> bool,
+        on_progress: &(dyn Fn(Vec<recipe::StepProgress>) + Sync),
+        report: &dyn Fn(Result<String, String>),
+    ) {
+        let mut waiting = self.waits_for_trigger();
+        let mut keyboard = None;
+        let mut gamepads = None;
+        if waiting {
+            keyboard = Keyboard::open();
+            if !self.gamepad.is_empty() {
+                gamepads = Gamepads::open();
+                if gamepads.is_none() {
+                    println!(
+                        "Error: Could not access controllers, {} will not be detected",
+                        gamepad::format(&self.gamepad)
+                    );
+                }
+            }
+            if keyboard.is_none() {
+                let e = format!(
+                    "Could not access the keyboard, {} will not be detected",
+                    self.hotkey_name
+                );
+                println!("Error: {}", e);
+                if self.auto_launch.is_none() && gamepads.is_none() {
+                    waiting = false;
+                    report(Err(e));
+                }
+            }
+        }
+        if waiting {
+            match self.auto_launch {
+                Some(delay) => println!(
+                    "Waiting for the game (then {}s) or {}...",
+                    delay.as_secs(),
+                    self.trigger_name()
+                ),
+                None => println!("Waiting for {}...", self.trigger_name()),
+            }
+        }
+        // Sorted by when they're due, in list order when that's the same
+        let mut after_start: Vec<&ExeEntry> = self.stage(Stage::AfterStart).collect();
+        after_start.sort_by_key(|program| program.start_after_secs);
+        let mut next = 0;
+        let mut injector = None;
+
+        // When the game's session was first seen
+        let mut session_since: Option<Instant> = None;
+        while waiting || next < after_start.len() {
+            let needs_session = (waiting && self.auto_launch.is_some()) || next < after_start.len();
+            let up_for = if needs_session && self.backend.is_running(&self.app_id) {
+                Some(session_since.get_or_insert_with(Instant::now).elapsed())
+            } else {
+                session_since = None;
+                None
+            };
+
+            if waiting {
+                let pressed = keyboard
+                    .as_mut()
+                    .is_some_and(|keyboard| hotkey::is_pressed(&keyboard.get_keys(), &self.hotkey))
+                    || gamepads.as_mut().is_some_and(|gamepads| {
+                        gamepad::is_pressed(&gamepads.held(), &self.gamepad)
+                    });
+                let started = self
+                    .auto_launch
+                    .zip(up_for)
+                    .is_some_and(|(delay, up_for)| up_for >= delay);
+                if started && !pressed {
+                    println!("Game {} is running, starting its programs", self.app_id);
+                }
+                if pressed || started {
+                    waiting = false;
+                    if allow() {
+                        report(self.start_programs(tracker, on_progress));
+                    }
+                }
+            }
+
+            if let Some(up_for) = up_for
+                && let Some(program) = after_start.get(next)
+                && up_for >= Duration::from_secs(program.start_after_secs)
+            {
+                next += 1;
+                if !allow() {
+                    next = after_start.len();
+                    continue;
+                }
+                if injector.is_none() {
+                    match self.injector(self.backend) {
+                        Ok(created) => injector = Some(created),
+                        Err(e) => {
+                            println!("Error: {}", e);
+                            report(Err(e));
+                            next = after_start.len();
+                            continue;
+                        }
+                    }
+                }
+                if let Some(injector) = &injector {
+                    thread::sleep(self.pause_before(program, true));
+                    self.spawn(program, injector, tracker);
+                    report(Ok(format!("Started {}", exe_display_name(&program.path))));
+                }
+            }
+            thread::sleep(Duration::from_millis(self.tunables.key_poll_ms));
+        }
+    }
+
+    /// Start the recorder and then the recipe or the hotkey's programs in order. Returns a
+    /// summary for the notification; an error if the recipe stopped.
+    fn start_programs(
+        &self,
+        tracker: &ProcessTracker,
+        on_progress: &(dyn Fn(Vec<recipe::StepProgress>) + Sync),
+    ) -> Result<String, String> {
+        // The hotkey was pressed in-game (or the game has been up for a while),
+        // so the focused window is the game's
+        // The native backend reads the game's Proton from its prefix, which is only
+        // sure to be set up now that the game runs
+        let injector = self.injector(self.backend)?;
+
+        let game_window = if self.refocus || self.recorder != recorder::Recorder::None {
+            focus::active_window()
+        } else {
+            None
+        };
+        recorder::start(
+            self.recorder,
+            game_window.as_deref(),
+            &self.app_id,
+            &self.game_name,
+            self.backend,
+            tracker,
+        );
+        let game_window = game_window.filter(|_| self.refocus);
+
+        // A recipe replaces starting the programs one by one
+        let result = if self.recipe.is_empty() {
+            for (index, program) in self.stage(Stage::OnTrigger).enumerate() {
+                thread::sleep(self.pause_before(program, index == 0));
+                self.spawn(program, &injector, tracker);
+            }
+            let names: Vec<String> = self
+                .stage(Stage::OnTrigger)
+                .map(|program| exe_display_name(&program.path))
+                .collect();
+            Ok(format!("Started {}", names.join(", ")))
+        } else {
+            recipe::run(
+                &self.recipe,
+                &self.app_id,
+                &injector,
+                self.prefix.as_deref(),
+                tracker,
+                on_progress,
+            )
+            .map(|()| "Recipe finished".to_string())
+            .map_err(|e| format!("Recipe stopped: {}", e))
+        };
+        if let Err(e) = &result {
+            println!("Error: {}", e);
+        }
+
+        // Only once a recipe is done, so its keystrokes still reach the tools
+        if let Some(window) = game_window {
+            focus::keep_focused(window, Duration::from_secs(self.tunables.refocus_secs));
+        }
+        result
+    }
+}
+
+/// Where a program path is now (see winpath::resolve), or the path as it was if it
+/// can't be found, so the error comes from protonhax (or Proton) as before
+fn locate(path: &str, prefix: Option<&Path>) -> String {
+    match winpath::resolve(path, prefix) {
+        Some(found) if found != Path::new(path) => {
+            println!("Using {} for {}", found.display(), path);
+            found.to_string_lossy().into_owned()
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Ask Steam to start the game
+pub fn start_game(app_id: &str) {
+    println!("Launching Steam Game {}...", app_id);
+    let url = shortcuts::run_url(app_id).unwrap_or_else(|| format!("steam://run/{}", app_id));
+    match steam::open_command(&url).spawn() {
+        Ok(child) => process::reap_in_background(child),
+        Err(e) => println!("Error: Could not start Steam: {}", e),
+    }
+}
@@ -0,0 +1,69 @@
+//! Links saved per game (mod guides, Nexus pages, PCGamingWiki...), shown as chips in
+//! the detail panel and opened in the browser.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+// Names for sites a chip would otherwise show as a bare host name
+const KNOWN_SITES: [(&str, &str); 6] = [
+    ("nexusmods.com", "Nexus Mods"),
+    ("pcgamingwiki.com", "PCGamingWiki"),
+    ("steamcommunity.com", "Steam Community"),
+    ("protondb.com", "ProtonDB"),
+    ("github.com", "GitHub"),
+    ("moddb.com", "ModDB"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Link {
+    // Shown on the chip, the site's name when empty
+    #[serde(default)]
+    pub title: String,
+    pub url: String,
+}
+
+impl Link {
+    /// Text on the chip
+    pub fn label(&self) -> String {
+        if !self.title.is_empty() {
+            return self.title.clone();
+        }
+        let host = host(&self.url);
+        KNOWN_SITES
+            .iter()
+            .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| host.trim_start_matches("www.").to_string())
+    }
+}
+
+/// "example.com" for "https://www.example.com/some/page"
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or_default()
+}
+
+/// A link typed into the add field: the URL, optionally after a title
+/// ("Modding guide https://...")
+pub fn parse(text: &str) -> Result<Link, String> {
+    let text = text.trim();
+    let (title, url) = match text.rsplit_once(char::is_whitespace) {
+        Some((title, url)) => (title.trim(), url),
+        None => ("", text),
+    };
+    if !(url.starts_with("https://") || url.starts_with("http://")) || host(url).is_empty() {
+        return Err(format!("'{}' is not a http(s) link", url));
+    }
+    Ok(Link {
+        title: title.to_string(),
+        url: url.to_string(),
+    })
+}
+
+/// Open a link in the default browser
+pub fn open(url: &str) -> Result<(), String> {
+    crate::logs::quiet(Command::new("xdg-open").arg(url))
+        .spawn()
+        .map(crate::process::reap_in_background)
+        .map_err(|e| format!("Could not open {}: {}", url, e))
+}
@@ -0,0 +1,36 @@
+//! Profile lock for shared PCs: launching keeps working, editing programs and launch
+//! options doesn't. The optional PIN is only stored as a SHA-256 hash.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Keeps the stored hash from matching a plain hash of the same digits elsewhere
+const PIN_SALT: &str = "protonic-lock:";
+
+/// Hash a PIN for storing in the config. An empty PIN means no PIN.
+pub fn hash_pin(pin: &str) -> Result<String, String> {
+    if pin.is_empty() {
+        return Ok(String::new());
+    }
+    let mut child = Command::new("sha256sum")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run sha256sum: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(format!("{}{}", PIN_SALT, pin).as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(String::from)
+        .ok_or_else(|| "sha256sum gave no output".to_string())
+}
+
+/// Whether `pin` unlocks a lock stored with `hash` (anything does when there's no PIN)
+pub fn pin_matches(pin: &str, hash: &str) -> bool {
+    hash.is_empty() || hash_pin(pin).is_ok_and(|entered| entered == hash)
+}
@@ -0,0 +1,169 @@
+//! Everything the window prints (and what the programs it starts print) also goes to a
+//! log file in `~/.local/share/protonic/logs/` and to the log panel, so a failed launch
+//! can be looked into without starting Protonic from a terminal. stdout and stderr are
+//! redirected into a pipe that a thread copies to the terminal, the file and the panel.
+//! Nothing reads that pipe once Protonic exits, so commands that can outlive it (Steam,
+//! the programs it starts in games, file managers) are given output of their own.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::os::fd::FromRawFd;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+// Log files kept, one per run
+const KEEP_FILES: usize = 10;
+// Lines the panel shows
+const PANEL_LINES: usize = 500;
+
+static LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+// Bumped for every line, so the panel only refreshes when there's something new
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Where the log files are
+pub fn logs_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join("logs"))
+}
+
+/// Local wall-clock time, e.g. "2024-05-01 21:14:03"
+fn local_time(timestamp: u64) -> String {
+    let time = timestamp as libc::time_t;
+    // SAFETY: localtime_r only writes to the tm we hand it
+    let tm = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&time, &mut tm).is_null() {
+            return timestamp.to_string();
+        }
+        tm
+    };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// Delete all but the newest log files. Their names sort by start time.
+fn prune(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    files.sort();
+    let excess = files.len().saturating_sub(KEEP_FILES);
+    for old in &files[..excess] {
+        let _ = fs::remove_file(old);
+    }
+}
+
+/// Start copying stdout and stderr into this run's log file and the panel.
+/// Returns the log file's path.
+pub fn start() -> Result<PathBuf, String> {
+    let dir = logs_dir().ok_or_else(|| "Could not find the data directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("protonic-{}.log", crate::journal::now()));
+    let mut file =
+        File::create(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    prune(&dir);
+
+    let mut fds = [0; 2];
+    // SAFETY: pipe2 and dup only create new descriptors; dup2 swaps ours in for stdout
+    // and stderr, which Rust's std keeps writing to by number. Both ends are close-on-exec
+    // so children don't hold the read end open; stdout and stderr themselves (which dup2
+    // leaves inheritable) are handled by quiet() and to_file()
+    let terminal = unsafe {
+        if libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+            return Err("Could not create the log pipe".to_string());
+        }
+        let terminal = libc::fcntl(libc::STDOUT_FILENO, libc::F_DUPFD_CLOEXEC, 0);
+        if terminal < 0
+            || libc::dup2(fds[1], libc::STDOUT_FILENO) < 0
+            || libc::dup2(fds[1], libc::STDERR_FILENO) < 0
+        {
+            return Err("Could not redirect output to the log".to_string());
+        }
+        libc::close(fds[1]);
+        File::from_raw_fd(terminal)
+    };
+    // SAFETY: the read end is ours alone from here on
+    let reader = BufReader::new(unsafe { File::from_raw_fd(fds[0]) });
+
+    thread::spawn(move || {
+        let (mut terminal, mut reader) = (terminal, reader);
+        let mut raw = Vec::new();
+        // Children write here too, e.g. Wine in a Windows codepage, so lines are decoded
+        // loosely. Stopping would leave the pipe undrained and block every println once
+        // it's full.
+        loop {
+            raw.clear();
+            // Only ends when every writer is gone
+            if reader.read_until(b'\n', &mut raw).unwrap_or(0) == 0 {
+                break;
+            }
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']).to_string();
+            let _ = writeln!(terminal, "{}", line);
+            let _ = writeln!(file, "{} {}", local_time(crate::journal::now()), line);
+            let mut lines = LINES.lock().unwrap();
+            if lines.len() == PANEL_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+            GENERATION.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    Ok(path)
+}
+
+/// Changes whenever a line is logged
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// The last lines logged, newest first
+pub fn recent() -> String {
+    // Also called from the panic hook, which shouldn't panic again
+    LINES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .rev()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Send a command's output nowhere, for commands that may outlive Protonic
+pub fn quiet(command: &mut Command) -> &mut Command {
+    command.stdout(Stdio::null()).stderr(Stdio::null())
+}
+
+/// Send a command's output to its own log file, `logs/programs/<name>.log`, which holds
+/// its last run. Falls back to quiet() if the file can't be created.
+pub fn to_file<'a>(command: &'a mut Command, name: &str) -> &'a mut Command {
+    let file_name: String = name
+        .chars()
+        .map(|c| if c == '/' { '_' } else { c })
+        .collect();
+    let file = logs_dir()
+        .map(|dir| dir.join("programs"))
+        .filter(|dir| fs::create_dir_all(dir).is_ok())
+        .and_then(|dir| File::create(dir.join(format!("{}.log", file_name))).ok())
+        .and_then(|file| Some((file.try_clone().ok()?, file)));
+    match file {
+        Some((stdout, stderr)) => command.stdout(stdout).stderr(stderr),
+        None => quiet(command),
+    }
+}
@@ -1,35 +1,425 @@
 slint::include_modules!();
-use device_query::{DeviceQuery, DeviceState, Keycode};
-use rfd::FileDialog;
-use rodio::{Decoder, OutputStream, Sink};
+mod anticheat;
+mod appinfo;
+mod audio;
+mod backend;
+mod backup;
+mod cli;
+mod crash;
+mod deploy;
+mod disk;
+mod display;
+mod doctor;
+mod focus;
+mod gamepad;
+mod history;
+mod hotkey;
+mod input_access;
+mod inspector;
+mod journal;
+mod keyboard;
+mod keystroke;
+mod launch;
+mod links;
+mod lock;
+mod logs;
+mod migrate;
+mod names;
+mod notify;
+mod overlay;
+mod pcgw;
+mod portal;
+mod presets;
+mod process;
+mod proton;
+mod protonhax;
+mod recipe;
+mod recorder;
+mod registry;
+mod remap;
+mod script;
+mod shortcuts;
+mod steam;
+mod store;
+mod sync;
+mod tasklist;
+mod terminal;
+mod theme;
+mod tools;
+mod tunables;
+mod updates;
+mod usage;
+mod vdf;
+mod winetricks;
+mod winpath;
+mod workers;
+mod wrapper;
+
+use appinfo::AppMetadata;
+use audio::{AudioService, BUILTIN_PACK, DEFAULT_DEVICE, SoundEvent};
+use clap::Parser;
+use process::ProcessTracker;
+use rfd::{FileDialog, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
 use serde::{Deserialize, Serialize};
-use slint::{ModelRc, SharedString, VecModel};
-use std::collections::{BTreeMap, HashMap};
+use slint::{
+    Image, Model, ModelRc, Rgba8Pixel, SharedPixelBuffer, SharedString, Timer, TimerMode, VecModel,
+};
+use std::cell::{OnceCell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::io::{BufReader, Cursor};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::rc::Rc;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
-use steamlocate::SteamDir;
 
-// Embed our ogg audio files at compile time
-const AUDIO_LAUNCH_GAME: &[u8] = include_bytes!("../audio/LaunchGame.ogg");
-const AUDIO_LAUNCH_PROGRAM: &[u8] = include_bytes!("../audio/LaunchProgram.ogg");
+/// A program started in the game's session on the hotkey
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+struct ExeEntry {
+    // Path of the .exe, or "tool:<name>" for a program from the tool library
+    path: String,
+    // Winetricks verbs it needs in the game's prefix (e.g. dotnet48)
+    verbs: Vec<String>,
+    // Command-line arguments passed to it, e.g. --fullscreen
+    args: Vec<String>,
+    // Directory it's started in, Protonic's own when not set
+    working_dir: Option<PathBuf>,
+    // When it starts: before the game, on the hotkey, or a while after the game starts
+    stage: launch::Stage,
+    // Seconds after the game's session appears, for the after-start stage
+    start_after_secs: u64,
+    // Pause before starting it, None for the game's delay between programs
+    delay_ms: Option<u64>,
+    // Environment variables it's started with, e.g. WINEDLLOVERRIDES=dinput8=n,b
+    env: Vec<EnvVar>,
+}
+
+/// An environment variable set for one program
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+struct EnvVar {
+    name: String,
+    value: String,
+}
+
+impl EnvVar {
+    /// Whether it can be set; rows still being typed in may have no name yet
+    fn is_valid(&self) -> bool {
+        wrapper::is_assignment(&format!("{}=", self.name))
+    }
+}
+
+impl ExeEntry {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            ..Self::default()
+        }
+    }
+
+    /// This program run in the game's prefix, with its arguments, environment and working
+    /// directory
+    fn command(&self, injector: &backend::Injector, app_id: &str) -> Command {
+        let mut command = injector.run_command(app_id, &self.path);
+        command.args(&self.args);
+        for var in &self.env {
+            if var.is_valid() {
+                command.env(&var.name, &var.value);
+            } else if !var.name.is_empty() {
+                println!(
+                    "Warning: '{}' isn't a valid variable name, not setting it for {}",
+                    var.name, self.path
+                );
+            }
+        }
+        if let Some(dir) = &self.working_dir {
+            command.current_dir(dir);
+        }
+        command
+    }
+}
+
+/// Resolve "tool:<name>" references in a game's programs to the tools' real paths
+fn resolve_programs(tools: &[tools::Tool], programs: &[ExeEntry]) -> Result<Vec<ExeEntry>, String> {
+    programs
+        .iter()
+        .map(|program| {
+            Ok(ExeEntry {
+                path: tools::resolve_program(tools, &program.path)?,
+                ..program.clone()
+            })
+        })
+        .collect()
+}
+
+/// Split an arguments field into words. Double or single quotes keep spaces in a word.
+fn split_args(text: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        args.push(current);
+    }
+    args
+}
+
+/// The reverse of split_args, for showing arguments in the UI
+fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
 struct GameConfig {
+    // Started one after another on the hotkey
+    programs: Vec<ExeEntry>,
+    // Key (or combination like "LControl+F9") that starts the programs, empty for F1
+    hotkey: String,
+    // Controller button or combination like "Select+RB" that also starts them, empty for none
+    gamepad_trigger: String,
+    // Start the programs once the game's protonhax session appears instead of waiting
+    // for the hotkey (which still works as a fallback), after this many seconds
+    auto_launch: bool,
+    auto_launch_delay_secs: u64,
+    // Silences Protonic's sounds for this game, regardless of the global setting
+    do_not_disturb: bool,
+    // Give focus back to the game when its programs open windows over it
+    refocus_game: bool,
+    // Screen recorder started alongside the programs on the hotkey
+    recorder: recorder::Recorder,
+    // How programs are started in the game's prefix: protonhax, or the game's own Proton
+    backend: backend::Backend,
+    // Guides, mod pages and wiki entries shown as chips in the detail panel
+    links: Vec<links::Link>,
+    // Shell commands run after this game's programs are stopped (e.g. resetting gamma)
+    cleanup_hooks: Vec<String>,
+    // Launch options template auto-configure adds, empty for the default wrapper
+    wrapper: String,
+    // VAR=value pairs set in the launch options, on top of (or replacing) the default env
+    env: Vec<String>,
+    // Pause between starting one program and the next, None for the default
+    program_delay_ms: Option<u64>,
+    // Steps run on the hotkey instead of just starting the programs, when not empty
+    recipe: Vec<recipe::GuardedStep>,
+    // Wine registry values set once in the game's prefix before its programs start
+    registry: Vec<registry::Tweak>,
+    // Files copied into the game's folder or prefix before it starts (DLL proxies etc.)
+    files: Vec<deploy::FileDrop>,
+    // Winetricks verbs installed into the game's prefix from Protonic, in order
+    verbs_installed: Vec<String>,
+    // Shell commands run after Steam finishes updating the game (re-patching configs etc.)
+    update_hooks: Vec<String>,
+    // The game's other named program sets; `programs` holds the one in use. Called
+    // profiles in older configs.
+    #[serde(alias = "profiles")]
+    program_sets: BTreeMap<String, Vec<ExeEntry>>,
+    // Name of the program set in `programs`, empty for the default one
+    #[serde(alias = "profile")]
+    program_set: String,
+    // Program set switched to when the game is selected or launched, empty for the default one
+    #[serde(alias = "default_profile")]
+    default_program_set: String,
+    // Exe 1 and 2 from configs written before the program list; moved into `programs` on load
+    #[serde(skip_serializing)]
     exe1_path: String,
+    #[serde(skip_serializing)]
     exe2_path: String,
+    #[serde(skip_serializing)]
+    exe1_verbs: Vec<String>,
+    #[serde(skip_serializing)]
+    exe2_verbs: Vec<String>,
+}
+
+impl GameConfig {
+    /// Move exe 1 / exe 2 from an old config into the program list
+    fn upgrade_programs(&mut self) {
+        let old = [
+            (
+                std::mem::take(&mut self.exe1_path),
+                std::mem::take(&mut self.exe1_verbs),
+            ),
+            (
+                std::mem::take(&mut self.exe2_path),
+                std::mem::take(&mut self.exe2_verbs),
+            ),
+        ];
+        if self.programs.is_empty() {
+            self.programs = old
+                .into_iter()
+                .filter(|(path, _)| !path.is_empty())
+                .map(|(path, verbs)| ExeEntry {
+                    verbs,
+                    ..ExeEntry::new(path)
+                })
+                .collect();
+        }
+    }
+
+    /// Name of the program set in use
+    fn active_program_set(&self) -> &str {
+        if self.program_set.is_empty() {
+            DEFAULT_PROGRAM_SET
+        } else {
+            &self.program_set
+        }
+    }
+
+    /// Every program set's name, the default one first
+    fn program_set_names(&self) -> Vec<String> {
+        let mut names: BTreeSet<&str> = self.program_sets.keys().map(String::as_str).collect();
+        names.insert(self.active_program_set());
+        names.remove(DEFAULT_PROGRAM_SET);
+        std::iter::once(DEFAULT_PROGRAM_SET)
+            .chain(names)
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Put the programs of `name` in use (none for a new name), keeping the ones in use
+    /// under their own name
+    fn switch_program_set(&mut self, name: &str) {
+        if name == self.active_program_set() {
+            return;
+        }
+        let programs = std::mem::take(&mut self.programs);
+        self.program_sets
+            .insert(self.active_program_set().to_string(), programs);
+        self.programs = self.program_sets.remove(name).unwrap_or_default();
+        self.program_set = if name == DEFAULT_PROGRAM_SET {
+            String::new()
+        } else {
+            name.to_string()
+        };
+    }
+
+    /// Put the game's default program set in use. Returns whether that changed anything.
+    fn use_default_program_set(&mut self) -> bool {
+        let default = match self.default_program_set.as_str() {
+            "" => DEFAULT_PROGRAM_SET.to_string(),
+            name => name.to_string(),
+        };
+        if default == self.active_program_set() || !self.program_set_names().contains(&default) {
+            return false;
+        }
+        self.switch_program_set(&default);
+        true
+    }
 }
 
+// Name of the program set every game starts with
+const DEFAULT_PROGRAM_SET: &str = "Default";
+
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
 struct AppConfig {
     last_game_name: String,
     last_app_id: String,
     auto_configure: bool,
+    sounds_enabled: bool,
+    // Warn when a game's Wine prefix grows beyond this many GB (0 disables the check)
+    prefix_size_alert_gb: u64,
+    // Name of the sound pack directory, empty for the built-in sounds
+    sound_pack: String,
+    // Output device sounds play on, empty for the system default
+    audio_device: String,
+    // Stop injected programs when Protonic itself is closed
+    kill_on_exit: bool,
+    // Stop a game's programs (and run its cleanup hooks) once the game exits
+    stop_with_game: bool,
+    // Steam Web API key for fetching store info Steam hasn't cached locally
+    steam_api_key: String,
+    // Global switch for anything that talks to the network
+    online_metadata: bool,
+    // Hold back background work and refresh less while a game is running
+    low_resource_mode: bool,
+    // Terminal command for interactive tools, empty to auto-detect
+    terminal: String,
+    // Downloadable companion tools, referenced from games as "tool:<name>"
+    tools: Vec<tools::Tool>,
+    // When the tools were last checked for new releases (unix seconds)
+    tools_checked_at: u64,
+    // Desktop notifications when a game and its programs are launched, launch options
+    // are configured, or something goes wrong
+    notifications: bool,
+    // The same launch status and errors in a box over the game, see overlay.rs
+    overlay: bool,
+    // UI scale in percent (75-200), 0 to let the platform decide
+    ui_scale: u32,
+    // Accent color of the windows, as #rrggbb
+    accent_color: String,
+    // Corner radius of panels and tiles in pixels
+    corner_radius: u32,
+    // Programs and launch options can't be edited, only launched
+    locked: bool,
+    // SHA-256 of the unlock PIN, empty when unlocking needs no PIN
+    lock_pin: String,
+    // Defaults games use until they set their own: wrapper template (empty for
+    // protonhax) and VAR=value pairs for the launch options
+    default_wrapper: String,
+    default_env: Vec<String>,
+    // Programs recently added to any game, most recent first
+    recent_programs: Vec<String>,
+    // Sort "The Witcher 3" under W in the game list
+    ignore_articles: bool,
+    // Show the game list as a grid of artwork
+    grid_view: bool,
+    // Hide the library sidebar, leaving the window to the selected game
+    sidebar_collapsed: bool,
+    // Width of the library sidebar in pixels
+    sidebar_width: u32,
+    // Keep a local history of sessions and programs for the usage dashboard
+    usage_history: bool,
+    // Ask for a short note when a game exits, kept with the game's session history
+    session_notes: bool,
+    // Paths from other computers and where they are on this one
+    path_remaps: Vec<remap::PathRemap>,
+    // Folder kept in sync between computers (Syncthing, Nextcloud...) that the config
+    // is mirrored to, empty to not sync
+    sync_dir: String,
+    // Steam installation to use when there are several (native, Flatpak, Snap),
+    // empty for the first one found
+    steam_path: String,
+    // SteamID64 of the account whose launch options are edited, 0 for the one that
+    // logged in to Steam last
+    steam_id: u64,
+    // Polling intervals, delays and timeouts
+    tunables: tunables::Tunables,
     game_configs: HashMap<String, GameConfig>,
+    // Top-level before the tunables section; moved there on load
+    #[serde(skip_serializing)]
+    kill_grace_ms: Option<u64>,
 }
 
 impl Default for AppConfig {
@@ -38,476 +428,5908 @@ impl Default for AppConfig {
             last_game_name: String::new(),
             last_app_id: String::new(),
             auto_configure: true,
+            sounds_enabled: true,
+            prefix_size_alert_gb: 20,
+            sound_pack: String::new(),
+            audio_device: String::new(),
+            kill_on_exit: false,
+            stop_with_game: true,
+            steam_api_key: String::new(),
+            online_metadata: true,
+            low_resource_mode: true,
+            terminal: String::new(),
+            tools: Vec::new(),
+            tools_checked_at: 0,
+            notifications: true,
+            overlay: false,
+            ui_scale: 0,
+            accent_color: theme::DEFAULT_ACCENT.to_string(),
+            corner_radius: theme::DEFAULT_RADIUS,
+            locked: false,
+            lock_pin: String::new(),
+            default_wrapper: String::new(),
+            default_env: Vec::new(),
+            recent_programs: Vec::new(),
+            ignore_articles: false,
+            grid_view: false,
+            sidebar_collapsed: false,
+            sidebar_width: 300,
+            usage_history: false,
+            session_notes: false,
+            path_remaps: Vec::new(),
+            sync_dir: String::new(),
+            steam_path: String::new(),
+            steam_id: 0,
+            tunables: tunables::Tunables::default(),
             game_configs: HashMap::new(),
+            kill_grace_ms: None,
         }
     }
 }
 
-/// Play an embedded audio file in a separate thread
-fn play_audio(audio_data: &'static [u8]) {
-    thread::spawn(move || {
-        // Get output stream - _stream must be kept alive for playback
-        if let Ok((_stream, stream_handle)) = OutputStream::try_default() {
-            if let Ok(sink) = Sink::try_new(&stream_handle) {
-                let cursor = Cursor::new(audio_data);
-                if let Ok(source) = Decoder::new(BufReader::new(cursor)) {
-                    sink.append(source);
-                    sink.sleep_until_end();
-                }
+/// Where an installed game lives, and who it belongs to
+#[derive(Clone)]
+struct InstalledGame {
+    library: PathBuf,
+    // Where the game's files are (steamapps/common/<game>)
+    install_dir: PathBuf,
+    // SteamID64 of the owning account when the game is Family Shared from someone else
+    shared_from: Option<u64>,
+    metadata: AppMetadata,
+    // Runs through Proton (has a compat tool, a Wine prefix or only a Windows build)
+    uses_proton: bool,
+}
+
+// Wrapper dropdown entry that reveals the free-form template field
+const CUSTOM_WRAPPER: &str = "Custom";
+
+// Proton dropdown entry for leaving the choice to Steam
+const STEAM_DEFAULT_TOOL: &str = "Steam default";
+
+// How many executables the "Recent" dropdowns remember
+const RECENT_PROGRAMS_LIMIT: usize = 10;
+
+// Platform filters for the game list
+const FILTER_ALL: &str = "All games";
+const FILTER_WINDOWS_ONLY: &str = "Windows-only";
+const FILTER_NATIVE_LINUX: &str = "Native Linux";
+
+/// Game names matching the search text and platform filter, in the locale's sort order.
+/// Games without cached metadata only show up under "All games".
+fn filter_games(
+    games: &BTreeMap<String, String>,
+    installed: &HashMap<String, InstalledGame>,
+    search: &str,
+    platform_filter: &str,
+    ignore_articles: bool,
+) -> Vec<GameTile> {
+    let mut names: Vec<(&String, &String)> = games
+        .iter()
+        .filter(|(name, _)| names::matches(name, search))
+        .filter(|(_, id)| {
+            let metadata = installed.get(*id).map(|g| &g.metadata);
+            match platform_filter {
+                FILTER_WINDOWS_ONLY => metadata.is_some_and(|m| m.is_windows_only()),
+                FILTER_NATIVE_LINUX => metadata.is_some_and(|m| m.has_native_linux()),
+                _ => true,
             }
+        })
+        .collect();
+    match names::NameOrder::new(ignore_articles) {
+        Ok(order) => names.sort_by(|(a, _), (b, _)| order.compare(a, b)),
+        Err(e) => println!("Warning: {}, sorting games by their raw names", e),
+    }
+    names
+        .into_iter()
+        .map(|(name, id)| GameTile {
+            name: name.into(),
+            app_id: id.into(),
+            art: Image::default(),
+            has_art: false,
+        })
+        .collect()
+}
+
+/// Read and decode an image off the UI thread; slint makes the image from the pixels
+fn decode_image(path: &Path) -> Option<SharedPixelBuffer<Rgba8Pixel>> {
+    let pixels = match image::open(path) {
+        Ok(image) => image.into_rgba8(),
+        Err(e) => {
+            println!("Warning: Could not load {}: {}", path.display(), e);
+            return None;
         }
-    });
+    };
+    Some(SharedPixelBuffer::clone_from_slice(
+        pixels.as_raw(),
+        pixels.width(),
+        pixels.height(),
+    ))
 }
 
-/// Find the Steam userdata directory for the current user
-fn find_steam_userdata_path() -> Option<PathBuf> {
-    let steam_dir = SteamDir::locate().ok()?;
-    let userdata_path = steam_dir.path().join("userdata");
+// Artwork pixels of a game, None when it has none
+type Decoded = (String, Option<SharedPixelBuffer<Rgba8Pixel>>);
 
-    if userdata_path.exists() {
-        // Find the first user directory (we're going to assume most users have only one)
-        if let Ok(entries) = fs::read_dir(&userdata_path) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    // Check it's a numeric user ID directory
-                    if let Some(name) = path.file_name() {
-                        if name.to_string_lossy().chars().all(|c| c.is_ascii_digit()) {
-                            return Some(path);
-                        }
+/// Loads the artwork of the listed games for the grid view. The worker pool reads and
+/// decodes the files; slint images can only be made on the UI thread, so a timer there
+/// picks up the decoded pixels. Images are kept, so searching again doesn't reload them.
+struct ArtLoader {
+    steam_path: Option<PathBuf>,
+    images: RefCell<HashMap<String, Option<Image>>>,
+    // Queued for the workers and not picked up yet
+    pending: RefCell<HashSet<String>>,
+    decoded: Arc<Mutex<Vec<Decoded>>>,
+    timer: Timer,
+}
+
+impl ArtLoader {
+    fn new() -> ArtLoader {
+        ArtLoader {
+            steam_path: steam::path(),
+            images: RefCell::new(HashMap::new()),
+            pending: RefCell::new(HashSet::new()),
+            decoded: Arc::new(Mutex::new(Vec::new())),
+            timer: Timer::default(),
+        }
+    }
+
+    /// Read and decode a game's artwork; runs on a worker
+    fn decode(steam_path: Option<&Path>, app_id: &str) -> Option<SharedPixelBuffer<Rgba8Pixel>> {
+        let path = if shortcuts::is_shortcut(app_id) {
+            shortcuts::grid_image(app_id)
+        } else {
+            store::local_header(steam_path?, app_id)
+        }?;
+        decode_image(&path)
+    }
+
+    /// Fill artwork into the listed games, queueing what isn't loaded yet; held back
+    /// while background work is paused for a running game
+    fn start(loader: &Rc<ArtLoader>, ui: &AppWindow) {
+        if !ui.get_grid_view() || workers::paused() {
+            loader.timer.stop();
+            return;
+        }
+        let games = ui.get_games();
+        for row in 0..games.row_count() {
+            let Some(mut tile) = games.row_data(row) else {
+                continue;
+            };
+            if tile.has_art {
+                continue;
+            }
+            let app_id = tile.app_id.to_string();
+            let cached = loader.images.borrow().get(&app_id).cloned();
+            match cached {
+                Some(Some(image)) => {
+                    tile.art = image;
+                    tile.has_art = true;
+                    games.set_row_data(row, tile);
+                }
+                Some(None) => {}
+                None => {
+                    if !loader.pending.borrow_mut().insert(app_id.clone()) {
+                        continue;
                     }
+                    let steam_path = loader.steam_path.clone();
+                    let decoded = Arc::clone(&loader.decoded);
+                    workers::spawn(move || {
+                        let pixels = ArtLoader::decode(steam_path.as_deref(), &app_id);
+                        decoded.lock().unwrap().push((app_id, pixels));
+                    });
                 }
             }
         }
+        if loader.pending.borrow().is_empty() {
+            loader.timer.stop();
+            return;
+        }
+
+        let ui_handle = ui.as_weak();
+        let weak = Rc::downgrade(loader);
+        loader.timer.start(
+            TimerMode::Repeated,
+            std::time::Duration::from_millis(50),
+            move || {
+                let (Some(ui), Some(loader)) = (ui_handle.upgrade(), weak.upgrade()) else {
+                    return;
+                };
+                let decoded = std::mem::take(&mut *loader.decoded.lock().unwrap());
+                if decoded.is_empty() {
+                    return;
+                }
+                let games = ui.get_games();
+                for (app_id, pixels) in decoded {
+                    loader.pending.borrow_mut().remove(&app_id);
+                    let image = pixels.map(Image::from_rgba8);
+                    loader
+                        .images
+                        .borrow_mut()
+                        .insert(app_id.clone(), image.clone());
+                    let Some(image) = image else {
+                        continue;
+                    };
+                    // The game may have been searched away meanwhile
+                    for row in 0..games.row_count() {
+                        if let Some(mut tile) = games.row_data(row)
+                            && tile.app_id == app_id.as_str()
+                        {
+                            tile.art = image.clone();
+                            tile.has_art = true;
+                            games.set_row_data(row, tile);
+                        }
+                    }
+                }
+                if loader.pending.borrow().is_empty() {
+                    loader.timer.stop();
+                }
+            },
+        );
     }
-    None
 }
 
-/// Get the path to localconfig.vdf
-fn get_localconfig_path() -> Option<PathBuf> {
-    let userdata = find_steam_userdata_path()?;
-    let localconfig = userdata.join("config").join("localconfig.vdf");
-    if localconfig.exists() {
-        Some(localconfig)
-    } else {
-        None
+// Config name, "protonic" or "protonic-<profile>" when started with --profile
+static CONFIG_NAME: OnceLock<String> = OnceLock::new();
+
+/// Name the config is stored under with confy
+fn config_name() -> &'static str {
+    CONFIG_NAME.get().map(String::as_str).unwrap_or("protonic")
+}
+
+fn save_config(cfg: &AppConfig) {
+    let _ = confy::store(config_name(), None, cfg);
+    if !cfg.sync_dir.is_empty() {
+        let pushed = toml::Value::try_from(cfg)
+            .map_err(|e| e.to_string())
+            .and_then(|value| sync::push(Path::new(&cfg.sync_dir), value));
+        if let Err(e) = pushed {
+            println!("Warning: Config not synced: {}", e);
+        }
     }
 }
 
-/// Check if protonhax is already in the launch options for a game
-fn has_protonhax_configured(app_id: &str) -> bool {
-    if let Some(localconfig_path) = get_localconfig_path() {
-        if let Ok(content) = fs::read_to_string(&localconfig_path) {
-            // Simple text-based check - look for the app ID section and protonhax
-            // VDF structure: "Apps" { "app_id" { "LaunchOptions" "..." } }
-            if let Some(app_pos) = content.find(&format!("\"{}\"", app_id)) {
-                // Look for LaunchOptions within a reasonable distance after the app ID
-                let search_area = &content[app_pos..std::cmp::min(app_pos + 500, content.len())];
-                if let Some(launch_pos) = search_area.find("\"LaunchOptions\"") {
-                    let after_launch = &search_area[launch_pos..];
-                    // Find the value (next quoted string after "LaunchOptions")
-                    if let Some(first_quote) = after_launch[15..].find('"') {
-                        let value_start = 15 + first_quote + 1;
-                        if let Some(end_quote) = after_launch[value_start..].find('"') {
-                            let launch_options =
-                                &after_launch[value_start..value_start + end_quote];
-                            return launch_options.contains("protonhax");
-                        }
-                    }
+/// The config with the copy in its sync folder merged in, and the settings that were
+/// changed differently on this and another computer
+fn pull_synced_config(cfg: &AppConfig) -> Result<(AppConfig, Vec<String>), String> {
+    let local = toml::Value::try_from(cfg).map_err(|e| e.to_string())?;
+    let (merged, conflicts) = sync::pull(Path::new(&cfg.sync_dir), local)?;
+    let merged = merged
+        .try_into()
+        .map_err(|e| format!("Invalid synced config: {}", e))?;
+    Ok((merged, conflicts))
+}
+
+/// Profile name given with `--profile`. Only letters, digits, '-' and '_' are kept,
+/// since it ends up in a directory name.
+fn profile_name(raw: &str) -> Option<String> {
+    let name: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// A game to select when the window opens, from `--game <app id>` (plus `--launch`) or
+/// a protonic://select/<app id> or protonic://launch/<app id> link
+struct StartupGame {
+    app_id: String,
+    launch: bool,
+}
+
+fn startup_game(args: &cli::Cli) -> Option<StartupGame> {
+    let (app_id, launch) = match &args.game {
+        Some(app_id) => (app_id.clone(), args.launch),
+        None => {
+            let link = args.link.as_deref()?;
+            let Some(url) = link.strip_prefix("protonic://") else {
+                println!("Warning: Ignoring '{}', not a protonic:// link", link);
+                return None;
+            };
+            let mut parts = url.trim_end_matches('/').split('/');
+            match (parts.next(), parts.next()) {
+                (Some("launch"), Some(app_id)) => (app_id.to_string(), true),
+                (Some("select"), Some(app_id)) => (app_id.to_string(), false),
+                (Some(app_id), None) => (app_id.to_string(), false),
+                _ => {
+                    println!(
+                        "Warning: Ignoring protonic://{}, expected protonic://select/<app id> or protonic://launch/<app id>",
+                        url
+                    );
+                    return None;
                 }
             }
         }
+    };
+    if app_id.is_empty() || !app_id.chars().all(|c| c.is_ascii_digit()) {
+        println!("Warning: Ignoring '{}', not an app ID", app_id);
+        return None;
     }
-    false
+    Some(StartupGame { app_id, launch })
 }
 
-/// Configure protonhax in Steam launch options for a game
-fn configure_launch_options(app_id: &str) -> Result<String, String> {
-    let localconfig_path =
-        get_localconfig_path().ok_or_else(|| "Could not find Steam localconfig.vdf".to_string())?;
+/// Whether launch sounds should play for a game (global setting + per-game do-not-disturb)
+fn sounds_allowed(cfg: &AppConfig, app_id: &str) -> bool {
+    let do_not_disturb = cfg
+        .game_configs
+        .get(app_id)
+        .map(|g| g.do_not_disturb)
+        .unwrap_or(false);
+    cfg.sounds_enabled && !do_not_disturb
+}
 
-    let content = fs::read_to_string(&localconfig_path)
-        .map_err(|e| format!("Failed to read localconfig.vdf: {}", e))?;
+/// Whether launch notifications should be shown for a game. Do-not-disturb silences them too.
+fn notifications_allowed(cfg: &AppConfig, app_id: &str) -> bool {
+    let do_not_disturb = cfg
+        .game_configs
+        .get(app_id)
+        .map(|g| g.do_not_disturb)
+        .unwrap_or(false);
+    cfg.notifications && !do_not_disturb
+}
 
-    // Check if already configured
-    if has_protonhax_configured(app_id) {
-        return Ok("Launch options already configured".to_string());
-    }
-
-    // Find the app section
-    let app_pattern = format!("\"{}\"", app_id);
-    let app_pos = content.find(&app_pattern).ok_or_else(|| {
-        "Game not found in Steam config. Launch the game from Steam at least once first."
-            .to_string()
-    })?;
-
-    // Find the opening brace for this app's section
-    let after_app = &content[app_pos + app_pattern.len()..];
-    let brace_offset = after_app
-        .find('{')
-        .ok_or_else(|| "Invalid VDF structure".to_string())?;
-    let section_start = app_pos + app_pattern.len() + brace_offset + 1;
-
-    // Check if LaunchOptions already exists
-    let search_end = std::cmp::min(section_start + 500, content.len());
-    let search_area = &content[section_start..search_end];
-
-    let new_content = if let Some(launch_pos) = search_area.find("\"LaunchOptions\"") {
-        // LaunchOptions exists - we need to prepend protonhax to the existing value
-        let abs_launch_pos = section_start + launch_pos;
-        let after_key = &content[abs_launch_pos + 15..]; // 15 = len of "LaunchOptions"
-
-        // Find the value
-        let first_quote = after_key
-            .find('"')
-            .ok_or_else(|| "Invalid LaunchOptions format".to_string())?;
-        let value_start = abs_launch_pos + 15 + first_quote + 1;
-        let value_area = &content[value_start..];
-        let end_quote = value_area
-            .find('"')
-            .ok_or_else(|| "Invalid LaunchOptions format".to_string())?;
-
-        let existing_options = &content[value_start..value_start + end_quote];
-
-        // Build new launch options
-        let new_options = if existing_options.is_empty() {
-            "protonhax init %COMMAND%".to_string()
-        } else {
-            format!("protonhax init {} %COMMAND%", existing_options)
-        };
+// CPU use (of one core) at which a running program is highlighted
+const BUSY_CPU_PERCENT: f64 = 80.0;
 
-        // Replace the old value with the new one
-        format!(
-            "{}{}{}",
-            &content[..value_start],
-            new_options,
-            &content[value_start + end_quote..]
-        )
-    } else {
-        // LaunchOptions doesn't exist - add it
-        // Find a good place to insert (after the opening brace)
-        let insert_pos = section_start;
-
-        // Detect indentation by looking at the surrounding content
-        let indent = "\t\t\t\t\t\t\t";
-        let new_line = format!(
-            "\n{}\"LaunchOptions\"\t\t\"protonhax init %COMMAND%\"",
-            indent
-        );
+// How often appmanifests are checked for finished game updates
+const UPDATE_CHECK_SECS: u64 = 10;
 
-        format!(
-            "{}{}{}",
-            &content[..insert_pos],
-            new_line,
-            &content[insert_pos..]
-        )
-    };
+// How often Protonic looks whether the daily tool update check is due
+const TOOL_UPDATE_POLL_SECS: u64 = 60 * 60;
 
-    // Write the modified content back
-    fs::write(&localconfig_path, new_content)
-        .map_err(|e| format!("Failed to write localconfig.vdf: {}", e))?;
+// In low-resource mode, running sessions are refreshed this many times less often
+const LOW_RESOURCE_SLOWDOWN: u32 = 4;
 
-    Ok("Launch options configured successfully".to_string())
+// How long after Launch the overlay waits for the game to show that the hotkey is armed
+const OVERLAY_ARMED_WAIT_SECS: u32 = 300;
+
+/// Whether the in-game overlay should be shown for a game; do-not-disturb hides it too
+fn overlay_allowed(cfg: &AppConfig, app_id: &str) -> bool {
+    let do_not_disturb = cfg
+        .game_configs
+        .get(app_id)
+        .map(|g| g.do_not_disturb)
+        .unwrap_or(false);
+    cfg.overlay && !do_not_disturb
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let ui = AppWindow::new()?;
+/// Short name for an executable path, used in logs and the UI
+fn exe_display_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
 
-    // Load config from ~/.config/protonic/default-config.toml
-    let cfg: AppConfig = confy::load("protonic", None).unwrap_or_default();
+/// Describe a Steam library for the detail panel: its location and free space
+fn library_info(library: &Path) -> String {
+    match disk::free_space(library) {
+        Some(free) => format!(
+            "Library: {} ({} free)",
+            library.display(),
+            disk::format_size(free)
+        ),
+        None => format!("Library: {}", library.display()),
+    }
+}
 
-    // Use Arc<Mutex> for thread-safe config sharing
-    let config = Arc::new(Mutex::new(cfg));
+/// The Wine prefix (compatdata) directory for a game installed in `library`
+fn prefix_path(library: &Path, app_id: &str) -> PathBuf {
+    library.join("steamapps").join("compatdata").join(app_id)
+}
 
-    // Set initial UI state from config
+/// Show a folder in the file manager
+fn open_folder(dir: &Path) {
+    if !dir.is_dir() {
+        println!("Error: {} doesn't exist", dir.display());
+        return;
+    }
+    if let Err(e) = logs::quiet(Command::new("xdg-open").arg(dir))
+        .spawn()
+        .map(process::reap_in_background)
     {
-        let cfg = config.lock().unwrap();
-        ui.set_search_text(cfg.last_game_name.clone().into());
-        ui.set_app_id(cfg.last_app_id.clone().into());
-        ui.set_auto_configure(cfg.auto_configure);
+        println!("Error: Could not open {}: {}", dir.display(), e);
+    }
+}
+
+/// Games set to the native backend, which are seen running without a protonhax session
+fn native_games(cfg: &AppConfig) -> BTreeSet<String> {
+    cfg.game_configs
+        .iter()
+        .filter(|(_, game_cfg)| game_cfg.backend == backend::Backend::Native)
+        .map(|(app_id, _)| app_id.clone())
+        .collect()
+}
+
+/// What starts programs in a game's prefix, per the game's backend setting
+fn injector(
+    cfg: &AppConfig,
+    installed: &HashMap<String, InstalledGame>,
+    app_id: &str,
+) -> Result<backend::Injector, String> {
+    let backend = cfg
+        .game_configs
+        .get(app_id)
+        .map(|game_cfg| game_cfg.backend)
+        .unwrap_or_default();
+    let prefix = installed
+        .get(app_id)
+        .map(|game| prefix_path(&game.library, app_id));
+    backend::Injector::new(backend, app_id, prefix.as_deref())
+}
 
-        // Load exe paths for last selected game if any
-        if !cfg.last_app_id.is_empty() {
-            if let Some(game_cfg) = cfg.game_configs.get(&cfg.last_app_id) {
-                ui.set_exe1_path(game_cfg.exe1_path.clone().into());
-                ui.set_exe2_path(game_cfg.exe2_path.clone().into());
+/// Measure a game's prefix in the background and raise an alert in the UI if it is
+/// bigger than the configured limit
+fn check_prefix_size(
+    ui_handle: slint::Weak<AppWindow>,
+    app_id: String,
+    prefix: PathBuf,
+    limit_gb: u64,
+) {
+    if limit_gb == 0 || !prefix.is_dir() {
+        return;
+    }
+    // Only the last game selected is measured; scans queued for earlier ones are dropped
+    static LATEST: AtomicU64 = AtomicU64::new(0);
+    let check = LATEST.fetch_add(1, Ordering::SeqCst) + 1;
+    workers::spawn(move || {
+        if LATEST.load(Ordering::SeqCst) != check {
+            return;
+        }
+        let size = disk::dir_size(&prefix);
+        if size < limit_gb * 1024 * 1024 * 1024 {
+            return;
+        }
+        let alert = format!(
+            "This game's prefix is using {} (alert threshold {} GB). Trainers and logs can fill it up over time.",
+            disk::format_size(size),
+            limit_gb
+        );
+        println!("Warning: {}", alert);
+        let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+            // The user may have picked another game while we were counting
+            if ui.get_app_id() == app_id.as_str() {
+                ui.set_prefix_alert(alert.into());
             }
+        });
+    });
+}
 
-            // Check launch options status
-            if cfg.auto_configure {
-                let status = if has_protonhax_configured(&cfg.last_app_id) {
-                    "✓ Launch options configured"
-                } else {
-                    "Launch options will be configured on launch"
-                };
-                ui.set_launch_options_status(status.into());
+/// Fill the Proton dropdown with the installed tools and the one Steam runs the game with
+fn show_compat_tool(ui: &AppWindow, app_id: &str) {
+    let tools = proton::installed_tools();
+    let mut choices: Vec<SharedString> = vec![STEAM_DEFAULT_TOOL.into()];
+    choices.extend(
+        tools
+            .iter()
+            .map(|tool| SharedString::from(tool.label.as_str())),
+    );
+    let current = match proton::compat_tool(app_id) {
+        None => STEAM_DEFAULT_TOOL.to_string(),
+        Some(name) => match tools.iter().find(|tool| tool.name == name) {
+            Some(tool) => tool.label.clone(),
+            // Set in Steam but not installed any more
+            None => {
+                choices.push(name.as_str().into());
+                name
             }
-        }
+        },
+    };
+    ui.set_compat_tool_choices(ModelRc::from(Rc::new(VecModel::from(choices))));
+    ui.set_compat_tool_choice(current.into());
+}
+
+/// Switch the UI to a game and remember it as the last selection.
+/// `game` is None for app IDs that weren't found in any local Steam library.
+fn select_game(
+    ui: &AppWindow,
+    cfg: &mut AppConfig,
+    id: &str,
+    name: &str,
+    game: Option<&InstalledGame>,
+    safe_mode: bool,
+) {
+    ui.set_app_id(SharedString::from(id));
+    ui.set_launch_options_note(SharedString::new());
+    let info = game.map(|g| library_info(&g.library)).unwrap_or_default();
+    ui.set_library_info(info.into());
+    let details = game.map(|g| g.metadata.summary()).unwrap_or_default();
+    ui.set_game_details(details.into());
+    show_store_details(ui, cfg, id, game, safe_mode);
+    ui.set_prefix_alert(SharedString::new());
+    let prefix = game.map(|g| prefix_path(&g.library, id));
+    ui.set_prefix_exists(prefix.as_ref().is_some_and(|prefix| prefix.is_dir()));
+    ui.set_prefix_path(
+        prefix
+            .map(|prefix| prefix.to_string_lossy().into_owned())
+            .unwrap_or_default()
+            .into(),
+    );
+
+    let warning = match game {
+        None => format!(
+            "App {} isn't installed in a Steam library on this machine. \
+             Protonic will still try to configure and launch it.",
+            id
+        ),
+        Some(InstalledGame {
+            shared_from: Some(owner),
+            ..
+        }) => format!(
+            "Family Shared from another account ({}). Launch options set here only apply \
+             to this account, not the owner's.",
+            owner
+        ),
+        Some(_) => String::new(),
+    };
+    ui.set_app_warning(warning.into());
+
+    // Start from the game's default program set
+    if let Some(game_cfg) = cfg.game_configs.get_mut(id)
+        && game_cfg.use_default_program_set()
+    {
+        save_config(cfg);
     }
 
-    // Fetch list of installed Steam games
-    let mut games: BTreeMap<String, String> = BTreeMap::new();
-    if let Ok(steam_dir) = SteamDir::locate() {
-        if let Ok(library_iter) = steam_dir.libraries() {
-            for library in library_iter {
-                if let Ok(lib) = library {
-                    for app in lib.apps() {
-                        if let Ok(a) = app {
-                            if let Some(name) = &a.name {
-                                games.insert(name.clone(), a.app_id.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    // Load exe paths for selected game
+    let game_cfg = cfg.game_configs.get(id).cloned().unwrap_or_default();
+    ui.set_programs(program_rows(&game_cfg.programs));
+    show_program_sets(ui, &game_cfg);
+    ui.set_program_set_error(SharedString::new());
+    show_recent_programs(ui, cfg, id);
+    ui.set_recipe(recipe::to_text(&game_cfg.recipe).into());
+    ui.set_recipe_error(SharedString::new());
+    ui.set_registry_tweaks(registry::to_text(&game_cfg.registry).into());
+    ui.set_registry_error(SharedString::new());
+    ui.set_file_drops(file_drop_rows(&game_cfg.files));
+    ui.set_verbs_installed(game_cfg.verbs_installed.join(", ").into());
+    if !ui.get_winetricks_busy() {
+        ui.set_winetricks_status(SharedString::new());
     }
+    show_files_status(ui, id, false);
+    ui.set_do_not_disturb(game_cfg.do_not_disturb);
+    ui.set_refocus_game(game_cfg.refocus_game);
+    ui.set_recorder_choice(game_cfg.recorder.label().into());
+    ui.set_backend_choice(game_cfg.backend.label().into());
+    show_compat_tool(ui, id);
+    ui.set_hotkey(hotkey::label(&game_cfg.hotkey).into());
+    ui.set_hotkey_capturing(false);
+    ui.set_gamepad_trigger(game_cfg.gamepad_trigger.clone().into());
+    ui.set_gamepad_capturing(false);
+    ui.set_auto_launch(game_cfg.auto_launch);
+    ui.set_auto_launch_delay(game_cfg.auto_launch_delay_secs.to_string().into());
+    ui.set_cleanup_hooks(game_cfg.cleanup_hooks.join("\n").into());
+    ui.set_update_hooks(game_cfg.update_hooks.join("\n").into());
+    ui.set_game_links(link_rows(&game_cfg.links));
+    ui.set_link_error(SharedString::new());
+    ui.set_pcgw_available(!shortcuts::is_shortcut(id));
+    ui.set_pcgw_summary(
+        pcgw::cached(id)
+            .map(|summary| summary.to_text())
+            .unwrap_or_default()
+            .into(),
+    );
 
-    let all_game_names: Vec<String> = games.keys().cloned().collect();
+    let template = wrapper_template(cfg, id);
+    if wrapper::PRESETS.contains(&template.as_str()) {
+        ui.set_wrapper_choice(template.clone().into());
+    } else {
+        ui.set_wrapper_choice(CUSTOM_WRAPPER.into());
+    }
+    show_mangohud_config(ui, &template);
+    ui.set_wrapper_template(template.into());
+    ui.set_game_env(game_cfg.env.join(" ").into());
+    ui.set_game_env_error(SharedString::new());
+    ui.set_default_env(cfg.default_env.join(" ").into());
 
-    // Initial population of the list (filtered by saved search text if any)
-    let initial_search = config.lock().unwrap().last_game_name.to_lowercase();
-    let initial_filtered: Vec<SharedString> = all_game_names
-        .iter()
-        .filter(|n| n.to_lowercase().contains(&initial_search))
-        .map(|n| SharedString::from(n))
-        .collect();
-    ui.set_game_names(ModelRc::from(Rc::new(VecModel::from(initial_filtered))));
+    // Update launch options status
+    if cfg.auto_configure && !safe_mode {
+        ui.set_launch_options_status(launch_options_status(cfg, id).into());
+    }
 
-    // Search Callback (to filter the game list as you type)
-    let ui_handle_search = ui.as_weak();
-    let names_for_search = all_game_names.clone();
-    ui.on_search_edited(move |text| {
-        if let Some(ui) = ui_handle_search.upgrade() {
-            let search_term = text.to_lowercase();
-            let filtered: Vec<SharedString> = names_for_search
-                .iter()
-                .filter(|n| n.to_lowercase().contains(&search_term))
-                .map(|n| SharedString::from(n))
-                .collect();
-            ui.set_game_names(ModelRc::from(Rc::new(VecModel::from(filtered))));
-        }
-    });
+    if let Some(game) = game
+        && !safe_mode
+    {
+        check_prefix_size(
+            ui.as_weak(),
+            id.to_string(),
+            prefix_path(&game.library, id),
+            cfg.prefix_size_alert_gb,
+        );
+    }
 
-    // Game Selection Callback
-    let ui_handle_select = ui.as_weak();
-    let games_clone = games.clone();
-    let config_select = Arc::clone(&config);
-    ui.on_game_selected(move |name| {
-        if let Some(ui) = ui_handle_select.upgrade() {
-            if let Some(id) = games_clone.get(name.as_str()) {
-                ui.set_app_id(SharedString::from(id));
+    // Save last selected game
+    cfg.last_game_name = name.to_string();
+    cfg.last_app_id = id.to_string();
+    save_config(cfg);
+}
 
-                let mut cfg = config_select.lock().unwrap();
+fn set_capsule(ui: &AppWindow, pixels: Option<SharedPixelBuffer<Rgba8Pixel>>) {
+    let image = pixels.map(Image::from_rgba8);
+    ui.set_has_capsule(image.is_some());
+    ui.set_capsule(image.unwrap_or_default());
+}
 
-                // Load exe paths for selected game
-                let game_cfg = cfg.game_configs.get(id).cloned().unwrap_or_default();
-                ui.set_exe1_path(game_cfg.exe1_path.into());
-                ui.set_exe2_path(game_cfg.exe2_path.into());
+// Games whose store details are being fetched, so selecting one again doesn't fetch twice
+static FETCHING_DETAILS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
 
-                // Update launch options status
-                if cfg.auto_configure {
-                    let status = if has_protonhax_configured(id) {
-                        "✓ Launch options configured"
-                    } else {
-                        "Launch options will be configured on launch"
-                    };
-                    ui.set_launch_options_status(status.into());
-                }
+/// Fill in capsule art and the store description. Offline sources come first;
+/// the Web API is only asked about games Steam has no local data for. The files are
+/// read and decoded on the workers, and dropped if another game was picked meanwhile.
+fn show_store_details(
+    ui: &AppWindow,
+    cfg: &AppConfig,
+    id: &str,
+    game: Option<&InstalledGame>,
+    safe_mode: bool,
+) {
+    // Nothing from the previous game stays up while this one's art loads
+    set_capsule(ui, None);
+    ui.set_store_description(SharedString::new());
 
-                // Save last selected game
-                cfg.last_game_name = name.to_string();
-                cfg.last_app_id = id.clone();
-                let _ = confy::store("protonic", None, &*cfg);
-            }
+    // Held back while a game runs, it's fetched the next time the game is selected
+    let may_fetch =
+        cfg.online_metadata && !cfg.steam_api_key.is_empty() && !safe_mode && !workers::paused();
+    let no_summary = game.is_none_or(|g| g.metadata.summary().is_empty());
+    let api_key = cfg.steam_api_key.clone();
+    let ui_weak = ui.as_weak();
+    let app_id = id.to_string();
+    workers::spawn_requested(move || {
+        // Non-Steam games only have the artwork picked for them in Steam
+        if shortcuts::is_shortcut(&app_id) {
+            let pixels = shortcuts::grid_image(&app_id).and_then(|path| decode_image(&path));
+            let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+                if ui.get_app_id() == app_id.as_str() {
+                    set_capsule(&ui, pixels);
+                }
+            });
+            return;
         }
-    });
-
-    // Auto-configure toggle callback
-    let ui_handle_toggle = ui.as_weak();
-    let config_toggle = Arc::clone(&config);
-    ui.on_auto_configure_toggled(move |enabled| {
-        if let Some(ui) = ui_handle_toggle.upgrade() {
-            let mut cfg = config_toggle.lock().unwrap();
-            cfg.auto_configure = enabled;
-            let _ = confy::store("protonic", None, &*cfg);
 
-            // Update status display
-            if enabled && !cfg.last_app_id.is_empty() {
-                let status = if has_protonhax_configured(&cfg.last_app_id) {
-                    "✓ Launch options configured"
-                } else {
-                    "Launch options will be configured on launch"
-                };
-                ui.set_launch_options_status(status.into());
-            } else {
-                ui.set_launch_options_status(SharedString::new());
+        let local_capsule = steam::path().and_then(|steam| store::local_capsule(&steam, &app_id));
+        let cached = store::cached_details(&app_id);
+        let capsule = local_capsule
+            .clone()
+            .or_else(|| cached.as_ref().and_then(|d| d.capsule_path.clone()));
+        let pixels = capsule.as_deref().and_then(decode_image);
+        let description = cached
+            .as_ref()
+            .map(|d| d.description.clone())
+            .unwrap_or_default();
+        let shown_id = app_id.clone();
+        let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+            if ui.get_app_id() == shown_id.as_str() {
+                set_capsule(&ui, pixels);
+                ui.set_store_description(description.into());
             }
+        });
+
+        let missing_local_data = local_capsule.is_none() || no_summary;
+        if cached.is_some()
+            || !missing_local_data
+            || !may_fetch
+            || !FETCHING_DETAILS.lock().unwrap().insert(app_id.clone())
+        {
+            return;
         }
+        // Network waits don't hold up a disk worker
+        thread::spawn(move || {
+            let fetched = store::fetch_details(&app_id, &api_key);
+            FETCHING_DETAILS.lock().unwrap().remove(&app_id);
+            let details = match fetched {
+                Ok(details) => details,
+                Err(e) => {
+                    println!("Warning: {}", e);
+                    return;
+                }
+            };
+            let pixels = match (&local_capsule, &details.capsule_path) {
+                (None, Some(path)) => decode_image(path),
+                _ => None,
+            };
+            let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+                // The user may have picked another game while we were fetching
+                if ui.get_app_id() != app_id.as_str() {
+                    return;
+                }
+                if local_capsule.is_none() {
+                    set_capsule(&ui, pixels);
+                }
+                ui.set_store_description(details.description.into());
+            });
+        });
     });
+}
 
-    // Browse user's exe 1 Callback
-    let ui_handle_browse1 = ui.as_weak();
-    let config_browse1 = Arc::clone(&config);
-    ui.on_browse_exe1(move || {
-        if let Some(ui) = ui_handle_browse1.upgrade() {
-            let app_id = ui.get_app_id().to_string();
-            if app_id.is_empty() {
-                return;
-            }
+/// Sessions of anti-cheat protected games the user agreed to run programs in, by app ID
+/// and when the session started
+type InjectionOverrides = Mutex<HashSet<(String, std::time::SystemTime)>>;
 
-            if let Some(path) = FileDialog::new()
-                .add_filter("Executables", &["exe"])
-                .add_filter("All Files", &["*"])
-                .pick_file()
-            {
-                let path_str = path.to_string_lossy().to_string();
-                ui.set_exe1_path(path_str.clone().into());
+/// Ask before running programs inside an anti-cheat protected game's session.
+/// A yes holds until the game is restarted, as far as its backend can tell.
+fn allow_injection(
+    app_id: &str,
+    game_name: &str,
+    backend: backend::Backend,
+    anti_cheat: anticheat::AntiCheat,
+    overrides: &InjectionOverrides,
+) -> bool {
+    let session = backend
+        .session_started(app_id)
+        .map(|started| (app_id.to_string(), started));
+    if let Some(session) = &session
+        && overrides.lock().unwrap().contains(session)
+    {
+        return true;
+    }
 
-                // Save to config
-                let mut cfg = config_browse1.lock().unwrap();
-                let game_cfg = cfg.game_configs.entry(app_id).or_default();
-                game_cfg.exe1_path = path_str;
-                let _ = confy::store("protonic", None, &*cfg);
-            }
+    let answer = MessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("Anti-cheat protected game")
+        .set_description(format!(
+            "{} uses {}, which can see programs started inside the game. Running them \
+             in online matches can get your account banned.\n\n\
+             Start them anyway for this session?",
+            game_name,
+            anti_cheat.label()
+        ))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    if answer != MessageDialogResult::Yes {
+        println!(
+            "{} uses {}, not starting programs in it",
+            game_name,
+            anti_cheat.label()
+        );
+        return false;
+    }
+    println!(
+        "Warning: Starting programs in {} despite {}, as requested",
+        game_name,
+        anti_cheat.label()
+    );
+    if let Some(session) = session {
+        overrides.lock().unwrap().insert(session);
+    }
+    true
+}
+
+/// Ask to install missing winetricks verbs. Returns true if the install was started,
+/// in which case the launch should wait until it has finished.
+fn offer_verb_install(
+    app_id: &str,
+    prefix: &Path,
+    missing: &[String],
+    config: &Arc<Mutex<AppConfig>>,
+) -> bool {
+    let answer = MessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("Missing winetricks verbs")
+        .set_description(format!(
+            "Your programs need {} in this game's prefix, which isn't installed yet.\n\n\
+             Install now? Launch again once the installation has finished.",
+            missing.join(", ")
+        ))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    if answer != MessageDialogResult::Yes {
+        return false;
+    }
+
+    let preference = config.lock().unwrap().terminal.clone();
+    let result = winetricks::install_command(app_id, prefix, missing)
+        .and_then(|command| terminal::spawn(&preference, &command));
+    match result {
+        Ok(()) => {
+            println!("Installing {} into {}", missing.join(" "), prefix.display());
+            true
+        }
+        Err(e) => {
+            println!("Warning: Could not install winetricks verbs: {}", e);
+            false
+        }
+    }
+}
+
+fn recipe_rows(progress: &[recipe::StepProgress]) -> ModelRc<RecipeStepRow> {
+    let rows: Vec<RecipeStepRow> = progress
+        .iter()
+        .map(|step| RecipeStepRow {
+            label: step.label.clone().into(),
+            state: match step.state {
+                recipe::StepState::Pending => "pending",
+                recipe::StepState::Running => "running",
+                recipe::StepState::Succeeded => "succeeded",
+                recipe::StepState::Failed => "failed",
+                recipe::StepState::Skipped => "skipped",
+            }
+            .into(),
+            duration: step
+                .duration
+                .map(|duration| format!("{:.1} s", duration.as_secs_f64()))
+                .unwrap_or_default()
+                .into(),
+            error: step.error.clone().into(),
+        })
+        .collect();
+    ModelRc::from(Rc::new(VecModel::from(rows)))
+}
+
+/// Newer releases found for tools in the library, by tool name
+type ToolUpdates = Arc<Mutex<HashMap<String, tools::Release>>>;
+
+fn tool_rows(tools: &[tools::Tool], updates: &HashMap<String, tools::Release>) -> ModelRc<ToolRow> {
+    let rows: Vec<ToolRow> = tools
+        .iter()
+        .map(|tool| ToolRow {
+            name: tool.name.clone().into(),
+            version: tool.version.clone().into(),
+            url: tool.url.clone().into(),
+            downloaded: tool.program_path().is_some(),
+            latest: updates
+                .get(&tool.name)
+                .map(|release| release.version.clone())
+                .unwrap_or_default()
+                .into(),
+        })
+        .collect();
+    ModelRc::from(Rc::new(VecModel::from(rows)))
+}
+
+/// Refresh the tool list and the update badge on the main window
+fn refresh_tools(
+    ui: &AppWindow,
+    window: &ToolsWindow,
+    config: &Mutex<AppConfig>,
+    updates: &ToolUpdates,
+) {
+    let updates = updates.lock().unwrap();
+    window.set_rows(tool_rows(&config.lock().unwrap().tools, &updates));
+    ui.set_tool_updates(updates.len() as i32);
+}
+
+/// Download a tool in the background and report back in the tool window
+fn start_tool_download(
+    ui_weak: slint::Weak<AppWindow>,
+    window_weak: slint::Weak<ToolsWindow>,
+    config: Arc<Mutex<AppConfig>>,
+    updates: ToolUpdates,
+    tool: tools::Tool,
+) {
+    if let Some(window) = window_weak.upgrade() {
+        window.set_status(format!("Downloading {}...", tool.name).into());
+    }
+    thread::spawn(move || {
+        let status = match tools::download(&tool) {
+            // Tools added before checksums were required
+            Ok(path) if tool.sha256.is_empty() => format!(
+                "Downloaded {} to {}, unverified since it has no checksum",
+                tool.name,
+                path.display()
+            ),
+            Ok(path) => format!("Downloaded {} to {}", tool.name, path.display()),
+            Err(e) => format!("Could not download {}: {}", tool.name, e),
+        };
+        println!("{}", status);
+        let _ = window_weak.upgrade_in_event_loop(move |window| {
+            if let Some(ui) = ui_weak.upgrade() {
+                refresh_tools(&ui, &window, &config, &updates);
+            }
+            window.set_status(status.into());
+        });
+    });
+}
+
+/// Download a tool's new release in the background. Games keep using the old version
+/// until the new one is downloaded and verified; if that fails, the update is offered
+/// again.
+fn start_tool_update(
+    ui_weak: slint::Weak<AppWindow>,
+    window_weak: slint::Weak<ToolsWindow>,
+    config: Arc<Mutex<AppConfig>>,
+    updates: ToolUpdates,
+    tool: tools::Tool,
+    release: tools::Release,
+) {
+    if let Some(window) = window_weak.upgrade() {
+        window.set_status(format!("Downloading {} {}...", tool.name, release.version).into());
+    }
+    let updated = tools::Tool {
+        version: release.version.clone(),
+        url: release.url.clone(),
+        sha256: release.sha256.clone(),
+        ..tool.clone()
+    };
+    thread::spawn(move || {
+        let status = match tools::download(&updated) {
+            Ok(path) => {
+                let mut cfg = config.lock().unwrap();
+                if let Some(entry) = cfg.tools.iter_mut().find(|entry| entry.name == tool.name) {
+                    *entry = updated.clone();
+                    save_config(&cfg);
+                }
+                format!(
+                    "Updated {} to {} ({})",
+                    tool.name,
+                    updated.version,
+                    path.display()
+                )
+            }
+            Err(e) => {
+                // Don't leave a half-downloaded version behind
+                if updated.install_dir() != tool.install_dir() {
+                    tools::remove_version(&updated);
+                }
+                updates.lock().unwrap().insert(tool.name.clone(), release);
+                format!(
+                    "Could not update {}, still using {}: {}",
+                    tool.name, tool.version, e
+                )
+            }
+        };
+        println!("{}", status);
+        let _ = window_weak.upgrade_in_event_loop(move |window| {
+            if let Some(ui) = ui_weak.upgrade() {
+                refresh_tools(&ui, &window, &config, &updates);
+            }
+            window.set_status(status.into());
+        });
+    });
+}
+
+/// Look for new GitHub releases of every tool in the background
+fn start_tool_update_check(
+    ui_weak: slint::Weak<AppWindow>,
+    window_weak: slint::Weak<ToolsWindow>,
+    config: Arc<Mutex<AppConfig>>,
+    updates: ToolUpdates,
+) {
+    let tools = config.lock().unwrap().tools.clone();
+    thread::spawn(move || {
+        let mut found = HashMap::new();
+        for tool in &tools {
+            match tools::check_for_update(tool) {
+                Ok(Some(release)) => {
+                    println!("{} {} is available", tool.name, release.version);
+                    found.insert(tool.name.clone(), release);
+                }
+                Ok(None) => {}
+                Err(e) => println!("Warning: {}", e),
+            }
+        }
+        *updates.lock().unwrap() = found;
+        {
+            let mut cfg = config.lock().unwrap();
+            cfg.tools_checked_at = journal::now();
+            save_config(&cfg);
+        }
+        let _ = window_weak.upgrade_in_event_loop(move |window| {
+            if let Some(ui) = ui_weak.upgrade() {
+                refresh_tools(&ui, &window, &config, &updates);
+            }
+        });
+    });
+}
+
+/// Check whether a program can be found in PATH
+fn command_in_path(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+/// Every program path and working directory in the config, recipes included
+fn config_paths(cfg: &AppConfig) -> Vec<String> {
+    let paths = RefCell::new(Vec::new());
+    for game_cfg in cfg.game_configs.values() {
+        for program in &game_cfg.programs {
+            paths.borrow_mut().push(program.path.clone());
+            if let Some(dir) = &program.working_dir {
+                paths.borrow_mut().push(dir.to_string_lossy().into_owned());
+            }
+        }
+        let mut recipe = game_cfg.recipe.clone();
+        let _ = recipe::resolve_programs(&mut recipe, &|path: &str| {
+            paths.borrow_mut().push(path.to_string());
+            Ok(path.to_string())
+        });
+    }
+    paths.into_inner()
+}
+
+/// Offer rules for program paths that came from another computer and don't exist here.
+/// Declined rules are kept switched off so they aren't offered again.
+fn offer_path_remaps(cfg: &mut AppConfig) {
+    let suggestions = remap::suggest(&config_paths(cfg), &cfg.path_remaps);
+    if suggestions.is_empty() {
+        return;
+    }
+    let total: usize = suggestions.iter().map(|(_, count)| count).sum();
+    let list: Vec<String> = suggestions
+        .iter()
+        .map(|(rule, count)| format!("{}  →  {}  ({} paths)", rule.from, rule.to, count))
+        .collect();
+    let answer = MessageDialog::new()
+        .set_title("Paths from another computer")
+        .set_description(format!(
+            "{} program paths don't exist on this computer, but would with:\n\n{}\n\n\
+             Look for them there? The paths in the config stay as they are.",
+            total,
+            list.join("\n")
+        ))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    let accepted = answer == MessageDialogResult::Yes;
+    for (mut rule, _) in suggestions {
+        rule.enabled = accepted;
+        cfg.path_remaps.push(rule);
+    }
+    save_config(cfg);
+}
+
+/// Accounts that have logged in to the Steam installation in use
+fn steam_accounts() -> Vec<steam::Account> {
+    match steam::locate().map(|steam_dir| steam::accounts(steam_dir.path())) {
+        Ok(Ok(accounts)) => accounts,
+        Ok(Err(e)) | Err(e) => {
+            println!("Warning: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Fill the account picker; it's only shown when more than one account has logged in
+fn show_steam_accounts(ui: &AppWindow, cfg: &AppConfig) {
+    let accounts = steam_accounts();
+    let labels: Vec<SharedString> = accounts
+        .iter()
+        .map(|account| SharedString::from(account.label()))
+        .collect();
+    ui.set_steam_accounts(ModelRc::from(Rc::new(VecModel::from(labels))));
+    let current = accounts
+        .iter()
+        .position(|account| account.steam_id64 == cfg.steam_id)
+        .or_else(|| accounts.iter().position(|account| account.most_recent))
+        .unwrap_or(0);
+    ui.set_steam_account_index(current as i32);
+}
+
+/// Get the path to localconfig.vdf
+fn get_localconfig_path() -> Option<PathBuf> {
+    let userdata = steam::userdata_path()?;
+    let localconfig = userdata.join("config").join("localconfig.vdf");
+    if localconfig.exists() {
+        Some(localconfig)
+    } else {
+        None
+    }
+}
+
+/// Key path of a game's section inside localconfig.vdf
+const LOCALCONFIG_APPS_PATH: [&str; 5] =
+    ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"];
+
+// UI scales offered in the settings, in percent
+const UI_SCALES: [u32; 6] = [75, 100, 125, 150, 175, 200];
+const UI_SCALE_AUTO: &str = "Automatic";
+
+/// A game's LaunchOptions in parsed localconfig.vdf, None if it has none yet.
+/// Fails if Steam hasn't written a section for the game.
+fn launch_options_in(root: &vdf::Node, app_id: &str) -> Result<Option<String>, String> {
+    let mut path = LOCALCONFIG_APPS_PATH.to_vec();
+    path.push(app_id);
+    match root.get_path(&path) {
+        Some(section @ vdf::Node::Section(_)) => match section.get("LaunchOptions") {
+            Some(vdf::Node::Value(options)) => Ok(Some(options.clone())),
+            _ => Ok(None),
+        },
+        _ => Err(
+            "Game not found in Steam config. Launch the game from Steam at least once first."
+                .to_string(),
+        ),
+    }
+}
+
+/// Read and parse localconfig.vdf
+fn read_localconfig() -> Result<(PathBuf, vdf::Node), String> {
+    let path =
+        get_localconfig_path().ok_or_else(|| "Could not find Steam localconfig.vdf".to_string())?;
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read localconfig.vdf: {}", e))?;
+    let root =
+        vdf::parse(&content).map_err(|e| format!("Could not parse localconfig.vdf: {}", e))?;
+    Ok((path, root))
+}
+
+/// Current launch options for a game, None if none are set (or Steam's config can't be read)
+fn read_launch_options(app_id: &str) -> Option<String> {
+    if shortcuts::is_shortcut(app_id) {
+        return shortcuts::launch_options(app_id);
+    }
+    let (_, root) = read_localconfig().ok()?;
+    launch_options_in(&root, app_id).ok()?
+}
+
+/// Stop one of a game's programs (`pid`), or all of them, off the UI thread since it
+/// waits out the kill grace period. The game's cleanup hooks run once none are left.
+fn stop_in_background(tracker: &ProcessTracker, cfg: &AppConfig, app_id: &str, pid: Option<u32>) {
+    let grace = std::time::Duration::from_millis(cfg.tunables.kill_grace_ms);
+    let hooks = cfg
+        .game_configs
+        .get(app_id)
+        .map(|game_cfg| game_cfg.cleanup_hooks.clone())
+        .unwrap_or_default();
+    let tracker = tracker.clone();
+    let app_id = app_id.to_string();
+    thread::spawn(move || {
+        let stopped = match pid {
+            Some(pid) => tracker.kill_pid(pid, grace),
+            None => tracker.kill_app(&app_id, grace),
+        };
+        if stopped && tracker.running_names(&app_id).is_empty() {
+            process::run_cleanup_hooks(&hooks);
+        }
+    });
+}
+
+/// Chips for a game's links
+fn link_rows(links: &[links::Link]) -> ModelRc<GameLink> {
+    let rows: Vec<GameLink> = links
+        .iter()
+        .map(|link| GameLink {
+            title: link.label().into(),
+            url: link.url.clone().into(),
+        })
+        .collect();
+    ModelRc::from(Rc::new(VecModel::from(rows)))
+}
+
+/// Rows for the program list
+fn program_rows(programs: &[ExeEntry]) -> ModelRc<ProgramRow> {
+    let rows: Vec<ProgramRow> = programs
+        .iter()
+        .map(|program| ProgramRow {
+            path: program.path.clone().into(),
+            verbs: program.verbs.join(" ").into(),
+            args: join_args(&program.args).into(),
+            working_dir: program
+                .working_dir
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .unwrap_or_default()
+                .into(),
+            stage: program.stage.label().into(),
+            start_after: program.start_after_secs.to_string().into(),
+            delay: program
+                .delay_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default()
+                .into(),
+            env: ModelRc::from(Rc::new(VecModel::from(
+                program
+                    .env
+                    .iter()
+                    .map(|var| EnvVarRow {
+                        name: var.name.clone().into(),
+                        value: var.value.clone().into(),
+                    })
+                    .collect::<Vec<_>>(),
+            ))),
+        })
+        .collect();
+    ModelRc::from(Rc::new(VecModel::from(rows)))
+}
+
+fn file_drop_rows(drops: &[deploy::FileDrop]) -> ModelRc<FileDropRow> {
+    let rows: Vec<FileDropRow> = drops
+        .iter()
+        .map(|drop| FileDropRow {
+            source: drop.source.clone().into(),
+            destination: drop.destination.clone().into(),
+        })
+        .collect();
+    ModelRc::from(Rc::new(VecModel::from(rows)))
+}
+
+/// How many of the game's files are in place, e.g. "3 files copied into the game", and
+/// whether any were changed since
+fn files_status(app_id: &str) -> String {
+    let changed = deploy::changed(app_id).len();
+    if changed > 0 {
+        return format!(
+            "{} of the copied files were changed or removed (a game update?), Copy now repairs them",
+            changed
+        );
+    }
+    match deploy::deployed_count(app_id) {
+        0 => String::new(),
+        1 => "1 file copied into the game, unchanged".to_string(),
+        count => format!("{} files copied into the game, unchanged", count),
+    }
+}
+
+/// Check the game's copied files in the background, hashing them takes a while
+fn show_files_status(ui: &AppWindow, app_id: &str, verify: bool) {
+    ui.set_files_status(if verify {
+        "Checking the copied files...".into()
+    } else {
+        SharedString::new()
+    });
+    let ui_handle = ui.as_weak();
+    let app_id = app_id.to_string();
+    let job = move || {
+        let status = files_status(&app_id);
+        let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+            if ui.get_app_id() != app_id.as_str() {
+                return;
+            }
+            ui.set_files_status(if status.is_empty() && verify {
+                "No files copied yet".into()
+            } else {
+                status.into()
+            });
+        });
+    };
+    if verify {
+        workers::spawn_requested(job);
+    } else {
+        workers::spawn(job);
+    }
+}
+
+/// The game's program sets, for the dropdown next to Launch
+fn show_program_sets(ui: &AppWindow, game_cfg: &GameConfig) {
+    let names: Vec<SharedString> = game_cfg
+        .program_set_names()
+        .into_iter()
+        .map(SharedString::from)
+        .collect();
+    ui.set_program_set_choices(ModelRc::from(Rc::new(VecModel::from(names))));
+    ui.set_program_set_choice(game_cfg.active_program_set().into());
+    ui.set_program_set_is_default(game_cfg.program_set == game_cfg.default_program_set);
+}
+
+/// Change the selected game's program sets, save them and show the set in use
+fn update_program_sets(
+    ui: &AppWindow,
+    config: &Mutex<AppConfig>,
+    change: impl FnOnce(&mut GameConfig) -> Result<(), String>,
+) {
+    let app_id = ui.get_app_id().to_string();
+    if app_id.is_empty() {
+        return;
+    }
+    let mut cfg = config.lock().unwrap();
+    let game_cfg = cfg.game_configs.entry(app_id).or_default();
+    let result = change(game_cfg);
+    ui.set_programs(program_rows(&game_cfg.programs));
+    show_program_sets(ui, game_cfg);
+    match result {
+        Ok(()) => {
+            ui.set_program_set_error(SharedString::new());
+            save_config(&cfg);
+        }
+        Err(e) => ui.set_program_set_error(e.into()),
+    }
+}
+
+/// Change the selected game's program list, save it and show the result
+fn update_programs(
+    ui: &AppWindow,
+    config: &Mutex<AppConfig>,
+    change: impl FnOnce(&mut Vec<ExeEntry>),
+) {
+    let app_id = ui.get_app_id().to_string();
+    if app_id.is_empty() {
+        return;
+    }
+    let mut cfg = config.lock().unwrap();
+    let game_cfg = cfg.game_configs.entry(app_id.clone()).or_default();
+    let before: Vec<String> = game_cfg.programs.iter().map(|p| p.path.clone()).collect();
+    change(&mut game_cfg.programs);
+    ui.set_programs(program_rows(&game_cfg.programs));
+    let added: Vec<String> = game_cfg
+        .programs
+        .iter()
+        .map(|program| program.path.clone())
+        .filter(|path| !path.is_empty() && !before.contains(path))
+        .collect();
+    for path in added {
+        cfg.recent_programs.retain(|recent| *recent != path);
+        cfg.recent_programs.insert(0, path);
+    }
+    cfg.recent_programs.truncate(RECENT_PROGRAMS_LIMIT);
+    show_recent_programs(ui, &cfg, &app_id);
+    save_config(&cfg);
+}
+
+/// Recently used programs the game doesn't have yet, most recent first
+fn recent_programs(cfg: &AppConfig, app_id: &str) -> Vec<String> {
+    let used: Vec<&str> = cfg
+        .game_configs
+        .get(app_id)
+        .map(|game_cfg| game_cfg.programs.iter().map(|p| p.path.as_str()).collect())
+        .unwrap_or_default();
+    cfg.recent_programs
+        .iter()
+        .filter(|path| !used.contains(&path.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Fill the "Recent" dropdowns, by file name
+fn show_recent_programs(ui: &AppWindow, cfg: &AppConfig, app_id: &str) {
+    let names: Vec<SharedString> = recent_programs(cfg, app_id)
+        .iter()
+        .map(|path| exe_display_name(path).into())
+        .collect();
+    ui.set_recent_programs(ModelRc::from(Rc::new(VecModel::from(names))));
+}
+
+/// Pick an executable with the file dialog
+fn pick_program() -> Option<String> {
+    FileDialog::new()
+        .add_filter("Executables", &["exe"])
+        .add_filter("All Files", &["*"])
+        .pick_file()
+        .map(|path| portal::host_path(path).to_string_lossy().to_string())
+}
+
+/// Show the MangoHud row for templates that use MangoHud, with the config they set
+fn show_mangohud_config(ui: &AppWindow, template: &str) {
+    ui.set_mangohud_enabled(wrapper::uses_mangohud(template));
+    ui.set_mangohud_config(wrapper::mangohud_config(template).into());
+}
+
+/// Show launch options in the editor as toggles. Variables that are off keep the value
+/// they were last turned on with.
+fn show_launch_options(window: &LaunchOptionsWindow, options: &str) {
+    let parts = wrapper::Parts::parse(options);
+    window.set_gamemode(parts.wrapper("gamemoderun").is_some());
+    window.set_mangohud(parts.wrapper("mangohud").is_some());
+    window.set_protonhax(parts.wrapper("protonhax").is_some());
+    window.set_gamescope(parts.wrapper("gamescope").is_some());
+    if let Some(args) = parts.wrapper("gamescope") {
+        let gamescope = wrapper::Gamescope::from_args(args);
+        window.set_gamescope_width(gamescope.width.into());
+        window.set_gamescope_height(gamescope.height.into());
+        window.set_gamescope_refresh(gamescope.refresh.into());
+        window.set_gamescope_fullscreen(gamescope.fullscreen);
+    }
+    let shown = window.get_env();
+    let rows: Vec<EnvToggle> = wrapper::COMMON_ENV
+        .iter()
+        .enumerate()
+        .map(|(index, (name, value, description))| {
+            let set = parts.env(name);
+            let kept = shown
+                .row_data(index)
+                .map(|row| row.value)
+                .filter(|value| !value.is_empty());
+            EnvToggle {
+                name: (*name).into(),
+                value: set
+                    .map(SharedString::from)
+                    .or(kept)
+                    .unwrap_or_else(|| (*value).into()),
+                description: (*description).into(),
+                enabled: set.is_some(),
+            }
+        })
+        .collect();
+    window.set_env(ModelRc::from(Rc::new(VecModel::from(rows))));
+    window.set_problems(wrapper::conflicts(options).join("\n").into());
+}
+
+/// Rewrite the editor's launch options after a toggle changed
+fn apply_launch_option_toggles(window: &LaunchOptionsWindow) {
+    let mut parts = wrapper::Parts::parse(&window.get_options());
+    for (program, enabled, args) in [
+        ("gamemoderun", window.get_gamemode(), Vec::new()),
+        ("mangohud", window.get_mangohud(), Vec::new()),
+        (
+            "protonhax",
+            window.get_protonhax(),
+            vec!["init".to_string()],
+        ),
+    ] {
+        // A wrapper that's already there keeps the arguments it had
+        if !enabled {
+            parts.set_wrapper(program, None);
+        } else if parts.wrapper(program).is_none() {
+            parts.set_wrapper(program, Some(&args));
+        }
+    }
+    if window.get_gamescope() {
+        let gamescope = wrapper::Gamescope {
+            width: window.get_gamescope_width().to_string(),
+            height: window.get_gamescope_height().to_string(),
+            refresh: window.get_gamescope_refresh().to_string(),
+            fullscreen: window.get_gamescope_fullscreen(),
+        };
+        let args = gamescope.apply(parts.wrapper("gamescope").unwrap_or_default());
+        parts.set_wrapper("gamescope", Some(&args));
+    } else {
+        parts.set_wrapper("gamescope", None);
+    }
+    for row in window.get_env().iter() {
+        let value = row.value.trim();
+        parts.set_env(
+            &row.name,
+            (row.enabled && !value.is_empty()).then_some(value),
+        );
+    }
+    let options = parts.to_string();
+    window.set_problems(wrapper::conflicts(&options).join("\n").into());
+    window.set_options(options.into());
+    window.set_unsaved(true);
+}
+
+/// Load the editor's game's launch options from Steam, dropping unsaved changes
+fn load_launch_options(window: &LaunchOptionsWindow) {
+    let options = read_launch_options(&window.get_app_id()).unwrap_or_default();
+    show_launch_options(window, &options);
+    window.set_options(options.into());
+    window.set_unsaved(false);
+    window.set_status(SharedString::new());
+}
+
+/// The wrapper games use when they haven't picked their own
+fn default_wrapper_template(cfg: &AppConfig) -> String {
+    if cfg.default_wrapper.is_empty() {
+        wrapper::DEFAULT_TEMPLATE.to_string()
+    } else {
+        cfg.default_wrapper.clone()
+    }
+}
+
+/// A game's wrapper template, as shown in the wrapper picker
+fn wrapper_template(cfg: &AppConfig, app_id: &str) -> String {
+    cfg.game_configs
+        .get(app_id)
+        .map(|game_cfg| game_cfg.wrapper.clone())
+        .filter(|wrapper| !wrapper.is_empty())
+        .unwrap_or_else(|| default_wrapper_template(cfg))
+}
+
+/// The launch options template auto-configure uses for a game: its wrapper with the
+/// default env and the game's own env in front. The game's variables win over the defaults.
+fn launch_template(cfg: &AppConfig, app_id: &str) -> String {
+    let game_env = cfg
+        .game_configs
+        .get(app_id)
+        .map(|game_cfg| game_cfg.env.clone())
+        .unwrap_or_default();
+    let mut template = wrapper_template(cfg, app_id);
+    // The native backend finds the game by itself, protonhax isn't needed
+    if cfg
+        .game_configs
+        .get(app_id)
+        .is_some_and(|game_cfg| game_cfg.backend == backend::Backend::Native)
+    {
+        template = wrapper::without_protonhax(&template);
+    }
+    let template = wrapper::with_env(&template, &game_env);
+    wrapper::with_env(&template, &cfg.default_env)
+}
+
+/// Status line for the selected game's launch options, including wrapper conflicts
+fn launch_options_status(cfg: &AppConfig, app_id: &str) -> String {
+    let template = launch_template(cfg, app_id);
+    let existing = read_launch_options(app_id).unwrap_or_default();
+    let configured = wrapper::is_configured(&template, &existing);
+    let options = if configured {
+        existing
+    } else {
+        match wrapper::apply(&template, &existing) {
+            Ok(options) => options,
+            Err(e) => return format!("⚠ {}", e),
+        }
+    };
+
+    let conflicts = wrapper::conflicts(&options);
+    if !conflicts.is_empty() {
+        format!("⚠ {}", conflicts.join("; "))
+    } else if configured {
+        "✓ Launch options configured".to_string()
+    } else {
+        "Launch options will be configured on launch".to_string()
+    }
+}
+
+// Set while Steam is being closed for an edit, so a second one isn't started meanwhile
+static CLOSING_STEAM: AtomicBool = AtomicBool::new(false);
+
+/// Run an edit of Steam's config files, warning first if Steam is running: it keeps its own
+/// copy and writes it over the files when it exits, undoing the edit. Offers to close
+/// Steam for the edit and start it again afterwards; waiting for Steam to exit happens
+/// off the UI thread. `done` gets the edit's result on the UI thread, None if the edit
+/// was cancelled.
+fn with_steam_closed<T: Send + 'static>(
+    what: &str,
+    edit: impl FnOnce() -> T + Send + 'static,
+    done: impl FnOnce(Option<T>) + Send + 'static,
+) {
+    if CLOSING_STEAM.load(Ordering::Relaxed) {
+        println!("Warning: Still waiting for Steam to exit, try again once it has");
+        done(None);
+        return;
+    }
+    if !steam::is_running() {
+        done(Some(edit()));
+        return;
+    }
+    let answer = MessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("Steam is running")
+        .set_description(format!(
+            "Steam writes its own copy of its config files over them when it exits, \
+             which would undo {}.\n\n\
+             Close Steam for the change and start it again afterwards? \
+             Choose No to change the file anyway.",
+            what
+        ))
+        .set_buttons(MessageButtons::YesNoCancel)
+        .show();
+    match answer {
+        MessageDialogResult::Yes => {
+            println!("Closing Steam to edit its config");
+            CLOSING_STEAM.store(true, Ordering::Relaxed);
+            thread::spawn(move || {
+                let result = steam::shutdown().map(|()| {
+                    let result = edit();
+                    steam::start();
+                    result
+                });
+                CLOSING_STEAM.store(false, Ordering::Relaxed);
+                let _ = slint::invoke_from_event_loop(move || match result {
+                    Ok(result) => done(Some(result)),
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        MessageDialog::new()
+                            .set_level(MessageLevel::Error)
+                            .set_title("Steam is running")
+                            .set_description(format!("{}, nothing was changed.", e))
+                            .show();
+                        done(None);
+                    }
+                });
+            });
+        }
+        MessageDialogResult::No => {
+            println!(
+                "Warning: Editing Steam's config while Steam is running, it may undo {}",
+                what
+            );
+            done(Some(edit()));
+        }
+        _ => done(None),
+    }
+}
+
+/// Replace a game's launch options in localconfig.vdf (shortcuts.vdf for non-Steam games)
+/// and record the change in the journal
+fn set_launch_options(app_id: &str, value: &str, reason: &str) -> Result<(), String> {
+    let old_value = if shortcuts::is_shortcut(app_id) {
+        shortcuts::set_launch_options(app_id, value)?
+    } else {
+        write_localconfig_launch_options(&[(app_id, value)])?
+            .pop()
+            .flatten()
+    };
+    record_launch_options_change(app_id, old_value, value, reason);
+    Ok(())
+}
+
+/// Replace several games' launch options in one pass: localconfig.vdf is backed up and
+/// written once for all Steam games. Returns the games that failed, with why.
+fn set_many_launch_options(changes: &[(&str, &str)], reason: &str) -> Vec<(String, String)> {
+    let mut failed = Vec::new();
+    let (shortcut_changes, steam_changes): (Vec<_>, Vec<_>) = changes
+        .iter()
+        .partition(|(app_id, _)| shortcuts::is_shortcut(app_id));
+    for (app_id, value) in shortcut_changes {
+        if let Err(e) = set_launch_options(app_id, value, reason) {
+            failed.push((app_id.to_string(), e));
+        }
+    }
+    if steam_changes.is_empty() {
+        return failed;
+    }
+    match write_localconfig_launch_options(&steam_changes) {
+        Ok(old_values) => {
+            for ((app_id, value), old_value) in steam_changes.into_iter().zip(old_values) {
+                record_launch_options_change(app_id, old_value, value, reason);
+            }
+        }
+        Err(e) => failed.extend(
+            steam_changes
+                .into_iter()
+                .map(|(app_id, _)| (app_id.to_string(), e.clone())),
+        ),
+    }
+    failed
+}
+
+fn record_launch_options_change(
+    app_id: &str,
+    old_value: Option<String>,
+    value: &str,
+    reason: &str,
+) {
+    let entry = journal::Entry {
+        timestamp: journal::now(),
+        app_id: app_id.to_string(),
+        old_value,
+        new_value: value.to_string(),
+        reason: reason.to_string(),
+    };
+    if let Err(e) = journal::record(&entry) {
+        println!("Warning: Could not record change in journal: {}", e);
+    }
+}
+
+/// Write games' LaunchOptions into localconfig.vdf, returning their old ones
+fn write_localconfig_launch_options(
+    changes: &[(&str, &str)],
+) -> Result<Vec<Option<String>>, String> {
+    let (localconfig_path, mut root) = read_localconfig()?;
+    let mut old_values = Vec::new();
+    for (app_id, value) in changes {
+        let old_value = match launch_options_in(&root, app_id) {
+            Ok(old_value) => old_value,
+            // Steam only adds a Family Shared game's section once this account has played
+            // it; until then, give it one of its own
+            Err(_) if is_family_shared(app_id) => {
+                let apps = root
+                    .get_path_mut(&LOCALCONFIG_APPS_PATH)
+                    .ok_or_else(|| "localconfig.vdf has no apps section".to_string())?;
+                apps.set(app_id, vdf::Node::Section(Vec::new()));
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        old_values.push(old_value);
+        let mut path = LOCALCONFIG_APPS_PATH.to_vec();
+        path.push(app_id);
+        if let Some(section) = root.get_path_mut(&path) {
+            section.set("LaunchOptions", vdf::Node::Value(value.to_string()));
+        }
+    }
+    let new_content = vdf::serialize(&root);
+
+    // Never hand Steam a file that doesn't read back as what we meant to write
+    if vdf::parse(&new_content).as_ref() != Ok(&root) {
+        return Err("Refusing to write localconfig.vdf, it would not parse back".to_string());
+    }
+    let snapshot = backup::snapshot_localconfig(&localconfig_path)?;
+    let written = fs::write(&localconfig_path, new_content)
+        .and_then(|()| fs::read_to_string(&localconfig_path))
+        .map_err(|e| e.to_string())
+        .and_then(|content| vdf::parse(&content));
+    if written.as_ref() != Ok(&root) {
+        backup::restore_snapshot(&snapshot, &localconfig_path)?;
+        return Err(format!(
+            "Writing localconfig.vdf failed ({}), restored the previous version",
+            written
+                .err()
+                .unwrap_or_else(|| "contents differ".to_string())
+        ));
+    }
+    Ok(old_values)
+}
+
+// What configure_launch_options reports when there was nothing to change
+const ALREADY_CONFIGURED: &str = "Launch options already configured";
+
+/// Whether configure_launch_options would change the game's launch options
+fn launch_options_pending(app_id: &str, template: &str) -> bool {
+    !wrapper::is_configured(template, &read_launch_options(app_id).unwrap_or_default())
+}
+
+/// Add a game's wrapper (protonhax unless another was chosen) to its Steam launch options
+fn configure_launch_options(
+    app_id: &str,
+    template: &str,
+    cooldown_secs: u64,
+) -> Result<String, String> {
+    // Check if already configured
+    let existing_options = read_launch_options(app_id).unwrap_or_default();
+    if wrapper::is_configured(template, &existing_options) {
+        return Ok(ALREADY_CONFIGURED.to_string());
+    }
+
+    if let Some(last) = journal::last_change(app_id) {
+        let elapsed = journal::now().saturating_sub(last);
+        if elapsed < cooldown_secs {
+            return Err(format!(
+                "Launch options were changed {} s ago, not changing them again yet",
+                elapsed
+            ));
+        }
+    }
+
+    let new_options = wrapper::apply(template, &existing_options)?;
+    let conflicts = wrapper::conflicts(&new_options);
+    if !conflicts.is_empty() {
+        return Err(format!(
+            "'{}' has conflicting wrappers: {}",
+            new_options,
+            conflicts.join("; ")
+        ));
+    }
+    set_launch_options(app_id, &new_options, "auto-configure")?;
+
+    Ok("Launch options configured successfully".to_string())
+}
+
+/// Take protonhax back out of a game's Steam launch options, keeping the rest
+fn remove_protonhax_options(app_id: &str) -> Result<String, String> {
+    let existing = read_launch_options(app_id).unwrap_or_default();
+    let Some(options) = wrapper::remove_protonhax(&existing) else {
+        return Ok("protonhax isn't in this game's launch options".to_string());
+    };
+    set_launch_options(app_id, &options, "remove protonhax")?;
+    Ok(if options.is_empty() {
+        "Removed protonhax, the launch options are empty again".to_string()
+    } else {
+        format!(
+            "Removed protonhax, the launch options are now '{}'",
+            options
+        )
+    })
+}
+
+/// The sharing account of a Family Shared install: the manifest's LastOwner when it
+/// isn't the account whose launch options we edit
+fn shared_owner(last_owner: Option<u64>, current_user: Option<u64>) -> Option<u64> {
+    last_owner.filter(|owner| *owner != 0 && current_user.is_some_and(|me| me != *owner))
+}
+
+/// Whether an installed game is Family Shared to the current account
+fn is_family_shared(app_id: &str) -> bool {
+    let Ok(id) = app_id.parse() else {
+        return false;
+    };
+    steam::locate()
+        .ok()
+        .and_then(|steam_dir| steam_dir.find_app(id).ok().flatten())
+        .is_some_and(|(app, _)| shared_owner(app.last_user, steam::current_steam_id64()).is_some())
+}
+
+/// Installed Steam games by name (name -> app ID), and which library each one lives in
+fn scan_libraries() -> (BTreeMap<String, String>, HashMap<String, InstalledGame>) {
+    let mut games: BTreeMap<String, String> = BTreeMap::new();
+    let mut installed: HashMap<String, InstalledGame> = HashMap::new();
+    let current_user = steam::current_steam_id64();
+    if let Ok(steam_dir) = steam::locate() {
+        let compat_tools = steam_dir.compat_tool_mapping().unwrap_or_default();
+        if let Ok(library_iter) = steam_dir.libraries() {
+            for library in library_iter {
+                if let Ok(lib) = library {
+                    for app in lib.apps() {
+                        if let Ok(a) = app {
+                            if let Some(name) = &a.name {
+                                games.insert(name.clone(), a.app_id.to_string());
+                                let shared_from = shared_owner(a.last_user, current_user);
+                                let uses_proton = compat_tools.contains_key(&a.app_id)
+                                    || prefix_path(lib.path(), &a.app_id.to_string()).is_dir();
+                                installed.insert(
+                                    a.app_id.to_string(),
+                                    InstalledGame {
+                                        library: lib.path().to_path_buf(),
+                                        install_dir: lib.resolve_app_dir(&a),
+                                        shared_from,
+                                        metadata: AppMetadata::default(),
+                                        uses_proton,
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Non-Steam games keep their prefix in Steam's own library
+        match shortcuts::load() {
+            Ok(list) => {
+                for shortcut in list {
+                    let app_id = shortcut.app_id.to_string();
+                    let name = if games.contains_key(&shortcut.name) {
+                        format!("{} (non-Steam)", shortcut.name)
+                    } else {
+                        shortcut.name.clone()
+                    };
+                    games.insert(name, app_id.clone());
+                    let uses_proton = compat_tools.contains_key(&shortcut.app_id)
+                        || prefix_path(steam_dir.path(), &app_id).is_dir()
+                        || shortcut
+                            .exe
+                            .trim_matches('"')
+                            .to_lowercase()
+                            .ends_with(".exe");
+                    installed.insert(
+                        app_id,
+                        InstalledGame {
+                            library: steam_dir.path().to_path_buf(),
+                            install_dir: shortcut.start_dir,
+                            shared_from: None,
+                            metadata: AppMetadata::default(),
+                            uses_proton,
+                        },
+                    );
+                }
+            }
+            Err(e) => println!("Warning: Non-Steam games not listed: {}", e),
+        }
+
+        // Developer, genres and platforms from Steam's local metadata cache
+        let wanted = installed.keys().filter_map(|id| id.parse().ok()).collect();
+        match appinfo::load(steam_dir.path(), &wanted) {
+            Ok(metadata) => {
+                for (app_id, meta) in metadata {
+                    if let Some(game) = installed.get_mut(&app_id.to_string()) {
+                        game.uses_proton |= meta.is_windows_only();
+                        game.metadata = meta;
+                    }
+                }
+            }
+            Err(e) => println!("Warning: No app metadata available: {}", e),
+        }
+    }
+    (games, installed)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Write a crash report on panic, and offer to file the one from last time
+    crash::install_panic_hook();
+
+    let args = cli::Cli::parse();
+
+    // `protonic doctor` runs the diagnostics and exits without opening the window
+    if let Some(cli::Command::Doctor) = args.command {
+        std::process::exit(doctor::run());
+    }
+
+    // Separate setups live in their own config, ~/.config/protonic-<profile>/
+    let profile = args.profile.as_deref().and_then(profile_name);
+    if let Some(name) = &profile {
+        println!("Using profile {}", name);
+        let _ = CONFIG_NAME.set(format!("protonic-{}", name));
+    }
+
+    // Load config from ~/.config/protonic/default-config.toml
+    let mut cfg: AppConfig = confy::load(config_name(), None).unwrap_or_default();
+    for game_cfg in cfg.game_configs.values_mut() {
+        game_cfg.upgrade_programs();
+    }
+    if let Some(kill_grace_ms) = cfg.kill_grace_ms.take() {
+        cfg.tunables.kill_grace_ms = kill_grace_ms;
+    }
+    // Changes made on other computers since the last start
+    let mut sync_conflicts = Vec::new();
+    if !cfg.sync_dir.is_empty() {
+        match pull_synced_config(&cfg) {
+            Ok((merged, conflicts)) => {
+                cfg = merged;
+                sync_conflicts = conflicts;
+                save_config(&cfg);
+            }
+            Err(e) => println!("Warning: Could not merge the synced config: {}", e),
+        }
+    }
+    if !cfg.steam_path.is_empty() {
+        steam::prefer(Path::new(&cfg.steam_path));
+    }
+    if cfg.steam_id != 0 {
+        steam::select_account(Some(cfg.steam_id));
+    }
+    history::set_enabled(cfg.usage_history);
+
+    // `protonic launch <app id>` or `protonic launch --name <name>` starts a game and
+    // its programs without opening the window, e.g. from a script or a Steam shortcut
+    if let Some(cli::Command::Launch(launch)) = &args.command {
+        std::process::exit(cli::launch(cfg, launch));
+    }
+
+    // Slint reads the scale factor when the window is created. An explicit
+    // SLINT_SCALE_FACTOR in the environment still wins over the setting.
+    if cfg.ui_scale != 0 && std::env::var_os("SLINT_SCALE_FACTOR").is_none() {
+        if UI_SCALES.contains(&cfg.ui_scale) {
+            // SAFETY: no other threads have been started yet
+            unsafe {
+                std::env::set_var(
+                    "SLINT_SCALE_FACTOR",
+                    (cfg.ui_scale as f32 / 100.0).to_string(),
+                );
+            }
+        } else {
+            println!(
+                "Warning: Ignoring ui_scale = {}, it must be one of {:?} (or 0)",
+                cfg.ui_scale, UI_SCALES
+            );
+        }
+    }
+
+    // From here on, output also goes to the log file and the log panel. Started after
+    // the scale is set, since that has to happen before any other thread runs.
+    match logs::start() {
+        Ok(path) => println!("Logging to {}", path.display()),
+        Err(e) => println!("Warning: Could not start the log file: {}", e),
+    }
+
+    crash::offer_pending_report();
+    offer_path_remaps(&mut cfg);
+
+    if !sync_conflicts.is_empty() {
+        MessageDialog::new()
+            .set_level(MessageLevel::Warning)
+            .set_title("Settings changed on two computers")
+            .set_description(format!(
+                "These settings were changed differently here and on another computer, \
+                 so this computer's values were kept:\n\n{}\n\nThe other values were saved \
+                 in sync-conflict-*.toml next to the config.",
+                sync_conflicts.join("\n")
+            ))
+            .set_buttons(MessageButtons::Ok)
+            .show();
+    }
+
+    // --safe-mode: start with hotkeys, audio, auto-configure and integrations off,
+    // for tracking down crashes caused by a bad config or a broken audio/input stack
+    let safe_mode = args.safe_mode;
+    if safe_mode {
+        println!("Starting in safe mode");
+    }
+
+    let ui = AppWindow::new()?;
+    ui.set_safe_mode(safe_mode);
+    ui.set_profile(profile.unwrap_or_default().into());
+
+    workers::set_limit(cfg.tunables.disk_jobs);
+    // Use Arc<Mutex> for thread-safe config sharing
+    let config = Arc::new(Mutex::new(cfg));
+
+    // Anti-cheat protected sessions the user agreed to run programs in
+    let injection_overrides: Arc<InjectionOverrides> = Arc::default();
+
+    // Every program we inject is tracked so it can be stopped again
+    let tracker = ProcessTracker::default();
+    let config_reaper = Arc::clone(&config);
+    tracker.start_reaper(move |report| {
+        println!("{}", report.describe());
+        if report.status.success() || safe_mode {
+            return;
+        }
+        let cfg = config_reaper.lock().unwrap();
+        if notifications_allowed(&cfg, &report.app_id) {
+            notify::send("Program stopped unexpectedly", &report.describe(), None);
+        }
+        if overlay_allowed(&cfg, &report.app_id) {
+            overlay::show("Program stopped unexpectedly", &report.describe(), true);
+        }
+    });
+
+    // Single audio service so sounds queue up instead of overlapping
+    let audio = AudioService::new();
+    audio.set_sound_pack(&config.lock().unwrap().sound_pack);
+    audio.set_output_device(&config.lock().unwrap().audio_device);
+
+    // Set initial UI state from config
+    {
+        let cfg = config.lock().unwrap();
+        ui.set_search_text(cfg.last_game_name.clone().into());
+        ui.set_app_id(cfg.last_app_id.clone().into());
+        // Safe mode only overrides what's shown; the saved settings are left alone
+        ui.set_auto_configure(cfg.auto_configure && !safe_mode);
+        ui.set_sounds_enabled(cfg.sounds_enabled && !safe_mode);
+        ui.set_notifications(cfg.notifications && !safe_mode);
+        ui.set_overlay(cfg.overlay && !safe_mode);
+        ui.set_kill_on_exit(cfg.kill_on_exit);
+        ui.set_stop_with_game(cfg.stop_with_game);
+        ui.set_steam_api_key(cfg.steam_api_key.clone().into());
+        ui.set_online_metadata(cfg.online_metadata);
+        ui.set_low_resource_mode(cfg.low_resource_mode);
+        ui.set_terminal(cfg.terminal.clone().into());
+        ui.set_locked(cfg.locked);
+        ui.set_lock_has_pin(!cfg.lock_pin.is_empty());
+        let ui_scales: Vec<SharedString> = std::iter::once(UI_SCALE_AUTO.to_string())
+            .chain(UI_SCALES.iter().map(|scale| format!("{}%", scale)))
+            .map(SharedString::from)
+            .collect();
+        ui.set_ui_scales(ModelRc::from(Rc::new(VecModel::from(ui_scales))));
+        ui.set_ui_scale(if cfg.ui_scale == 0 {
+            UI_SCALE_AUTO.into()
+        } else {
+            format!("{}%", cfg.ui_scale).into()
+        });
+        theme::set(&cfg.accent_color, cfg.corner_radius);
+        theme::apply(&ui);
+        let swatches: Vec<AccentSwatch> = theme::ACCENTS
+            .iter()
+            .filter_map(|(_, hex)| {
+                Some(AccentSwatch {
+                    hex: (*hex).into(),
+                    color: theme::parse_color(hex).ok()?,
+                })
+            })
+            .collect();
+        ui.set_accent_swatches(ModelRc::from(Rc::new(VecModel::from(swatches))));
+        ui.set_accent_color(cfg.accent_color.clone().into());
+        let radii: Vec<SharedString> = theme::RADII
+            .iter()
+            .map(|radius| format!("{} px", radius).into())
+            .collect();
+        ui.set_corner_radii(ModelRc::from(Rc::new(VecModel::from(radii))));
+        ui.set_corner_radius(format!("{} px", cfg.corner_radius).into());
+        let steam_installations: Vec<SharedString> = steam::installations()
+            .iter()
+            .map(|installation| SharedString::from(installation.label()))
+            .collect();
+        ui.set_steam_installations(ModelRc::from(Rc::new(VecModel::from(steam_installations))));
+        if let Some(installation) = steam::current() {
+            ui.set_steam_installation(installation.label().into());
+        }
+        show_steam_accounts(&ui, &cfg);
+        ui.set_sync_dir(cfg.sync_dir.clone().into());
+        let mut wrapper_choices: Vec<SharedString> = wrapper::PRESETS
+            .iter()
+            .map(|p| SharedString::from(*p))
+            .collect();
+        wrapper_choices.push(CUSTOM_WRAPPER.into());
+        ui.set_wrapper_choices(ModelRc::from(Rc::new(VecModel::from(wrapper_choices))));
+        let recorder_choices: Vec<SharedString> = recorder::Recorder::ALL
+            .iter()
+            .map(|r| SharedString::from(r.label()))
+            .collect();
+        ui.set_recorder_choices(ModelRc::from(Rc::new(VecModel::from(recorder_choices))));
+        let backend_choices: Vec<SharedString> = backend::Backend::ALL
+            .iter()
+            .map(|b| SharedString::from(b.label()))
+            .collect();
+        ui.set_backend_choices(ModelRc::from(Rc::new(VecModel::from(backend_choices))));
+        let mangohud_presets: Vec<MangoHudPreset> = wrapper::MANGOHUD_PRESETS
+            .iter()
+            .map(|(name, config)| MangoHudPreset {
+                name: (*name).into(),
+                config: (*config).into(),
+            })
+            .collect();
+        ui.set_mangohud_presets(ModelRc::from(Rc::new(VecModel::from(mangohud_presets))));
+        let registry_presets: Vec<RegistryPreset> = registry::PRESETS
+            .iter()
+            .map(|(name, line)| RegistryPreset {
+                name: (*name).into(),
+                line: (*line).into(),
+            })
+            .collect();
+        ui.set_registry_presets(ModelRc::from(Rc::new(VecModel::from(registry_presets))));
+        let verb_choices: Vec<SharedString> = winetricks::COMMON_VERBS
+            .iter()
+            .map(|verb| SharedString::from(*verb))
+            .collect();
+        ui.set_verb_choices(ModelRc::from(Rc::new(VecModel::from(verb_choices))));
+        let detected = terminal::detect().unwrap_or("none found");
+        ui.set_detected_terminal(detected.into());
+
+        // Offer the built-in sounds plus any installed sound packs
+        let mut packs = vec![SharedString::from(BUILTIN_PACK)];
+        packs.extend(
+            audio::available_sound_packs()
+                .into_iter()
+                .map(SharedString::from),
+        );
+        ui.set_sound_packs(ModelRc::from(Rc::new(VecModel::from(packs))));
+        if cfg.sound_pack.is_empty() {
+            ui.set_sound_pack(BUILTIN_PACK.into());
+        } else {
+            ui.set_sound_pack(cfg.sound_pack.clone().into());
+        }
+
+        // The default device plus every output device, and the saved one even if it's
+        // unplugged right now
+        let mut devices = vec![SharedString::from(DEFAULT_DEVICE)];
+        let mut names = audio::output_devices();
+        if !cfg.audio_device.is_empty() && !names.contains(&cfg.audio_device) {
+            names.push(cfg.audio_device.clone());
+        }
+        devices.extend(names.into_iter().map(SharedString::from));
+        ui.set_audio_devices(ModelRc::from(Rc::new(VecModel::from(devices))));
+        if cfg.audio_device.is_empty() {
+            ui.set_audio_device(DEFAULT_DEVICE.into());
+        } else {
+            ui.set_audio_device(cfg.audio_device.clone().into());
+        }
+    }
+
+    // Warn up front if the hotkey can't be captured in this session
+    let display_server = display::detect_display_server();
+    if !safe_mode && let Some(warning) = display::hotkey_limitation(display_server) {
+        println!("Warning: {}", warning);
+        ui.set_hotkey_warning(warning.into());
+    }
+
+    // Under Wayland, global keys need direct /dev/input access; offer to set it up
+    if !safe_mode
+        && matches!(display_server, display::DisplayServer::Wayland { .. })
+        && !input_access::input_devices_readable()
+    {
+        ui.set_input_access_help(input_access::setup_instructions().into());
+    }
+
+    // Grant input access callback (polkit one-time setup)
+    let ui_handle_access = ui.as_weak();
+    ui.on_grant_input_access(move || {
+        let ui_handle = ui_handle_access.clone();
+        // pkexec blocks while the password prompt is open, so keep it off the UI thread
+        thread::spawn(move || {
+            let message = match input_access::grant_input_access() {
+                Ok(msg) => msg,
+                Err(e) => format!("{} - {}", e, input_access::setup_instructions()),
+            };
+            println!("{}", message);
+            let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+                ui.set_input_access_help(message.into());
+            });
+        });
+    });
+
+    // Offer to install protonhax when it's missing
+    if !command_in_path("protonhax") {
+        let dir = protonhax::install_dir()
+            .map_or("~/.local/bin".to_string(), |dir| dir.display().to_string());
+        ui.set_protonhax_help(
+            format!(
+                "protonhax isn't installed, so games using it can't start your programs. \
+                 Install downloads it from its GitHub repository into {}. {}",
+                dir,
+                protonhax::package_hint()
+            )
+            .into(),
+        );
+    }
+
+    let ui_handle_install = ui.as_weak();
+    ui.on_install_protonhax(move || {
+        let Some(ui) = ui_handle_install.upgrade() else {
+            return;
+        };
+        ui.set_protonhax_installing(true);
+        let ui_handle = ui_handle_install.clone();
+        thread::spawn(move || {
+            let result = protonhax::install();
+            let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+                ui.set_protonhax_installing(false);
+                match result {
+                    Ok(message) => {
+                        println!("{}", message);
+                        // Still shown when the install folder isn't in PATH
+                        if command_in_path("protonhax") {
+                            ui.set_protonhax_help("".into());
+                        } else {
+                            ui.set_protonhax_help(message.into());
+                        }
+                    }
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        ui.set_protonhax_help(
+                            format!("{}. {}", e, protonhax::package_hint()).into(),
+                        );
+                    }
+                }
+            });
+        });
+    });
+
+    // Fetch list of installed Steam games (and which library each one lives in)
+    let (games, installed) = scan_libraries();
+
+    // Select the game asked for on the command line or in a protonic:// link, or else
+    // restore the last selected one
+    let startup = startup_game(&args);
+    {
+        let mut cfg = config.lock().unwrap();
+        if let Some(startup) = &startup {
+            let name = games
+                .iter()
+                .find(|(_, id)| **id == startup.app_id)
+                .map(|(name, _)| name.clone())
+                .unwrap_or_else(|| format!("App {}", startup.app_id));
+            ui.set_search_text(name.clone().into());
+            select_game(
+                &ui,
+                &mut cfg,
+                &startup.app_id,
+                &name,
+                installed.get(&startup.app_id),
+                safe_mode,
+            );
+        } else if !cfg.last_app_id.is_empty() {
+            let id = cfg.last_app_id.clone();
+            let name = cfg.last_game_name.clone();
+            select_game(&ui, &mut cfg, &id, &name, installed.get(&id), safe_mode);
+        }
+    }
+
+    // Prefix tools, and the disk-usage alert's Open prefix
+    let ui_handle_prefix = ui.as_weak();
+    ui.on_open_prefix(move || {
+        if let Some(ui) = ui_handle_prefix.upgrade() {
+            open_folder(Path::new(ui.get_prefix_path().as_str()));
+        }
+    });
+    let ui_handle_drive_c = ui.as_weak();
+    ui.on_open_drive_c(move || {
+        if let Some(ui) = ui_handle_drive_c.upgrade() {
+            open_folder(
+                &Path::new(ui.get_prefix_path().as_str())
+                    .join("pfx")
+                    .join("drive_c"),
+            );
+        }
+    });
+    let ui_handle_copy_prefix = ui.as_weak();
+    ui.on_copy_prefix_path(move || {
+        if let Some(ui) = ui_handle_copy_prefix.upgrade() {
+            match display::copy_to_clipboard(ui.get_prefix_path().as_str()) {
+                Ok(()) => println!("Copied {} to the clipboard", ui.get_prefix_path()),
+                Err(e) => println!("Error: {}", e),
+            }
+        }
+    });
+
+    // Initial population of the list (filtered by saved search text if any)
+    let (initial_search, ignore_articles, grid_view, sidebar_collapsed, sidebar_width) = {
+        let cfg = config.lock().unwrap();
+        (
+            cfg.last_game_name.clone(),
+            cfg.ignore_articles,
+            cfg.grid_view,
+            cfg.sidebar_collapsed,
+            cfg.sidebar_width,
+        )
+    };
+    ui.set_ignore_articles(ignore_articles);
+    ui.set_grid_view(grid_view);
+    ui.set_sidebar_collapsed(sidebar_collapsed);
+    // A hand-edited width outside what dragging allows is brought back into range
+    ui.set_sidebar_width(
+        (sidebar_width as f32).clamp(ui.get_min_sidebar_width(), ui.get_max_sidebar_width()),
+    );
+    let art_loader = Rc::new(ArtLoader::new());
+    let initial_filtered = filter_games(
+        &games,
+        &installed,
+        &initial_search,
+        FILTER_ALL,
+        ignore_articles,
+    );
+    ui.set_games(ModelRc::from(Rc::new(VecModel::from(initial_filtered))));
+    ArtLoader::start(&art_loader, &ui);
+    ui.set_platform_filters(ModelRc::from(Rc::new(VecModel::from(vec![
+        SharedString::from(FILTER_ALL),
+        SharedString::from(FILTER_WINDOWS_ONLY),
+        SharedString::from(FILTER_NATIVE_LINUX),
+    ]))));
+
+    // Search Callback (to filter the game list as you type or change the platform filter)
+    let ui_handle_search = ui.as_weak();
+    let games_search = games.clone();
+    let installed_search = installed.clone();
+    let art_loader_search = Rc::clone(&art_loader);
+    ui.on_search_edited(move |text| {
+        if let Some(ui) = ui_handle_search.upgrade() {
+            let filtered = filter_games(
+                &games_search,
+                &installed_search,
+                &text,
+                &ui.get_platform_filter(),
+                ui.get_ignore_articles(),
+            );
+            ui.set_games(ModelRc::from(Rc::new(VecModel::from(filtered))));
+            ArtLoader::start(&art_loader_search, &ui);
+
+            // A bare number that isn't one of our installed games can still be used as an app ID
+            let term = text.trim();
+            let is_unknown_app_id = !term.is_empty()
+                && term.chars().all(|c| c.is_ascii_digit())
+                && !installed_search.contains_key(term);
+            ui.set_manual_app_id(if is_unknown_app_id { term } else { "" }.into());
+        }
+    });
+
+    // Leading articles toggle; the list is re-sorted by the UI re-running the search
+    let config_articles = Arc::clone(&config);
+    ui.on_ignore_articles_toggled(move |enabled| {
+        let mut cfg = config_articles.lock().unwrap();
+        cfg.ignore_articles = enabled;
+        save_config(&cfg);
+    });
+
+    // List/grid toggle; switching to the grid starts loading artwork
+    let art_loader_grid = Rc::clone(&art_loader);
+    let ui_handle_grid = ui.as_weak();
+    let config_grid = Arc::clone(&config);
+    ui.on_grid_view_toggled(move |enabled| {
+        let mut cfg = config_grid.lock().unwrap();
+        cfg.grid_view = enabled;
+        save_config(&cfg);
+        if let Some(ui) = ui_handle_grid.upgrade() {
+            ArtLoader::start(&art_loader_grid, &ui);
+        }
+    });
+
+    // Library sidebar: collapsed, and resized by dragging its edge
+    let config_sidebar = Arc::clone(&config);
+    ui.on_sidebar_toggled(move |collapsed| {
+        let mut cfg = config_sidebar.lock().unwrap();
+        cfg.sidebar_collapsed = collapsed;
+        save_config(&cfg);
+    });
+
+    let config_sidebar_width = Arc::clone(&config);
+    ui.on_sidebar_resized(move |width| {
+        let mut cfg = config_sidebar_width.lock().unwrap();
+        cfg.sidebar_width = width.max(0) as u32;
+        save_config(&cfg);
+    });
+
+    // Game Selection Callback
+    let ui_handle_select = ui.as_weak();
+    let games_clone = games.clone();
+    let installed_select = installed.clone();
+    let config_select = Arc::clone(&config);
+    ui.on_game_selected(move |name| {
+        if let Some(ui) = ui_handle_select.upgrade() {
+            if let Some(id) = games_clone.get(name.as_str()) {
+                let mut cfg = config_select.lock().unwrap();
+                select_game(
+                    &ui,
+                    &mut cfg,
+                    id,
+                    &name,
+                    installed_select.get(id),
+                    safe_mode,
+                );
+            }
+        }
+    });
+
+    // Manual app ID Callback, for games that aren't in the scanned libraries
+    let ui_handle_manual = ui.as_weak();
+    let installed_manual = installed.clone();
+    let config_manual = Arc::clone(&config);
+    ui.on_app_id_entered(move |id| {
+        if let Some(ui) = ui_handle_manual.upgrade() {
+            let id = id.trim().to_string();
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+                return;
+            }
+            let mut cfg = config_manual.lock().unwrap();
+            select_game(
+                &ui,
+                &mut cfg,
+                &id,
+                &id,
+                installed_manual.get(&id),
+                safe_mode,
+            );
+        }
+    });
+
+    // Undo auto-configure for the selected game
+    let ui_handle_remove_options = ui.as_weak();
+    let config_remove_options = Arc::clone(&config);
+    ui.on_remove_from_launch_options(move || {
+        let Some(ui) = ui_handle_remove_options.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if app_id.is_empty() {
+            return;
+        }
+        if config_remove_options.lock().unwrap().locked {
+            return;
+        }
+        let ui_handle = ui_handle_remove_options.clone();
+        let config = Arc::clone(&config_remove_options);
+        let edited_app_id = app_id.clone();
+        with_steam_closed(
+            "removing protonhax",
+            move || remove_protonhax_options(&edited_app_id),
+            move |removed| {
+                let (Some(removed), Some(ui)) = (removed, ui_handle.upgrade()) else {
+                    return;
+                };
+                let cfg = config.lock().unwrap();
+                let note = match removed {
+                    Ok(message) if cfg.auto_configure => format!(
+                        "{}. Auto-configure adds it back on the next launch from Protonic unless you turn it off",
+                        message
+                    ),
+                    Ok(message) => message,
+                    Err(e) => format!("Could not remove protonhax: {}", e),
+                };
+                println!("{}", note);
+                ui.set_launch_options_note(note.into());
+                if cfg.auto_configure {
+                    ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
+                }
+            },
+        );
+    });
+
+    // Auto-configure toggle callback
+    let ui_handle_toggle = ui.as_weak();
+    let config_toggle = Arc::clone(&config);
+    ui.on_auto_configure_toggled(move |enabled| {
+        if let Some(ui) = ui_handle_toggle.upgrade() {
+            let mut cfg = config_toggle.lock().unwrap();
+            cfg.auto_configure = enabled;
+            save_config(&cfg);
+
+            // Update status display
+            if enabled && !cfg.last_app_id.is_empty() {
+                ui.set_launch_options_status(launch_options_status(&cfg, &cfg.last_app_id).into());
+            } else {
+                ui.set_launch_options_status(SharedString::new());
+            }
+        }
+    });
+
+    // Global sounds toggle callback
+    let config_sounds = Arc::clone(&config);
+    ui.on_sounds_toggled(move |enabled| {
+        let mut cfg = config_sounds.lock().unwrap();
+        cfg.sounds_enabled = enabled;
+        save_config(&cfg);
+    });
+
+    let config_notifications = Arc::clone(&config);
+    ui.on_notifications_toggled(move |enabled| {
+        let mut cfg = config_notifications.lock().unwrap();
+        cfg.notifications = enabled;
+        save_config(&cfg);
+    });
+
+    let config_overlay = Arc::clone(&config);
+    ui.on_overlay_toggled(move |enabled| {
+        let mut cfg = config_overlay.lock().unwrap();
+        cfg.overlay = enabled;
+        save_config(&cfg);
+    });
+
+    // Kill-on-exit toggle callback
+    let config_kill_exit = Arc::clone(&config);
+    ui.on_kill_on_exit_toggled(move |enabled| {
+        let mut cfg = config_kill_exit.lock().unwrap();
+        cfg.kill_on_exit = enabled;
+        save_config(&cfg);
+    });
+
+    let config_stop_with_game = Arc::clone(&config);
+    ui.on_stop_with_game_toggled(move |enabled| {
+        let mut cfg = config_stop_with_game.lock().unwrap();
+        cfg.stop_with_game = enabled;
+        save_config(&cfg);
+    });
+
+    // Online store info callbacks
+    let config_online = Arc::clone(&config);
+    ui.on_online_metadata_toggled(move |enabled| {
+        let mut cfg = config_online.lock().unwrap();
+        cfg.online_metadata = enabled;
+        save_config(&cfg);
+    });
+
+    // Takes effect at the next session refresh
+    let config_low_resource = Arc::clone(&config);
+    ui.on_low_resource_mode_toggled(move |enabled| {
+        let mut cfg = config_low_resource.lock().unwrap();
+        cfg.low_resource_mode = enabled;
+        save_config(&cfg);
+    });
+
+    let config_api_key = Arc::clone(&config);
+    ui.on_steam_api_key_edited(move |key| {
+        let mut cfg = config_api_key.lock().unwrap();
+        cfg.steam_api_key = key.trim().to_string();
+        save_config(&cfg);
+    });
+
+    let config_terminal = Arc::clone(&config);
+    ui.on_terminal_edited(move |command| {
+        let mut cfg = config_terminal.lock().unwrap();
+        cfg.terminal = command.trim().to_string();
+        save_config(&cfg);
+    });
+
+    // Profile lock callbacks
+    let ui_handle_lock = ui.as_weak();
+    let config_lock = Arc::clone(&config);
+    ui.on_lock(move |pin| {
+        let Some(ui) = ui_handle_lock.upgrade() else {
+            return;
+        };
+        let hash = match lock::hash_pin(&pin) {
+            Ok(hash) => hash,
+            Err(e) => {
+                ui.set_lock_status(format!("Could not set the PIN: {}", e).into());
+                return;
+            }
+        };
+        let mut cfg = config_lock.lock().unwrap();
+        cfg.locked = true;
+        ui.set_lock_has_pin(!hash.is_empty());
+        cfg.lock_pin = hash;
+        save_config(&cfg);
+        ui.set_locked(true);
+        ui.set_lock_status(SharedString::new());
+    });
+
+    let ui_handle_unlock = ui.as_weak();
+    let config_unlock = Arc::clone(&config);
+    ui.on_unlock(move |pin| {
+        let Some(ui) = ui_handle_unlock.upgrade() else {
+            return;
+        };
+        let mut cfg = config_unlock.lock().unwrap();
+        if !lock::pin_matches(&pin, &cfg.lock_pin) {
+            ui.set_lock_status("Wrong PIN".into());
+            return;
+        }
+        cfg.locked = false;
+        cfg.lock_pin.clear();
+        save_config(&cfg);
+        ui.set_locked(false);
+        ui.set_lock_has_pin(false);
+        ui.set_lock_status(SharedString::new());
+    });
+
+    // UI scale callback. Slint only picks up the scale factor at startup.
+    let ui_handle_scale = ui.as_weak();
+    let config_scale = Arc::clone(&config);
+    ui.on_ui_scale_selected(move |choice| {
+        let scale = choice.trim_end_matches('%').parse().unwrap_or(0);
+        let mut cfg = config_scale.lock().unwrap();
+        if cfg.ui_scale != scale {
+            cfg.ui_scale = scale;
+            save_config(&cfg);
+            if let Some(ui) = ui_handle_scale.upgrade() {
+                ui.set_ui_scale_pending(true);
+            }
+        }
+    });
+
+    // Steam installation callback. Libraries are only scanned at startup.
+    let ui_handle_steam = ui.as_weak();
+    let config_steam = Arc::clone(&config);
+    ui.on_steam_installation_selected(move |choice| {
+        let Some(installation) = steam::installations()
+            .into_iter()
+            .find(|installation| installation.label() == choice.as_str())
+        else {
+            return;
+        };
+        let path = installation.path.to_string_lossy().into_owned();
+        let mut cfg = config_steam.lock().unwrap();
+        if cfg.steam_path != path {
+            cfg.steam_path = path;
+            save_config(&cfg);
+            if let Some(ui) = ui_handle_steam.upgrade() {
+                ui.set_steam_pending(true);
+            }
+        }
+    });
+
+    // Steam account callback. Launch options are read and written for the new account
+    // right away.
+    let ui_handle_account = ui.as_weak();
+    let config_account = Arc::clone(&config);
+    ui.on_steam_account_selected(move |index| {
+        let Some(ui) = ui_handle_account.upgrade() else {
+            return;
+        };
+        let Some(account) = steam_accounts().into_iter().nth(index as usize) else {
+            return;
+        };
+        let mut cfg = config_account.lock().unwrap();
+        cfg.steam_id = account.steam_id64;
+        save_config(&cfg);
+        steam::select_account(Some(account.steam_id64));
+        println!("Editing launch options for {}", account.label());
+        let app_id = ui.get_app_id().to_string();
+        if !app_id.is_empty() && cfg.auto_configure && !ui.get_safe_mode() {
+            ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
+        }
+    });
+
+    // Sync folder callbacks
+    let ui_handle_sync = ui.as_weak();
+    let config_sync = Arc::clone(&config);
+    ui.on_choose_sync_dir(move || {
+        let Some(ui) = ui_handle_sync.upgrade() else {
+            return;
+        };
+        let Some(dir) = FileDialog::new().pick_folder() else {
+            return;
+        };
+        let dir = portal::host_path(dir);
+        let mut cfg = config_sync.lock().unwrap();
+        cfg.sync_dir = dir.to_string_lossy().into_owned();
+        ui.set_sync_dir(cfg.sync_dir.clone().into());
+        // Another computer's copy would be overwritten, so it's merged at the next start
+        if sync::has_copy(&dir) {
+            let _ = confy::store(config_name(), None, &*cfg);
+            ui.set_sync_status("Found a synced config there, restart Protonic to merge it".into());
+        } else {
+            save_config(&cfg);
+            ui.set_sync_status("Settings are mirrored there on every change".into());
+        }
+    });
+
+    let ui_handle_sync_stop = ui.as_weak();
+    let config_sync_stop = Arc::clone(&config);
+    ui.on_stop_sync(move || {
+        let mut cfg = config_sync_stop.lock().unwrap();
+        cfg.sync_dir.clear();
+        save_config(&cfg);
+        if let Some(ui) = ui_handle_sync_stop.upgrade() {
+            ui.set_sync_dir(SharedString::new());
+            ui.set_sync_status(SharedString::new());
+        }
+    });
+
+    // Per-game wrapper selection callbacks
+    let ui_handle_wrapper = ui.as_weak();
+    let config_wrapper = Arc::clone(&config);
+    let save_wrapper = move |template: String| {
+        if let Some(ui) = ui_handle_wrapper.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            let mut cfg = config_wrapper.lock().unwrap();
+            let inherited = default_wrapper_template(&cfg);
+            let game_cfg = cfg.game_configs.entry(app_id.clone()).or_default();
+            game_cfg.wrapper = if template == inherited {
+                String::new()
+            } else {
+                template.clone()
+            };
+            save_config(&cfg);
+            show_mangohud_config(&ui, &template);
+            ui.set_wrapper_template(template.into());
+            if cfg.auto_configure && !safe_mode {
+                ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
+            }
+        }
+    };
+    let save_wrapper_preset = save_wrapper.clone();
+    ui.on_wrapper_selected(move |choice| {
+        // Picking "Custom" keeps the current template until it's edited
+        if choice != CUSTOM_WRAPPER {
+            save_wrapper_preset(choice.to_string());
+        }
+    });
+    let save_wrapper_custom = save_wrapper.clone();
+    ui.on_custom_wrapper_edited(move |template| {
+        save_wrapper_custom(template.trim().to_string());
+    });
+
+    // Per-game env vars, space separated VAR=value pairs
+    let ui_handle_env = ui.as_weak();
+    let config_env = Arc::clone(&config);
+    ui.on_game_env_edited(move |text| {
+        let Some(ui) = ui_handle_env.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if app_id.is_empty() {
+            return;
+        }
+        let (env, invalid): (Vec<String>, Vec<String>) = text
+            .split_whitespace()
+            .map(str::to_string)
+            .partition(|word| wrapper::is_assignment(word));
+        ui.set_game_env_error(if invalid.is_empty() {
+            SharedString::new()
+        } else {
+            format!("Not VAR=value: {}", invalid.join(" ")).into()
+        });
+
+        let mut cfg = config_env.lock().unwrap();
+        let game_cfg = cfg.game_configs.entry(app_id.clone()).or_default();
+        game_cfg.env = env;
+        save_config(&cfg);
+        if cfg.auto_configure && !safe_mode {
+            ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
+        }
+    });
+
+    // MangoHud presets and edits rewrite the MANGOHUD_CONFIG in the game's template
+    let ui_handle_mangohud = ui.as_weak();
+    ui.on_mangohud_config_edited(move |config| {
+        let Some(ui) = ui_handle_mangohud.upgrade() else {
+            return;
+        };
+        let template = wrapper::with_mangohud_config(&ui.get_wrapper_template(), &config);
+        if wrapper::PRESETS.contains(&template.as_str()) {
+            ui.set_wrapper_choice(template.clone().into());
+        } else {
+            ui.set_wrapper_choice(CUSTOM_WRAPPER.into());
+        }
+        save_wrapper(template);
+    });
+
+    // Sound pack selection callback
+    let config_pack = Arc::clone(&config);
+    let audio_pack = audio.clone();
+    ui.on_sound_pack_selected(move |name| {
+        let pack = if name == BUILTIN_PACK {
+            String::new()
+        } else {
+            name.to_string()
+        };
+        audio_pack.set_sound_pack(&pack);
+
+        let mut cfg = config_pack.lock().unwrap();
+        cfg.sound_pack = pack;
+        save_config(&cfg);
+    });
+
+    // Audio output device selection callback
+    let config_device = Arc::clone(&config);
+    let audio_device = audio.clone();
+    ui.on_audio_device_selected(move |name| {
+        let device = if name == DEFAULT_DEVICE {
+            String::new()
+        } else {
+            name.to_string()
+        };
+        audio_device.set_output_device(&device);
+
+        let mut cfg = config_device.lock().unwrap();
+        cfg.audio_device = device;
+        save_config(&cfg);
+    });
+
+    // Per-game do-not-disturb toggle callback
+    let ui_handle_dnd = ui.as_weak();
+    let config_dnd = Arc::clone(&config);
+    ui.on_do_not_disturb_toggled(move |enabled| {
+        if let Some(ui) = ui_handle_dnd.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_dnd.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.do_not_disturb = enabled;
+            save_config(&cfg);
+        }
+    });
+
+    // Per-game refocus toggle callback
+    let ui_handle_refocus = ui.as_weak();
+    let config_refocus = Arc::clone(&config);
+    ui.on_refocus_game_toggled(move |enabled| {
+        if let Some(ui) = ui_handle_refocus.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_refocus.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.refocus_game = enabled;
+            save_config(&cfg);
+        }
+    });
+
+    // Per-game screen recorder callback
+    let ui_handle_recorder = ui.as_weak();
+    let config_recorder = Arc::clone(&config);
+    ui.on_recorder_selected(move |label| {
+        if let Some(ui) = ui_handle_recorder.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_recorder.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.recorder = recorder::Recorder::from_label(&label);
+            save_config(&cfg);
+        }
+    });
+
+    // Per-game injection backend; the launch options status changes with it, since the
+    // native backend doesn't need protonhax in them
+    let ui_handle_backend = ui.as_weak();
+    let config_backend = Arc::clone(&config);
+    ui.on_backend_selected(move |label| {
+        if let Some(ui) = ui_handle_backend.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_backend.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id.clone()).or_default();
+            game_cfg.backend = backend::Backend::from_label(&label);
+            save_config(&cfg);
+            if cfg.auto_configure && !safe_mode {
+                ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
+            }
+        }
+    });
+
+    // Steam's compatibility tool for the game, written to its config.vdf
+    let ui_handle_compat_tool = ui.as_weak();
+    let config_compat_tool = Arc::clone(&config);
+    ui.on_compat_tool_selected(move |label| {
+        let Some(ui) = ui_handle_compat_tool.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if app_id.is_empty() {
+            return;
+        }
+        if config_compat_tool.lock().unwrap().locked {
+            show_compat_tool(&ui, &app_id);
+            return;
+        }
+        let name = if label == STEAM_DEFAULT_TOOL {
+            None
+        } else {
+            Some(
+                proton::installed_tools()
+                    .into_iter()
+                    .find(|tool| tool.label == label.as_str())
+                    .map_or(label.to_string(), |tool| tool.name),
+            )
+        };
+        let ui_handle = ui_handle_compat_tool.clone();
+        let (edited_app_id, edited_name) = (app_id.clone(), name.clone());
+        with_steam_closed(
+            "the Proton change",
+            move || proton::set_compat_tool(&edited_app_id, edited_name.as_deref()),
+            move |changed| {
+                match changed {
+                    Some(Ok(())) => println!(
+                        "Game {} now runs with {}",
+                        app_id,
+                        name.as_deref()
+                            .unwrap_or("Steam's default compatibility tool")
+                    ),
+                    Some(Err(e)) => println!("Error: Could not change the Proton version: {}", e),
+                    None => {}
+                }
+                // Another game may have been selected while Steam was closing
+                if let Some(ui) = ui_handle.upgrade()
+                    && ui.get_app_id() == app_id.as_str()
+                {
+                    show_compat_tool(&ui, &app_id);
+                }
+            },
+        );
+    });
+
+    // Per-game hotkey: capture the next key combination pressed
+    let ui_handle_capture = ui.as_weak();
+    let config_capture = Arc::clone(&config);
+    ui.on_capture_hotkey(move || {
+        let Some(ui) = ui_handle_capture.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if app_id.is_empty() {
+            return;
+        }
+        ui.set_hotkey_capturing(true);
+
+        let ui_handle = ui_handle_capture.clone();
+        let config = Arc::clone(&config_capture);
+        thread::spawn(move || {
+            let captured = match keyboard::Keyboard::open() {
+                Some(mut keyboard) => hotkey::capture(&mut keyboard),
+                None => {
+                    println!("Error: Could not access the keyboard to capture a hotkey");
+                    None
+                }
+            };
+            let hotkey = captured.map(|keys| hotkey::format(&keys));
+            if let Some(hotkey) = &hotkey {
+                let mut cfg = config.lock().unwrap();
+                cfg.game_configs.entry(app_id.clone()).or_default().hotkey = hotkey.clone();
+                save_config(&cfg);
+                println!("Hotkey for {} set to {}", app_id, hotkey);
+            }
+            let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+                ui.set_hotkey_capturing(false);
+                // Another game may have been selected while capturing
+                if let Some(hotkey) = hotkey
+                    && ui.get_app_id() == app_id.as_str()
+                {
+                    ui.set_hotkey(hotkey.into());
+                }
+            });
+        });
+    });
+
+    let ui_handle_reset_hotkey = ui.as_weak();
+    let config_reset_hotkey = Arc::clone(&config);
+    ui.on_reset_hotkey(move || {
+        if let Some(ui) = ui_handle_reset_hotkey.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_reset_hotkey.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.hotkey.clear();
+            save_config(&cfg);
+            ui.set_hotkey(hotkey::DEFAULT.into());
+        }
+    });
+
+    // Per-game controller trigger: capture the next button combination pressed
+    let ui_handle_capture_gamepad = ui.as_weak();
+    let config_capture_gamepad = Arc::clone(&config);
+    ui.on_capture_gamepad_trigger(move || {
+        let Some(ui) = ui_handle_capture_gamepad.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if app_id.is_empty() || config_capture_gamepad.lock().unwrap().locked {
+            return;
+        }
+        ui.set_gamepad_capturing(true);
+
+        let ui_handle = ui_handle_capture_gamepad.clone();
+        let config = Arc::clone(&config_capture_gamepad);
+        thread::spawn(move || {
+            let captured = match gamepad::Gamepads::open() {
+                Some(mut gamepads) => gamepad::capture(&mut gamepads),
+                None => {
+                    println!("Error: Could not access controllers to capture a button");
+                    None
+                }
+            };
+            let trigger = captured.map(|buttons| gamepad::format(&buttons));
+            if let Some(trigger) = &trigger {
+                let mut cfg = config.lock().unwrap();
+                cfg.game_configs
+                    .entry(app_id.clone())
+                    .or_default()
+                    .gamepad_trigger = trigger.clone();
+                save_config(&cfg);
+                println!("Controller trigger for {} set to {}", app_id, trigger);
+            }
+            let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+                ui.set_gamepad_capturing(false);
+                if let Some(trigger) = trigger
+                    && ui.get_app_id() == app_id.as_str()
+                {
+                    ui.set_gamepad_trigger(trigger.into());
+                }
+            });
+        });
+    });
+
+    let ui_handle_clear_gamepad = ui.as_weak();
+    let config_clear_gamepad = Arc::clone(&config);
+    ui.on_clear_gamepad_trigger(move || {
+        if let Some(ui) = ui_handle_clear_gamepad.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_clear_gamepad.lock().unwrap();
+            if cfg.locked {
+                return;
+            }
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.gamepad_trigger.clear();
+            save_config(&cfg);
+            ui.set_gamepad_trigger(SharedString::new());
+        }
+    });
+
+    // Per-game auto-launch toggle and delay
+    let ui_handle_auto_launch = ui.as_weak();
+    let config_auto_launch = Arc::clone(&config);
+    ui.on_auto_launch_toggled(move |enabled| {
+        if let Some(ui) = ui_handle_auto_launch.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_auto_launch.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.auto_launch = enabled;
+            save_config(&cfg);
+        }
+    });
+
+    let ui_handle_auto_delay = ui.as_weak();
+    let config_auto_delay = Arc::clone(&config);
+    ui.on_auto_launch_delay_edited(move |text| {
+        if let Some(ui) = ui_handle_auto_delay.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            // Keep the last valid delay while the field is being typed in
+            let Ok(secs) = text.trim().parse::<u64>() else {
+                return;
+            };
+
+            let mut cfg = config_auto_delay.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.auto_launch_delay_secs = secs;
+            save_config(&cfg);
+        }
+    });
+
+    // Cleanup hooks editor callback (one command per line)
+    let ui_handle_hooks = ui.as_weak();
+    let config_hooks = Arc::clone(&config);
+    ui.on_cleanup_hooks_edited(move |text| {
+        if let Some(ui) = ui_handle_hooks.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_hooks.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.cleanup_hooks = text
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect();
+            save_config(&cfg);
+        }
+    });
+
+    let ui_handle_update_hooks = ui.as_weak();
+    let config_update_hooks = Arc::clone(&config);
+    ui.on_update_hooks_edited(move |text| {
+        if let Some(ui) = ui_handle_update_hooks.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+
+            let mut cfg = config_update_hooks.lock().unwrap();
+            let game_cfg = cfg.game_configs.entry(app_id).or_default();
+            game_cfg.update_hooks = text
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect();
+            save_config(&cfg);
+        }
+    });
+
+    // Per-game links: added from the field next to the chips, opened in the browser
+    let ui_handle_add_link = ui.as_weak();
+    let config_add_link = Arc::clone(&config);
+    ui.on_add_link(move |text| {
+        let Some(ui) = ui_handle_add_link.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if app_id.is_empty() || text.trim().is_empty() {
+            return;
+        }
+        let link = match links::parse(&text) {
+            Ok(link) => link,
+            Err(e) => {
+                ui.set_link_error(e.into());
+                return;
+            }
+        };
+        ui.set_link_error(SharedString::new());
+
+        let mut cfg = config_add_link.lock().unwrap();
+        let game_cfg = cfg.game_configs.entry(app_id).or_default();
+        game_cfg.links.push(link);
+        ui.set_game_links(link_rows(&game_cfg.links));
+        save_config(&cfg);
+    });
+
+    let ui_handle_open_link = ui.as_weak();
+    ui.on_open_link(move |index| {
+        let Some(ui) = ui_handle_open_link.upgrade() else {
+            return;
+        };
+        if let Some(link) = ui.get_game_links().row_data(index as usize)
+            && let Err(e) = links::open(&link.url)
+        {
+            println!("Error: {}", e);
+        }
+    });
+
+    // PCGamingWiki: the page in the browser, or a summary of its fixes when online
+    let ui_handle_pcgw = ui.as_weak();
+    ui.on_open_pcgw(move || {
+        if let Some(ui) = ui_handle_pcgw.upgrade()
+            && let Err(e) = links::open(&pcgw::page_url(&ui.get_app_id()))
+        {
+            println!("Error: {}", e);
+        }
+    });
+
+    let ui_handle_pcgw_summary = ui.as_weak();
+    ui.on_fetch_pcgw_summary(move || {
+        let Some(ui) = ui_handle_pcgw_summary.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        ui.set_pcgw_summary("Looking up PCGamingWiki...".into());
+        let ui_weak = ui.as_weak();
+        thread::spawn(move || {
+            let text = match pcgw::fetch(&app_id) {
+                Ok(summary) => summary.to_text(),
+                Err(e) => {
+                    println!("Warning: {}", e);
+                    e
+                }
+            };
+            let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+                // The user may have picked another game while we were fetching
+                if ui.get_app_id() == app_id.as_str() {
+                    ui.set_pcgw_summary(text.into());
+                }
+            });
+        });
+    });
+
+    let ui_handle_remove_link = ui.as_weak();
+    let config_remove_link = Arc::clone(&config);
+    ui.on_remove_link(move |index| {
+        let Some(ui) = ui_handle_remove_link.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let mut cfg = config_remove_link.lock().unwrap();
+        let Some(game_cfg) = cfg.game_configs.get_mut(&app_id) else {
+            return;
+        };
+        let index = index as usize;
+        if index < game_cfg.links.len() {
+            game_cfg.links.remove(index);
+            ui.set_game_links(link_rows(&game_cfg.links));
+            save_config(&cfg);
+        }
+    });
+
+    // Tool library
+    let tools_window = ToolsWindow::new()?;
+    theme::apply(&tools_window);
+    let tool_updates: ToolUpdates = Arc::default();
+    refresh_tools(&ui, &tools_window, &config, &tool_updates);
+
+    let tools_handle_add = tools_window.as_weak();
+    let config_tools_add = Arc::clone(&config);
+    let tool_updates_add = Arc::clone(&tool_updates);
+    tools_window.on_add_tool(move |name, version, url, sha256, executable| {
+        let Some(window) = tools_handle_add.upgrade() else {
+            return;
+        };
+        let mut cfg = config_tools_add.lock().unwrap();
+        let name = name.trim().to_string();
+        if let Err(e) = tools::check_name(&name) {
+            window.set_status(e.into());
+            return;
+        }
+        if cfg.tools.iter().any(|tool| tool.name == name) {
+            window.set_status(format!("There's already a tool called {}", name).into());
+            return;
+        }
+        let sha256 = sha256.trim().to_string();
+        if let Err(e) = tools::check_sha256(&sha256) {
+            window.set_status(e.into());
+            return;
+        }
+        cfg.tools.push(tools::Tool {
+            name,
+            version: version.trim().to_string(),
+            url: url.trim().to_string(),
+            sha256,
+            executable: executable.trim().to_string(),
+        });
+        save_config(&cfg);
+        window.set_rows(tool_rows(&cfg.tools, &tool_updates_add.lock().unwrap()));
+        window.set_status(SharedString::new());
+    });
+
+    let ui_handle_download = ui.as_weak();
+    let tools_handle_download = tools_window.as_weak();
+    let config_tools_download = Arc::clone(&config);
+    let tool_updates_download = Arc::clone(&tool_updates);
+    tools_window.on_download(move |name| {
+        let Some(tool) = config_tools_download
+            .lock()
+            .unwrap()
+            .tools
+            .iter()
+            .find(|tool| tool.name == name.as_str())
+            .cloned()
+        else {
+            return;
+        };
+        start_tool_download(
+            ui_handle_download.clone(),
+            tools_handle_download.clone(),
+            Arc::clone(&config_tools_download),
+            Arc::clone(&tool_updates_download),
+            tool,
+        );
+    });
+
+    // One-click update: switch the tool to the new release and download it.
+    // Games reference tools by name, so their settings carry over untouched.
+    let ui_handle_update = ui.as_weak();
+    let tools_handle_update = tools_window.as_weak();
+    let config_tools_update = Arc::clone(&config);
+    let tool_updates_update = Arc::clone(&tool_updates);
+    tools_window.on_update(move |name| {
+        let Some(release) = tool_updates_update
+            .lock()
+            .unwrap()
+            .get(name.as_str())
+            .cloned()
+        else {
+            return;
+        };
+        // Without a digest from GitHub there's nothing to check the download against
+        if release.sha256.is_empty() {
+            let answer = MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title("Unverified update")
+                .set_description(format!(
+                    "GitHub publishes no checksum for {} {}, so the download can't be \
+                     verified.\n\nInstall it anyway?",
+                    name, release.version
+                ))
+                .set_buttons(MessageButtons::YesNo)
+                .show();
+            if answer != MessageDialogResult::Yes {
+                return;
+            }
+            println!(
+                "Warning: Updating {} to {} without a checksum, as requested",
+                name, release.version
+            );
+        }
+        let Some(tool) = config_tools_update
+            .lock()
+            .unwrap()
+            .tools
+            .iter()
+            .find(|tool| tool.name == name.as_str())
+            .cloned()
+        else {
+            return;
+        };
+        tool_updates_update.lock().unwrap().remove(name.as_str());
+        start_tool_update(
+            ui_handle_update.clone(),
+            tools_handle_update.clone(),
+            Arc::clone(&config_tools_update),
+            Arc::clone(&tool_updates_update),
+            tool,
+            release,
+        );
+    });
+
+    let ui_handle_check = ui.as_weak();
+    let tools_handle_check = tools_window.as_weak();
+    let config_tools_check = Arc::clone(&config);
+    let tool_updates_check = Arc::clone(&tool_updates);
+    tools_window.on_check_updates(move || {
+        if let Some(window) = tools_handle_check.upgrade() {
+            window.set_status("Checking for updates...".into());
+        }
+        start_tool_update_check(
+            ui_handle_check.clone(),
+            tools_handle_check.clone(),
+            Arc::clone(&config_tools_check),
+            Arc::clone(&tool_updates_check),
+        );
+    });
+
+    // Periodic check, as long as the user allows network access. In low-resource mode
+    // it waits until no game is running.
+    let ui_handle_tools_due = ui.as_weak();
+    let tools_handle_due = tools_window.as_weak();
+    let config_tools_due = Arc::clone(&config);
+    let tool_updates_due = Arc::clone(&tool_updates);
+    let check_tools_due = move || {
+        let cfg = config_tools_due.lock().unwrap();
+        let due = journal::now().saturating_sub(cfg.tools_checked_at)
+            >= tools::UPDATE_CHECK_INTERVAL_SECS;
+        let playing =
+            cfg.low_resource_mode && !backend::running_games(&native_games(&cfg)).is_empty();
+        if due && cfg.online_metadata && !cfg.tools.is_empty() && !safe_mode && !playing {
+            drop(cfg);
+            start_tool_update_check(
+                ui_handle_tools_due.clone(),
+                tools_handle_due.clone(),
+                Arc::clone(&config_tools_due),
+                Arc::clone(&tool_updates_due),
+            );
+        }
+    };
+    check_tools_due();
+    let tools_due_timer = Timer::default();
+    tools_due_timer.start(
+        TimerMode::Repeated,
+        std::time::Duration::from_secs(TOOL_UPDATE_POLL_SECS),
+        check_tools_due,
+    );
+
+    let tools_handle_remove = tools_window.as_weak();
+    let config_tools_remove = Arc::clone(&config);
+    let tool_updates_remove = Arc::clone(&tool_updates);
+    let ui_handle_remove = ui.as_weak();
+    tools_window.on_remove(move |name| {
+        let mut cfg = config_tools_remove.lock().unwrap();
+        if let Some(index) = cfg.tools.iter().position(|tool| tool.name == name.as_str()) {
+            let tool = cfg.tools.remove(index);
+            tools::remove(&tool);
+            tool_updates_remove.lock().unwrap().remove(&tool.name);
+            save_config(&cfg);
+        }
+        drop(cfg);
+        if let (Some(ui), Some(window)) =
+            (ui_handle_remove.upgrade(), tools_handle_remove.upgrade())
+        {
+            refresh_tools(&ui, &window, &config_tools_remove, &tool_updates_remove);
+        }
+    });
+
+    let ui_handle_use_tool = ui.as_weak();
+    let config_use_tool = Arc::clone(&config);
+    tools_window.on_use_tool(move |name| {
+        let Some(ui) = ui_handle_use_tool.upgrade() else {
+            return;
+        };
+        if ui.get_locked() {
+            return;
+        }
+        let path = format!("{}{}", tools::TOOL_REF_PREFIX, name);
+        update_programs(&ui, &config_use_tool, |programs| {
+            programs.push(ExeEntry::new(path));
+        });
+    });
+
+    let ui_handle_tools = ui.as_weak();
+    let tools_handle_open = tools_window.as_weak();
+    ui.on_show_tools(move || {
+        if let (Some(ui), Some(window)) = (ui_handle_tools.upgrade(), tools_handle_open.upgrade()) {
+            window.set_game_selected(!ui.get_app_id().is_empty() && !ui.get_locked());
+            let _ = window.show();
+        }
+    });
+
+    // Program list callbacks
+    let ui_handle_add_program = ui.as_weak();
+    let config_add_program = Arc::clone(&config);
+    ui.on_add_program(move || {
+        if let Some(ui) = ui_handle_add_program.upgrade()
+            && let Some(path) = pick_program()
+        {
+            update_programs(&ui, &config_add_program, |programs| {
+                programs.push(ExeEntry::new(path));
+            });
+        }
+    });
+
+    let ui_handle_browse_program = ui.as_weak();
+    let config_browse_program = Arc::clone(&config);
+    ui.on_browse_program(move |index| {
+        if let Some(ui) = ui_handle_browse_program.upgrade()
+            && let Some(path) = pick_program()
+        {
+            update_programs(&ui, &config_browse_program, |programs| {
+                if let Some(program) = programs.get_mut(index as usize) {
+                    program.path = path;
+                }
+            });
+        }
+    });
+
+    // A program from the "Recent" dropdown replaces the one at `index`, or is added for -1
+    let ui_handle_recent_program = ui.as_weak();
+    let config_recent_program = Arc::clone(&config);
+    ui.on_recent_program_selected(move |index, choice| {
+        let Some(ui) = ui_handle_recent_program.upgrade() else {
+            return;
+        };
+        let path = {
+            let cfg = config_recent_program.lock().unwrap();
+            recent_programs(&cfg, &ui.get_app_id())
+                .get(choice as usize)
+                .cloned()
+        };
+        let Some(path) = path else {
+            return;
+        };
+        update_programs(&ui, &config_recent_program, |programs| {
+            if index < 0 {
+                programs.push(ExeEntry::new(path));
+            } else if let Some(program) = programs.get_mut(index as usize) {
+                program.path = path;
+            }
+        });
+    });
+
+    let ui_handle_remove_program = ui.as_weak();
+    let config_remove_program = Arc::clone(&config);
+    ui.on_remove_program(move |index| {
+        if let Some(ui) = ui_handle_remove_program.upgrade() {
+            update_programs(&ui, &config_remove_program, |programs| {
+                if (index as usize) < programs.len() {
+                    programs.remove(index as usize);
+                }
+            });
+        }
+    });
+
+    // Moves a program one place up (offset -1) or down (offset 1)
+    let ui_handle_move_program = ui.as_weak();
+    let config_move_program = Arc::clone(&config);
+    ui.on_move_program(move |index, offset| {
+        if let Some(ui) = ui_handle_move_program.upgrade() {
+            update_programs(&ui, &config_move_program, |programs| {
+                let target = index + offset;
+                if index >= 0 && target >= 0 && (target as usize) < programs.len() {
+                    programs.swap(index as usize, target as usize);
+                }
+            });
+        }
+    });
+
+    // Winetricks verbs installed from the window; the output goes to the log panel
+    let ui_handle_verbs = ui.as_weak();
+    let config_verbs = Arc::clone(&config);
+    let installed_verbs = installed.clone();
+    ui.on_install_verbs(move |text| {
+        let Some(ui) = ui_handle_verbs.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let verbs = winetricks::parse_verbs(&text);
+        if verbs.is_empty() || ui.get_winetricks_busy() || config_verbs.lock().unwrap().locked {
+            return;
+        }
+        let Some(game) = installed_verbs.get(&app_id) else {
+            ui.set_winetricks_status("The game isn't installed, it has no prefix".into());
+            return;
+        };
+        let prefix = prefix_path(&game.library, &app_id);
+        let mut command = match winetricks::install_command(&app_id, &prefix, &verbs) {
+            Ok(command) => command,
+            Err(e) => {
+                println!("Error: {}", e);
+                ui.set_winetricks_status(e.into());
+                return;
+            }
+        };
+        println!("Installing {} into {}", verbs.join(" "), prefix.display());
+        ui.set_winetricks_busy(true);
+        ui.set_winetricks_status(
+            format!(
+                "Installing {}, the log panel shows the progress",
+                verbs.join(", ")
+            )
+            .into(),
+        );
+        let ui_weak = ui.as_weak();
+        let config = Arc::clone(&config_verbs);
+        thread::spawn(move || {
+            let status = command.status();
+            let message = match status {
+                Ok(status) if status.success() => {
+                    let mut cfg = config.lock().unwrap();
+                    let game_cfg = cfg.game_configs.entry(app_id.clone()).or_default();
+                    for verb in &verbs {
+                        if !game_cfg.verbs_installed.contains(verb) {
+                            game_cfg.verbs_installed.push(verb.clone());
+                        }
+                    }
+                    save_config(&cfg);
+                    format!("Installed {}", verbs.join(", "))
+                }
+                Ok(status) => format!("Installing {} failed ({})", verbs.join(", "), status),
+                Err(e) => format!("Could not run the installer: {}", e),
+            };
+            println!("{}", message);
+            let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+                ui.set_winetricks_busy(false);
+                if ui.get_app_id() == app_id.as_str() {
+                    ui.set_winetricks_status(message.into());
+                    let cfg = config.lock().unwrap();
+                    if let Some(game_cfg) = cfg.game_configs.get(&app_id) {
+                        ui.set_verbs_installed(game_cfg.verbs_installed.join(", ").into());
+                    }
+                }
+            });
+        });
+    });
+
+    // Files copied into the game: the list, and copying or removing them right away
+    let ui_handle_add_drop = ui.as_weak();
+    let config_add_drop = Arc::clone(&config);
+    ui.on_add_file_drop(move |folder| {
+        let Some(ui) = ui_handle_add_drop.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if config_add_drop.lock().unwrap().locked {
+            return;
+        }
+        let picked = if folder {
+            FileDialog::new().pick_folder()
+        } else {
+            FileDialog::new().pick_file()
+        };
+        let Some(path) = picked else {
+            return;
+        };
+        let mut cfg = config_add_drop.lock().unwrap();
+        if cfg.locked {
+            return;
+        }
+        let game_cfg = cfg.game_configs.entry(app_id).or_default();
+        game_cfg.files.push(deploy::FileDrop {
+            source: portal::host_path(path).to_string_lossy().into_owned(),
+            destination: String::new(),
+        });
+        ui.set_file_drops(file_drop_rows(&game_cfg.files));
+        save_config(&cfg);
+    });
+
+    let ui_handle_drop_destination = ui.as_weak();
+    let config_drop_destination = Arc::clone(&config);
+    ui.on_file_drop_destination_edited(move |index, text| {
+        let Some(ui) = ui_handle_drop_destination.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let mut cfg = config_drop_destination.lock().unwrap();
+        if cfg.locked {
+            return;
+        }
+        if let Some(drop) = cfg
+            .game_configs
+            .entry(app_id)
+            .or_default()
+            .files
+            .get_mut(index as usize)
+        {
+            drop.destination = text.trim().to_string();
+            save_config(&cfg);
+        }
+    });
+
+    let ui_handle_remove_drop = ui.as_weak();
+    let config_remove_drop = Arc::clone(&config);
+    ui.on_remove_file_drop(move |index| {
+        let Some(ui) = ui_handle_remove_drop.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let mut cfg = config_remove_drop.lock().unwrap();
+        if cfg.locked {
+            return;
+        }
+        let game_cfg = cfg.game_configs.entry(app_id).or_default();
+        if (index as usize) < game_cfg.files.len() {
+            game_cfg.files.remove(index as usize);
+        }
+        ui.set_file_drops(file_drop_rows(&game_cfg.files));
+        save_config(&cfg);
+    });
+
+    let ui_handle_deploy = ui.as_weak();
+    let config_deploy = Arc::clone(&config);
+    let installed_deploy = installed.clone();
+    ui.on_deploy_files(move || {
+        let Some(ui) = ui_handle_deploy.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let files = {
+            let cfg = config_deploy.lock().unwrap();
+            if cfg.locked {
+                return;
+            }
+            cfg.game_configs
+                .get(&app_id)
+                .map(|game_cfg| game_cfg.files.clone())
+                .unwrap_or_default()
+        };
+        let game = installed_deploy.get(&app_id);
+        let prefix = game.map(|game| prefix_path(&game.library, &app_id));
+        let result = deploy::deploy(
+            &app_id,
+            &files,
+            prefix.as_deref(),
+            game.map(|game| game.install_dir.as_path()),
+        );
+        let status = match result {
+            Ok(0) => "The files are already in place".to_string(),
+            Ok(count) => format!("Copied {} files", count),
+            Err(e) => {
+                println!("Error: Could not copy the game's files: {}", e);
+                format!("Could not copy: {}", e)
+            }
+        };
+        ui.set_files_status(status.into());
+    });
+
+    let ui_handle_verify = ui.as_weak();
+    ui.on_verify_deployed_files(move || {
+        if let Some(ui) = ui_handle_verify.upgrade() {
+            show_files_status(&ui, &ui.get_app_id(), true);
+        }
+    });
+
+    let ui_handle_undeploy = ui.as_weak();
+    ui.on_remove_deployed_files(move || {
+        let Some(ui) = ui_handle_undeploy.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let status = match deploy::remove(&app_id) {
+            Ok(0) => "No copied files to remove".to_string(),
+            Ok(count) => {
+                println!("Removed {} copied files from {}", count, app_id);
+                format!("Removed {} files, put back the ones they replaced", count)
+            }
+            Err(e) => {
+                println!("Error: Could not remove the copied files: {}", e);
+                format!("Could not remove: {}", e)
+            }
+        };
+        ui.set_files_status(status.into());
+    });
+
+    // Launch recipe editor callback. Only valid recipes are saved.
+    let ui_handle_recipe = ui.as_weak();
+    let config_recipe = Arc::clone(&config);
+    ui.on_recipe_edited(move |text| {
+        if let Some(ui) = ui_handle_recipe.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            match recipe::parse(&text) {
+                Ok(steps) => {
+                    ui.set_recipe_error(SharedString::new());
+                    let mut cfg = config_recipe.lock().unwrap();
+                    cfg.game_configs.entry(app_id).or_default().recipe = steps;
+                    save_config(&cfg);
+                }
+                Err(e) => ui.set_recipe_error(e.into()),
+            }
+        }
+    });
+
+    // Registry tweaks editor, one key\name = value per line
+    let ui_handle_registry = ui.as_weak();
+    let config_registry = Arc::clone(&config);
+    ui.on_registry_tweaks_edited(move |text| {
+        if let Some(ui) = ui_handle_registry.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            match registry::parse(&text) {
+                Ok(tweaks) => {
+                    ui.set_registry_error(SharedString::new());
+                    let mut cfg = config_registry.lock().unwrap();
+                    if cfg.locked {
+                        return;
+                    }
+                    cfg.game_configs.entry(app_id).or_default().registry = tweaks;
+                    save_config(&cfg);
+                }
+                Err(e) => ui.set_registry_error(e.into()),
+            }
+        }
+    });
+
+    // Arguments and working directory callbacks; like the verbs, they don't rebuild the list
+    let ui_handle_args = ui.as_weak();
+    let config_args = Arc::clone(&config);
+    ui.on_program_args_edited(move |index, text| {
+        if let Some(ui) = ui_handle_args.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            let mut cfg = config_args.lock().unwrap();
+            if let Some(program) = cfg
+                .game_configs
+                .entry(app_id)
+                .or_default()
+                .programs
+                .get_mut(index as usize)
+            {
+                program.args = split_args(&text);
+                save_config(&cfg);
+            }
+        }
+    });
+
+    let ui_handle_working_dir = ui.as_weak();
+    let config_working_dir = Arc::clone(&config);
+    ui.on_program_working_dir_edited(move |index, text| {
+        if let Some(ui) = ui_handle_working_dir.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            let mut cfg = config_working_dir.lock().unwrap();
+            if let Some(program) = cfg
+                .game_configs
+                .entry(app_id)
+                .or_default()
+                .programs
+                .get_mut(index as usize)
+            {
+                let text = text.trim();
+                program.working_dir = (!text.is_empty()).then(|| PathBuf::from(text));
+                save_config(&cfg);
+            }
         }
     });
 
-    // Browse user's exe 2 Callback
-    let ui_handle_browse2 = ui.as_weak();
-    let config_browse2 = Arc::clone(&config);
-    ui.on_browse_exe2(move || {
-        if let Some(ui) = ui_handle_browse2.upgrade() {
-            let app_id = ui.get_app_id().to_string();
-            if app_id.is_empty() {
+    let ui_handle_browse_dir = ui.as_weak();
+    let config_browse_dir = Arc::clone(&config);
+    ui.on_browse_program_working_dir(move |index| {
+        if let Some(ui) = ui_handle_browse_dir.upgrade()
+            && let Some(dir) = FileDialog::new().pick_folder()
+        {
+            let dir = portal::host_path(dir);
+            update_programs(&ui, &config_browse_dir, |programs| {
+                if let Some(program) = programs.get_mut(index as usize) {
+                    program.working_dir = Some(dir);
+                }
+            });
+        }
+    });
+
+    // Environment variables of a program; typing doesn't rebuild the list, adding and
+    // removing a variable does
+    let ui_handle_env_edit = ui.as_weak();
+    let config_env_edit = Arc::clone(&config);
+    ui.on_program_env_edited(move |index, var_index, name, value| {
+        if let Some(ui) = ui_handle_env_edit.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            let mut cfg = config_env_edit.lock().unwrap();
+            if cfg.locked {
+                return;
+            }
+            if let Some(var) = cfg
+                .game_configs
+                .entry(app_id)
+                .or_default()
+                .programs
+                .get_mut(index as usize)
+                .and_then(|program| program.env.get_mut(var_index as usize))
+            {
+                var.name = name.trim().to_string();
+                var.value = value.to_string();
+                save_config(&cfg);
+            }
+        }
+    });
+
+    let ui_handle_env_add = ui.as_weak();
+    let config_env_add = Arc::clone(&config);
+    ui.on_add_program_env(move |index| {
+        if let Some(ui) = ui_handle_env_add.upgrade() {
+            update_programs(&ui, &config_env_add, |programs| {
+                if let Some(program) = programs.get_mut(index as usize) {
+                    program.env.push(EnvVar::default());
+                }
+            });
+        }
+    });
+
+    let ui_handle_env_remove = ui.as_weak();
+    let config_env_remove = Arc::clone(&config);
+    ui.on_remove_program_env(move |index, var_index| {
+        if let Some(ui) = ui_handle_env_remove.upgrade() {
+            update_programs(&ui, &config_env_remove, |programs| {
+                if let Some(program) = programs.get_mut(index as usize)
+                    && (var_index as usize) < program.env.len()
+                {
+                    program.env.remove(var_index as usize);
+                }
+            });
+        }
+    });
+
+    // Required winetricks verbs callback. Doesn't rebuild the list, so the field keeps focus.
+    let ui_handle_verbs = ui.as_weak();
+    let config_verbs = Arc::clone(&config);
+    ui.on_program_verbs_edited(move |index, text| {
+        if let Some(ui) = ui_handle_verbs.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            let mut cfg = config_verbs.lock().unwrap();
+            if let Some(program) = cfg
+                .game_configs
+                .entry(app_id)
+                .or_default()
+                .programs
+                .get_mut(index as usize)
+            {
+                program.verbs = winetricks::parse_verbs(&text);
+                save_config(&cfg);
+            }
+        }
+    });
+
+    // Program sets: switching, saving the programs in use under a new name, deleting and
+    // picking the one a game starts with
+    let ui_handle_program_set = ui.as_weak();
+    let config_program_set = Arc::clone(&config);
+    ui.on_program_set_selected(move |name| {
+        if let Some(ui) = ui_handle_program_set.upgrade() {
+            update_program_sets(&ui, &config_program_set, |game_cfg| {
+                game_cfg.switch_program_set(&name);
+                Ok(())
+            });
+        }
+    });
+
+    let ui_handle_add_program_set = ui.as_weak();
+    let config_add_program_set = Arc::clone(&config);
+    ui.on_add_program_set(move |name| {
+        let Some(ui) = ui_handle_add_program_set.upgrade() else {
+            return;
+        };
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        update_program_sets(&ui, &config_add_program_set, |game_cfg| {
+            if game_cfg.program_set_names().contains(&name) {
+                return Err(format!("There's already a program set called {}", name));
+            }
+            // Starts as a copy of the programs in use
+            let programs = game_cfg.programs.clone();
+            game_cfg.switch_program_set(&name);
+            game_cfg.programs = programs;
+            Ok(())
+        });
+        ui.set_new_program_set_name(SharedString::new());
+    });
+
+    let ui_handle_delete_program_set = ui.as_weak();
+    let config_delete_program_set = Arc::clone(&config);
+    ui.on_delete_program_set(move || {
+        if let Some(ui) = ui_handle_delete_program_set.upgrade() {
+            update_program_sets(&ui, &config_delete_program_set, |game_cfg| {
+                if game_cfg.program_set.is_empty() {
+                    return Err("The default program set can't be deleted".to_string());
+                }
+                if game_cfg.default_program_set == game_cfg.program_set {
+                    game_cfg.default_program_set.clear();
+                }
+                game_cfg.program_set.clear();
+                game_cfg.programs = game_cfg
+                    .program_sets
+                    .remove(DEFAULT_PROGRAM_SET)
+                    .unwrap_or_default();
+                Ok(())
+            });
+        }
+    });
+
+    let ui_handle_default_program_set = ui.as_weak();
+    let config_default_program_set = Arc::clone(&config);
+    ui.on_set_default_program_set(move || {
+        if let Some(ui) = ui_handle_default_program_set.upgrade() {
+            update_program_sets(&ui, &config_default_program_set, |game_cfg| {
+                game_cfg.default_program_set = game_cfg.program_set.clone();
+                Ok(())
+            });
+        }
+    });
+
+    // When each program starts. The stage rebuilds the list, which shows the seconds field
+    // for after-start programs; the numbers don't, so their fields keep focus.
+    let ui_handle_stage = ui.as_weak();
+    let config_stage = Arc::clone(&config);
+    ui.on_program_stage_selected(move |index, label| {
+        if let Some(ui) = ui_handle_stage.upgrade() {
+            update_programs(&ui, &config_stage, |programs| {
+                if let Some(program) = programs.get_mut(index as usize) {
+                    program.stage = launch::Stage::from_label(&label);
+                }
+            });
+        }
+    });
+
+    let ui_handle_start_after = ui.as_weak();
+    let config_start_after = Arc::clone(&config);
+    ui.on_program_start_after_edited(move |index, text| {
+        if let Some(ui) = ui_handle_start_after.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            // Keep the last valid number while the field is being typed in
+            let Ok(secs) = text.trim().parse::<u64>() else {
+                return;
+            };
+            let mut cfg = config_start_after.lock().unwrap();
+            if let Some(program) = cfg
+                .game_configs
+                .entry(app_id)
+                .or_default()
+                .programs
+                .get_mut(index as usize)
+            {
+                program.start_after_secs = secs;
+                save_config(&cfg);
+            }
+        }
+    });
+
+    let ui_handle_program_delay = ui.as_weak();
+    let config_program_delay = Arc::clone(&config);
+    ui.on_program_delay_edited(move |index, text| {
+        if let Some(ui) = ui_handle_program_delay.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if app_id.is_empty() {
+                return;
+            }
+            // Empty goes back to the game's delay
+            let text = text.trim();
+            let delay_ms = if text.is_empty() {
+                None
+            } else {
+                match text.parse::<u64>() {
+                    Ok(ms) => Some(ms),
+                    Err(_) => return,
+                }
+            };
+            let mut cfg = config_program_delay.lock().unwrap();
+            if let Some(program) = cfg
+                .game_configs
+                .entry(app_id)
+                .or_default()
+                .programs
+                .get_mut(index as usize)
+            {
+                program.delay_ms = delay_ms;
+                save_config(&cfg);
+            }
+        }
+    });
+
+    // Read-only inspector for what Steam stored about the selected game
+    let inspector_window = VdfInspectorWindow::new()?;
+    theme::apply(&inspector_window);
+    let inspector = Rc::new(RefCell::new(inspector::Inspector::default()));
+
+    let inspector_handle_toggle = inspector_window.as_weak();
+    let inspector_toggle = Rc::clone(&inspector);
+    inspector_window.on_row_toggled(move |index| {
+        if let Some(window) = inspector_handle_toggle.upgrade() {
+            let mut state = inspector_toggle.borrow_mut();
+            state.toggle(index as usize);
+            window.set_rows(ModelRc::from(Rc::new(VecModel::from(state.rows()))));
+        }
+    });
+
+    let ui_handle_inspect = ui.as_weak();
+    let inspector_handle_open = inspector_window.as_weak();
+    let inspector_open = Rc::clone(&inspector);
+    ui.on_inspect_steam_config(move || {
+        let (Some(ui), Some(window)) =
+            (ui_handle_inspect.upgrade(), inspector_handle_open.upgrade())
+        else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+
+        let parsed = get_localconfig_path()
+            .ok_or_else(|| "Could not find Steam localconfig.vdf".to_string())
+            .and_then(|path| {
+                fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read localconfig.vdf: {}", e))
+            })
+            .and_then(|content| vdf::parse(&content));
+
+        let (heading, section) = match parsed {
+            Ok(root) => {
+                let mut path = LOCALCONFIG_APPS_PATH.to_vec();
+                path.push(&app_id);
+                match root.get_path(&path) {
+                    Some(section) => (
+                        format!("localconfig.vdf entry for app {}", app_id),
+                        Some(vdf::Node::Section(vec![(app_id.clone(), section.clone())])),
+                    ),
+                    None => (
+                        format!("No entry for app {} in localconfig.vdf yet", app_id),
+                        None,
+                    ),
+                }
+            }
+            Err(e) => (e, None),
+        };
+
+        let mut state = inspector_open.borrow_mut();
+        state.set_root(section);
+        // Start with the app's own section open
+        state.toggle(0);
+        window.set_heading(heading.into());
+        window.set_rows(ModelRc::from(Rc::new(VecModel::from(state.rows()))));
+        let _ = window.show();
+    });
+
+    // Advanced settings: polling intervals, delays and timeouts
+    let advanced_window = AdvancedWindow::new()?;
+    let show_tunables = |window: &AdvancedWindow, tunables: &tunables::Tunables| {
+        let defaults = tunables::Tunables::default();
+        let rows: Vec<TunableRow> = tunables::SETTINGS
+            .iter()
+            .map(|setting| TunableRow {
+                key: setting.key.into(),
+                label: setting.label.into(),
+                value: tunables
+                    .get(setting.key)
+                    .unwrap_or_default()
+                    .to_string()
+                    .into(),
+                unit: setting.unit.into(),
+                default_value: defaults
+                    .get(setting.key)
+                    .unwrap_or_default()
+                    .to_string()
+                    .into(),
+            })
+            .collect();
+        window.set_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+    };
+
+    let ui_handle_advanced = ui.as_weak();
+    let advanced_handle_open = advanced_window.as_weak();
+    let config_advanced_open = Arc::clone(&config);
+    ui.on_show_advanced_settings(move || {
+        if let (Some(ui), Some(window)) =
+            (ui_handle_advanced.upgrade(), advanced_handle_open.upgrade())
+        {
+            show_tunables(&window, &config_advanced_open.lock().unwrap().tunables);
+            window.set_locked(ui.get_locked());
+            window.set_status(SharedString::new());
+            let _ = window.show();
+        }
+    });
+
+    // Values are saved as they're typed, once they're valid
+    let advanced_handle_edit = advanced_window.as_weak();
+    let config_advanced_edit = Arc::clone(&config);
+    advanced_window.on_edited(move |key, text| {
+        let Some(window) = advanced_handle_edit.upgrade() else {
+            return;
+        };
+        let mut cfg = config_advanced_edit.lock().unwrap();
+        match cfg.tunables.set(&key, &text) {
+            Ok(()) => {
+                window.set_status(SharedString::new());
+                workers::set_limit(cfg.tunables.disk_jobs);
+                save_config(&cfg);
+            }
+            Err(e) => window.set_status(e.into()),
+        }
+    });
+
+    let advanced_handle_reset = advanced_window.as_weak();
+    let config_advanced_reset = Arc::clone(&config);
+    advanced_window.on_reset(move || {
+        let Some(window) = advanced_handle_reset.upgrade() else {
+            return;
+        };
+        let mut cfg = config_advanced_reset.lock().unwrap();
+        cfg.tunables = tunables::Tunables::default();
+        workers::set_limit(cfg.tunables.disk_jobs);
+        save_config(&cfg);
+        show_tunables(&window, &cfg.tunables);
+        window.set_status(SharedString::new());
+    });
+
+    // History of launch option changes, with per-change revert
+    let journal_window = JournalWindow::new()?;
+    theme::apply(&journal_window);
+    let app_names_journal: HashMap<String, String> = games
+        .iter()
+        .map(|(name, id)| (id.clone(), name.clone()))
+        .collect();
+    let show_journal = Arc::new(move |window: &JournalWindow| {
+        let rows: Vec<JournalRow> = journal::load()
+            .into_iter()
+            .enumerate()
+            .rev()
+            .map(|(index, entry)| JournalRow {
+                id: index as i32,
+                age: journal::format_age(entry.timestamp).into(),
+                game: app_names_journal
+                    .get(&entry.app_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("App {}", entry.app_id))
+                    .into(),
+                old_value: entry.old_value.unwrap_or_default().into(),
+                new_value: entry.new_value.into(),
+                reason: entry.reason.into(),
+            })
+            .collect();
+        window.set_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+    });
+
+    let journal_handle_revert = journal_window.as_weak();
+    let show_journal_revert = Arc::clone(&show_journal);
+    let config_revert = Arc::clone(&config);
+    journal_window.on_revert(move |id| {
+        let Some(window) = journal_handle_revert.upgrade() else {
+            return;
+        };
+        if config_revert.lock().unwrap().locked {
+            window.set_status("Protonic is locked, unlock it to revert changes".into());
+            return;
+        }
+        let Some(entry) = journal::load().into_iter().nth(id as usize) else {
+            return;
+        };
+        // Steam treats an empty value the same as no launch options
+        let old_value = entry.old_value.unwrap_or_default();
+        let app_id = entry.app_id;
+        let edited_app_id = app_id.clone();
+        let journal_handle = journal_handle_revert.clone();
+        let show_journal = Arc::clone(&show_journal_revert);
+        with_steam_closed(
+            "the revert",
+            move || set_launch_options(&edited_app_id, &old_value, "revert"),
+            move |reverted| {
+                let (Some(reverted), Some(window)) = (reverted, journal_handle.upgrade()) else {
+                    return;
+                };
+                let status = match reverted {
+                    Ok(()) => format!("Restored launch options for app {}", app_id),
+                    Err(e) => format!("Could not revert: {}", e),
+                };
+                println!("{}", status);
+                window.set_status(status.into());
+                show_journal(&window);
+            },
+        );
+    });
+
+    let journal_handle_open = journal_window.as_weak();
+    ui.on_show_change_history(move || {
+        if let Some(window) = journal_handle_open.upgrade() {
+            window.set_status(SharedString::new());
+            show_journal(&window);
+            let _ = window.show();
+        }
+    });
+
+    // Usage dashboard, computed from the local usage history
+    let usage_window = UsageWindow::new()?;
+    let show_usage = Rc::new(|window: &UsageWindow| {
+        let summary = history::summarize(&history::load(), journal::now());
+        let counted = |rows: &[(String, usize)]| -> Vec<UsageRow> {
+            rows.iter()
+                .take(10)
+                .map(|(name, count)| UsageRow {
+                    label: name.clone().into(),
+                    value: format!("{}×", count).into(),
+                })
+                .collect()
+        };
+        let weeks: Vec<UsageRow> = if summary.per_week.iter().all(|count| *count == 0) {
+            Vec::new()
+        } else {
+            summary
+                .per_week
+                .iter()
+                .enumerate()
+                .map(|(weeks_ago, count)| UsageRow {
+                    label: history::week_label(weeks_ago).into(),
+                    value: count.to_string().into(),
+                })
+                .collect()
+        };
+        window.set_weeks(ModelRc::from(Rc::new(VecModel::from(weeks))));
+        window.set_average_session(
+            summary
+                .average_session
+                .map(history::format_length)
+                .unwrap_or_default()
+                .into(),
+        );
+        window.set_programs(ModelRc::from(Rc::new(VecModel::from(counted(
+            &summary.programs,
+        )))));
+        window.set_crashes(ModelRc::from(Rc::new(VecModel::from(counted(
+            &summary.crashes,
+        )))));
+    });
+
+    let config_usage = Arc::clone(&config);
+    usage_window.on_enabled_toggled(move |enabled| {
+        let mut cfg = config_usage.lock().unwrap();
+        cfg.usage_history = enabled;
+        save_config(&cfg);
+        history::set_enabled(enabled);
+    });
+
+    let usage_handle_clear = usage_window.as_weak();
+    let show_usage_clear = Rc::clone(&show_usage);
+    usage_window.on_clear(move || {
+        let Some(window) = usage_handle_clear.upgrade() else {
+            return;
+        };
+        let confirmed = MessageDialog::new()
+            .set_title("Clear usage history")
+            .set_description("Delete the recorded sessions and programs?")
+            .set_buttons(MessageButtons::YesNo)
+            .show();
+        if confirmed != MessageDialogResult::Yes {
+            return;
+        }
+        if let Err(e) = history::clear() {
+            println!("Error: {}", e);
+        }
+        show_usage_clear(&window);
+    });
+
+    let config_ask_notes = Arc::clone(&config);
+    usage_window.on_ask_notes_toggled(move |enabled| {
+        let mut cfg = config_ask_notes.lock().unwrap();
+        cfg.session_notes = enabled;
+        save_config(&cfg);
+    });
+
+    let usage_handle_open = usage_window.as_weak();
+    let config_usage_open = Arc::clone(&config);
+    ui.on_show_usage(move || {
+        if let Some(window) = usage_handle_open.upgrade() {
+            let cfg = config_usage_open.lock().unwrap();
+            window.set_enabled(cfg.usage_history);
+            window.set_ask_notes(cfg.session_notes);
+            drop(cfg);
+            show_usage(&window);
+            let _ = window.show();
+        }
+    });
+
+    // Add protonhax to every Proton game at once, after a preview
+    let bulk_window = BulkConfigureWindow::new()?;
+    theme::apply(&bulk_window);
+    let bulk_rows: Rc<VecModel<BulkRow>> = Rc::new(VecModel::default());
+    bulk_window.set_rows(ModelRc::from(Rc::clone(&bulk_rows)));
+
+    let bulk_rows_toggle = Rc::clone(&bulk_rows);
+    bulk_window.on_row_toggled(move |index, selected| {
+        let index = index as usize;
+        if let Some(mut row) = bulk_rows_toggle.row_data(index) {
+            row.selected = selected;
+            bulk_rows_toggle.set_row_data(index, row);
+        }
+    });
+
+    let bulk_rows_all = Rc::clone(&bulk_rows);
+    bulk_window.on_select_all(move |selected| {
+        for index in 0..bulk_rows_all.row_count() {
+            if let Some(mut row) = bulk_rows_all.row_data(index) {
+                row.selected = selected;
+                bulk_rows_all.set_row_data(index, row);
+            }
+        }
+    });
+
+    let bulk_handle_apply = bulk_window.as_weak();
+    let bulk_rows_apply = Rc::clone(&bulk_rows);
+    // The rows are found again through the window once Steam has been closed, since the
+    // model can't leave the UI thread
+    bulk_window.on_apply(move || {
+        let selected: Vec<BulkRow> = bulk_rows_apply.iter().filter(|row| row.selected).collect();
+        if selected.is_empty() {
+            return;
+        }
+        let changes: Vec<(String, String)> = selected
+            .iter()
+            .map(|row| (row.app_id.to_string(), row.proposed.to_string()))
+            .collect();
+        let bulk_handle = bulk_handle_apply.clone();
+        with_steam_closed(
+            "the new launch options",
+            move || {
+                let changes: Vec<(&str, &str)> = changes
+                    .iter()
+                    .map(|(app_id, proposed)| (app_id.as_str(), proposed.as_str()))
+                    .collect();
+                set_many_launch_options(&changes, "bulk configure")
+            },
+            move |failed| {
+                let (Some(failed), Some(window)) = (failed, bulk_handle.upgrade()) else {
+                    return;
+                };
+
+                let name = |app_id: &str| {
+                    selected
+                        .iter()
+                        .find(|row| row.app_id == app_id)
+                        .map_or(app_id.to_string(), |row| row.name.to_string())
+                };
+                let configured = selected.len() - failed.len();
+                let mut summary = format!("Configured {} of {} games", configured, selected.len());
+                for (app_id, e) in &failed {
+                    println!("Warning: Could not configure {}: {}", app_id, e);
+                    summary.push_str(&format!("\n\nFailed: {} - {}", name(app_id), e));
+                }
+                let skipped = window.get_skipped();
+                if !skipped.is_empty() {
+                    summary.push_str(&format!("\n\nLeft out: {}", skipped));
+                }
+                println!("{}", summary);
+                MessageDialog::new()
+                    .set_level(if failed.is_empty() {
+                        MessageLevel::Info
+                    } else {
+                        MessageLevel::Warning
+                    })
+                    .set_title("Configure all games")
+                    .set_description(summary)
+                    .show();
+
+                // Failed games stay listed so they can be tried again
+                let remaining: Vec<BulkRow> = selected
+                    .into_iter()
+                    .filter(|row| {
+                        failed
+                            .iter()
+                            .any(|(app_id, _)| row.app_id == app_id.as_str())
+                    })
+                    .collect();
+                window.set_status(format!("Configured {} games", configured).into());
+                if let Some(rows) = window
+                    .get_rows()
+                    .as_any()
+                    .downcast_ref::<VecModel<BulkRow>>()
+                {
+                    rows.set_vec(remaining);
+                }
+            },
+        );
+    });
+
+    let bulk_handle_open = bulk_window.as_weak();
+    let games_bulk = games.clone();
+    let installed_bulk = installed.clone();
+    let config_bulk = Arc::clone(&config);
+    ui.on_configure_all_games(move || {
+        let Some(window) = bulk_handle_open.upgrade() else {
+            return;
+        };
+        let root = match read_localconfig() {
+            Ok((_, root)) => root,
+            Err(e) => {
+                println!("Error: {}", e);
+                return;
+            }
+        };
+        let cfg = config_bulk.lock().unwrap();
+        let mut skipped = Vec::new();
+        let rows: Vec<BulkRow> = games_bulk
+            .iter()
+            .filter(|(_, id)| installed_bulk.get(*id).is_some_and(|g| g.uses_proton))
+            .filter_map(|(name, id)| {
+                // Games Steam hasn't written a section for yet can't be configured, except
+                // Family Shared ones, which get a section when they're written
+                let current = match launch_options_in(&root, id) {
+                    Ok(current) => current.unwrap_or_default(),
+                    Err(_)
+                        if installed_bulk
+                            .get(id)
+                            .is_some_and(|g| g.shared_from.is_some()) =>
+                    {
+                        String::new()
+                    }
+                    Err(_) => return None,
+                };
+                let template = launch_template(&cfg, id);
+                if wrapper::is_configured(&template, &current) {
+                    return None;
+                }
+                let proposed = match wrapper::apply(&template, &current) {
+                    Ok(options) if wrapper::conflicts(&options).is_empty() => options,
+                    _ => {
+                        println!("Skipping {}: wrapper conflicts with '{}'", name, current);
+                        skipped.push(format!("{} (conflicting wrappers)", name));
+                        return None;
+                    }
+                };
+                Some(BulkRow {
+                    app_id: id.into(),
+                    name: name.into(),
+                    proposed: proposed.into(),
+                    current: current.into(),
+                    selected: true,
+                })
+            })
+            .collect();
+        bulk_rows.set_vec(rows);
+        window.set_skipped(skipped.join(", ").into());
+        window.set_status(SharedString::new());
+        let _ = window.show();
+    });
+
+    // Backup and restore of all games' launch options
+    ui.on_export_launch_options(|| {
+        let Some(path) = FileDialog::new()
+            .add_filter("TOML", &["toml"])
+            .set_file_name("launch-options.toml")
+            .save_file()
+        else {
+            return;
+        };
+        let (level, message) = match backup::export_launch_options(&path) {
+            Ok(count) => (
+                MessageLevel::Info,
+                format!(
+                    "Saved launch options for {} games to {}",
+                    count,
+                    path.display()
+                ),
+            ),
+            Err(e) => (MessageLevel::Error, format!("Export failed: {}", e)),
+        };
+        println!("{}", message);
+        MessageDialog::new()
+            .set_level(level)
+            .set_title("Export launch options")
+            .set_description(message)
+            .show();
+    });
+
+    // Undo Protonic's last write to localconfig.vdf from its snapshot
+    let ui_handle_restore = ui.as_weak();
+    let config_restore = Arc::clone(&config);
+    ui.on_restore_previous_localconfig(move || {
+        let answer = MessageDialog::new()
+            .set_level(MessageLevel::Warning)
+            .set_title("Restore previous config")
+            .set_description("Put back localconfig.vdf as it was before Protonic's last change?")
+            .set_buttons(MessageButtons::YesNo)
+            .show();
+        if answer != MessageDialogResult::Yes {
+            return;
+        }
+        let ui_handle = ui_handle_restore.clone();
+        let config = Arc::clone(&config_restore);
+        with_steam_closed(
+            "the restore",
+            backup::restore_previous_localconfig,
+            move |restored| {
+                let Some(restored) = restored else {
+                    return;
+                };
+                let (level, message) = match restored {
+                    Ok(taken) => (
+                        MessageLevel::Info,
+                        format!(
+                            "Restored localconfig.vdf from {}",
+                            journal::format_age(taken)
+                        ),
+                    ),
+                    Err(e) => (MessageLevel::Error, format!("Restore failed: {}", e)),
+                };
+                println!("{}", message);
+                MessageDialog::new()
+                    .set_level(level)
+                    .set_title("Restore previous config")
+                    .set_description(message)
+                    .show();
+
+                if let Some(ui) = ui_handle.upgrade() {
+                    let cfg = config.lock().unwrap();
+                    let app_id = ui.get_app_id().to_string();
+                    if cfg.auto_configure && !safe_mode && !app_id.is_empty() {
+                        ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
+                    }
+                }
+            },
+        );
+    });
+
+    // Write the selected game's setup as a bash script that works without Protonic
+    let ui_handle_script = ui.as_weak();
+    let config_script = Arc::clone(&config);
+    let installed_script = installed.clone();
+    let app_names_script: HashMap<String, String> = games
+        .iter()
+        .map(|(name, id)| (id.clone(), name.clone()))
+        .collect();
+    ui.on_export_script(move || {
+        let Some(ui) = ui_handle_script.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let game_name = app_names_script
+            .get(&app_id)
+            .cloned()
+            .unwrap_or_else(|| format!("App {}", app_id));
+        let prefix = installed_script
+            .get(&app_id)
+            .map(|game| prefix_path(&game.library, &app_id));
+
+        // Tool references only mean something to Protonic, so the script gets real paths
+        let script = {
+            let cfg = config_script.lock().unwrap();
+            let game_cfg = cfg.game_configs.get(&app_id).cloned().unwrap_or_default();
+            let resolve = |path: &str| tools::resolve_program(&cfg.tools, path);
+            resolve_programs(&cfg.tools, &game_cfg.programs).and_then(|programs| {
+                let mut recipe = game_cfg.recipe;
+                recipe::resolve_programs(&mut recipe, &resolve)?;
+                Ok(script::launch_script(
+                    &app_id,
+                    &game_name,
+                    &programs,
+                    &recipe,
+                    &launch_template(&cfg, &app_id),
+                    prefix.as_deref(),
+                ))
+            })
+        };
+        let script = match script {
+            Ok(script) => script,
+            Err(e) => {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Error)
+                    .set_title("Export as script")
+                    .set_description(format!("Export failed: {}", e))
+                    .show();
+                return;
+            }
+        };
+
+        let file_name: String = game_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        let Some(path) = FileDialog::new()
+            .add_filter("Shell script", &["sh"])
+            .set_file_name(format!("{}.sh", file_name.trim_matches('-')))
+            .save_file()
+        else {
+            return;
+        };
+        let result = fs::write(&path, script).and_then(|()| {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+        });
+        let (level, message) = match result {
+            Ok(()) => (
+                MessageLevel::Info,
+                format!("Saved {}'s launch script to {}", game_name, path.display()),
+            ),
+            Err(e) => (MessageLevel::Error, format!("Export failed: {}", e)),
+        };
+        println!("{}", message);
+        MessageDialog::new()
+            .set_level(level)
+            .set_title("Export as script")
+            .set_description(message)
+            .show();
+    });
+
+    let ui_handle_import = ui.as_weak();
+    let config_import = Arc::clone(&config);
+    ui.on_import_launch_options(move || {
+        let Some(path) = FileDialog::new().add_filter("TOML", &["toml"]).pick_file() else {
+            return;
+        };
+        let ui_handle = ui_handle_import.clone();
+        let config = Arc::clone(&config_import);
+        with_steam_closed("the imported launch options", move || {
+            backup::import_launch_options(&path)
+        }, move |imported| {
+        let Some(imported) = imported else {
+            return;
+        };
+        let (level, message) = match imported {
+            Ok((restored, failed)) if failed.is_empty() => (
+                MessageLevel::Info,
+                format!("Restored launch options for {} games", restored),
+            ),
+            Ok((restored, failed)) => (
+                MessageLevel::Warning,
+                format!(
+                    "Restored launch options for {} games. These aren't in Steam's config yet \
+                     (launch them once, then import again): {}",
+                    restored,
+                    failed.join(", ")
+                ),
+            ),
+            Err(e) => (MessageLevel::Error, format!("Import failed: {}", e)),
+        };
+        println!("{}", message);
+        MessageDialog::new()
+            .set_level(level)
+            .set_title("Import launch options")
+            .set_description(message)
+            .show();
+
+        if let Some(ui) = ui_handle.upgrade() {
+            let cfg = config.lock().unwrap();
+            let app_id = ui.get_app_id().to_string();
+            if cfg.auto_configure && !safe_mode && !app_id.is_empty() {
+                ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
+            }
+        }
+        });
+    });
+
+    // Pre-populate games from SteamTinkerLaunch configs and protontricks'd prefixes.
+    // Only fills in what Protonic doesn't have yet.
+    let ui_handle_migrate = ui.as_weak();
+    let config_migrate = Arc::clone(&config);
+    let installed_migrate = installed.clone();
+    ui.on_import_from_other_tools(move || {
+        // Everything is read from disk first, so the config is only locked to fill it in
+        let imported_games = migrate::steamtinkerlaunch_games();
+        let prefix_verbs: HashMap<String, Vec<String>> = installed_migrate
+            .iter()
+            .map(|(app_id, game)| {
+                let prefix = prefix_path(&game.library, app_id);
+                (app_id.clone(), migrate::protontricks_verbs(&prefix))
+            })
+            .filter(|(_, verbs)| !verbs.is_empty())
+            .collect();
+
+        let mut cfg = config_migrate.lock().unwrap();
+        let mut programs = 0;
+        let mut wrappers = 0;
+        for imported in imported_games {
+            let game_cfg = cfg.game_configs.entry(imported.app_id).or_default();
+            if game_cfg.programs.is_empty() {
+                programs += imported.programs.len();
+                game_cfg.programs = imported.programs.into_iter().map(ExeEntry::new).collect();
+            }
+            if game_cfg.wrapper.is_empty()
+                && let Some(template) = imported.template
+            {
+                game_cfg.wrapper = template;
+                wrappers += 1;
+            }
+        }
+
+        let mut verb_games = 0;
+        for (app_id, game_cfg) in cfg.game_configs.iter_mut() {
+            // Verbs are per game in protontricks, so they go to the first program
+            let Some(first) = game_cfg.programs.first_mut() else {
+                continue;
+            };
+            if !first.verbs.is_empty() {
+                continue;
+            }
+            if let Some(verbs) = prefix_verbs.get(app_id) {
+                first.verbs = verbs.clone();
+                verb_games += 1;
+            }
+        }
+        save_config(&cfg);
+        drop(cfg);
+
+        let message = if programs + wrappers + verb_games == 0 {
+            "Nothing new to import from SteamTinkerLaunch or protontricks".to_string()
+        } else {
+            format!(
+                "Imported {} programs and {} wrapper settings from SteamTinkerLaunch, \
+                 and winetricks verbs for {} games from their prefixes",
+                programs, wrappers, verb_games
+            )
+        };
+        println!("{}", message);
+        MessageDialog::new()
+            .set_level(MessageLevel::Info)
+            .set_title("Import from other tools")
+            .set_description(message)
+            .show();
+
+        // Show what was filled in for the selected game
+        if let Some(ui) = ui_handle_migrate.upgrade() {
+            let app_id = ui.get_app_id().to_string();
+            if !app_id.is_empty() {
+                let mut cfg = config_migrate.lock().unwrap();
+                let name = cfg.last_game_name.clone();
+                select_game(
+                    &ui,
+                    &mut cfg,
+                    &app_id,
+                    &name,
+                    installed_migrate.get(&app_id),
+                    safe_mode,
+                );
+            }
+        }
+    });
+
+    // Launch logic
+    let config_launch = Arc::clone(&config);
+    let audio_launch = audio.clone();
+    let tracker_launch = tracker.clone();
+    let installed_launch = installed.clone();
+    let config_terminal_launch = Arc::clone(&config);
+    let overrides_launch = Arc::clone(&injection_overrides);
+    let ui_handle_launch = ui.as_weak();
+    let app_names_launch: HashMap<String, String> = games
+        .iter()
+        .map(|(name, id)| (id.clone(), name.clone()))
+        .collect();
+    ui.on_run_protonhax(move |app_id| {
+        let app_id_str = app_id.to_string();
+        let game_name = app_names_launch
+            .get(&app_id_str)
+            .cloned()
+            .unwrap_or_else(|| format!("App {}", app_id_str));
+
+        // Get config values
+        let (launch, auto_configure, sounds, notifications, overlay, template) = {
+            let cfg = config_launch.lock().unwrap();
+            (
+                launch::Launch::prepare(
+                    &cfg,
+                    &app_id_str,
+                    &game_name,
+                    installed_launch.get(&app_id_str),
+                    false,
+                ),
+                cfg.auto_configure && !safe_mode,
+                sounds_allowed(&cfg, &app_id_str) && !safe_mode,
+                notifications_allowed(&cfg, &app_id_str) && !safe_mode,
+                overlay_allowed(&cfg, &app_id_str) && !safe_mode,
+                launch_template(&cfg, &app_id_str),
+            )
+        };
+        let launch = match launch {
+            Ok(launch) => launch,
+            Err(e) => {
+                println!("Error: {}", e);
+                if notifications {
+                    notify::send(&format!("Can't launch {}", game_name), &e, None);
+                }
+                return;
+            }
+        };
+
+        // Offer to install winetricks verbs the programs need before the game starts
+        if let Some(prefix) = &launch.prefix
+            && !safe_mode
+        {
+            let required: Vec<String> = launch
+                .programs
+                .iter()
+                .flat_map(|program| program.verbs.iter().cloned())
+                .collect();
+            let missing = winetricks::missing_verbs(prefix, &required);
+            if !missing.is_empty()
+                && offer_verb_install(&app_id_str, prefix, &missing, &config_terminal_launch)
+            {
                 return;
             }
+        }
+
+        // Everything after auto-configure, which may have to wait for Steam to close first
+        let audio_launch = audio_launch.clone();
+        let tracker_launch = tracker_launch.clone();
+        let ui_handle_launch = ui_handle_launch.clone();
+        let overrides_launch = Arc::clone(&overrides_launch);
+        let configured_game_name = game_name.clone();
+        let configured_app_id = app_id_str.clone();
+        let cooldown_secs = launch.tunables.auto_configure_cooldown_secs;
+        let proceed = move || {
+            // Play launch game audio
+            if sounds {
+                audio_launch.play(SoundEvent::LaunchGame);
+            }
 
-            if let Some(path) = FileDialog::new()
-                .add_filter("Executables", &["exe"])
-                .add_filter("All Files", &["*"])
-                .pick_file()
+            let icon = steam::locate()
+                .ok()
+                .and_then(|steam| store::local_icon(steam.path(), &app_id_str));
+            if notifications {
+                notify::send(
+                    &format!("Launching {}", game_name),
+                    &launch.instructions(),
+                    icon.as_deref(),
+                );
+            }
+            if let Some(note) = launch.deploy_files()
+                && notifications
             {
-                let path_str = path.to_string_lossy().to_string();
-                ui.set_exe2_path(path_str.clone().into());
+                notify::send(&game_name, &note, None);
+            }
+            if safe_mode && launch.has_programs() {
+                println!(
+                    "Safe mode: hotkey disabled, not waiting for {}",
+                    launch.trigger_name()
+                );
+            }
+            // Before-game programs are started first, off the UI thread
+            if safe_mode || !launch.has_programs() {
+                launch::start_game(&app_id_str);
+                return;
+            }
+
+            // Show the recipe's steps as pending until the hotkey is pressed
+            if let Some(ui) = ui_handle_launch.upgrade() {
+                ui.set_recipe_progress(recipe_rows(&recipe::pending_progress(&launch.recipe)));
+            }
 
-                // Save to config
-                let mut cfg = config_browse2.lock().unwrap();
-                let game_cfg = cfg.game_configs.entry(app_id).or_default();
-                game_cfg.exe2_path = path_str;
-                let _ = confy::store("protonic", None, &*cfg);
+            let audio = audio_launch.clone();
+            let tracker = tracker_launch.clone();
+            let ui_progress = ui_handle_launch.clone();
+            let overrides = Arc::clone(&overrides_launch);
+            // Tell the player the hotkey is armed once the game is up
+            if overlay {
+                let (backend, app_id, game_name) = (
+                    launch.backend,
+                    launch.app_id.clone(),
+                    launch.game_name.clone(),
+                );
+                let instructions = launch.instructions();
+                thread::spawn(move || {
+                    for _ in 0..OVERLAY_ARMED_WAIT_SECS {
+                        if backend.is_running(&app_id) {
+                            overlay::show(&game_name, &instructions, false);
+                            return;
+                        }
+                        thread::sleep(std::time::Duration::from_secs(1));
+                    }
+                });
+            }
+
+            thread::spawn(move || {
+                // Asked once, before the first program starts
+                let consent = OnceCell::new();
+                let allow = || {
+                    *consent.get_or_init(|| {
+                        launch.anti_cheat.is_none_or(|anti_cheat| {
+                            allow_injection(
+                                &launch.app_id,
+                                &launch.game_name,
+                                launch.backend,
+                                anti_cheat,
+                                &overrides,
+                            )
+                        })
+                    })
+                };
+                if launch.has_stage(launch::Stage::BeforeGame) && allow() {
+                    launch.start_before_game(&tracker);
+                }
+                launch::start_game(&launch.app_id);
+
+                // Parallel recipe steps report from several threads, and slint::Weak isn't Sync
+                let ui_progress = Mutex::new(ui_progress);
+                let show_progress = |progress: Vec<recipe::StepProgress>| {
+                    let ui_progress = ui_progress.lock().unwrap().clone();
+                    let _ = ui_progress.upgrade_in_event_loop(move |ui| {
+                        ui.set_recipe_progress(recipe_rows(&progress));
+                    });
+                };
+                let report = |summary: Result<String, String>| {
+                    // Play program launch audio
+                    if sounds && summary.is_ok() {
+                        audio.play(SoundEvent::LaunchProgram);
+                    }
+                    if overlay {
+                        let (Ok(message) | Err(message)) = &summary;
+                        overlay::show(&launch.game_name, message, summary.is_err());
+                    }
+                    if notifications {
+                        let (Ok(message) | Err(message)) = summary;
+                        notify::send(&launch.game_name, &message, icon.as_deref());
+                    }
+                };
+                launch.run_schedule(&tracker, &allow, &show_progress, &report);
+            });
+        };
+
+        // Auto-configure launch options if enabled
+        let report_configured = move |configured: Option<Result<String, String>>| match configured
+            .unwrap_or_else(|| Err("Left unchanged while Steam is running".to_string()))
+        {
+            Ok(msg) => {
+                println!("{}", msg);
+                if notifications && msg != ALREADY_CONFIGURED {
+                    notify::send(&configured_game_name, &msg, None);
+                }
+            }
+            Err(e) => {
+                println!("Warning: Could not configure launch options: {}", e);
+                if notifications {
+                    notify::send(
+                        &configured_game_name,
+                        &format!("Launch options: {}", e),
+                        None,
+                    );
+                }
+            }
+        };
+        if auto_configure && launch_options_pending(&configured_app_id, &template) {
+            with_steam_closed(
+                "the new launch options",
+                move || configure_launch_options(&configured_app_id, &template, cooldown_secs),
+                move |configured| {
+                    report_configured(configured);
+                    proceed();
+                },
+            );
+        } else {
+            if auto_configure {
+                report_configured(Some(Ok(ALREADY_CONFIGURED.to_string())));
             }
+            proceed();
         }
     });
 
-    // Clear exe 1 Callback
-    let ui_handle_clear1 = ui.as_weak();
-    let config_clear1 = Arc::clone(&config);
-    ui.on_clear_exe1(move || {
-        if let Some(ui) = ui_handle_clear1.upgrade() {
+    // Running protonhax sessions, including games started outside Protonic
+    let app_names: HashMap<String, String> = games
+        .iter()
+        .map(|(name, id)| (id.clone(), name.clone()))
+        .collect();
+    let app_names_session = app_names.clone();
+    let app_names_processes = app_names.clone();
+    let app_names_updates = app_names.clone();
+    let app_names_presets = app_names.clone();
+    let app_names_launch_options = app_names.clone();
+
+    // Mini mode: a small always-on-top window standing in for the main one
+    let mini_window = MiniWindow::new()?;
+
+    let ui_handle_mini = ui.as_weak();
+    let mini_handle_open = mini_window.as_weak();
+    let app_names_mini = app_names.clone();
+    ui.on_show_mini_mode(move || {
+        if let (Some(ui), Some(mini)) = (ui_handle_mini.upgrade(), mini_handle_open.upgrade()) {
             let app_id = ui.get_app_id().to_string();
-            ui.set_exe1_path(SharedString::new());
+            mini.set_game_name(
+                app_names_mini
+                    .get(&app_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("App {}", app_id))
+                    .into(),
+            );
+            mini.set_can_launch(ui.get_programs().row_count() > 0 || ui.get_recipe() != "");
+            let _ = mini.show();
+            let _ = ui.hide();
+        }
+    });
 
-            if !app_id.is_empty() {
-                let mut cfg = config_clear1.lock().unwrap();
-                if let Some(game_cfg) = cfg.game_configs.get_mut(&app_id) {
-                    game_cfg.exe1_path = String::new();
-                    let _ = confy::store("protonic", None, &*cfg);
-                }
+    let ui_handle_mini_launch = ui.as_weak();
+    mini_window.on_launch(move || {
+        if let Some(ui) = ui_handle_mini_launch.upgrade() {
+            ui.invoke_run_protonhax(ui.get_app_id());
+        }
+    });
+
+    // Stopping waits out the kill grace period, so it happens off the UI thread
+    let ui_handle_mini_stop = ui.as_weak();
+    let config_mini_stop = Arc::clone(&config);
+    let tracker_mini_stop = tracker.clone();
+    mini_window.on_stop_programs(move || {
+        let Some(ui) = ui_handle_mini_stop.upgrade() else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        let (grace, hooks) = {
+            let cfg = config_mini_stop.lock().unwrap();
+            let hooks = cfg
+                .game_configs
+                .get(&app_id)
+                .map(|g| g.cleanup_hooks.clone())
+                .unwrap_or_default();
+            (
+                std::time::Duration::from_millis(cfg.tunables.kill_grace_ms),
+                hooks,
+            )
+        };
+        let tracker = tracker_mini_stop.clone();
+        thread::spawn(move || {
+            if tracker.kill_app(&app_id, grace) {
+                process::run_cleanup_hooks(&hooks);
+            } else {
+                println!("No programs running for {}", app_id);
+            }
+        });
+    });
+
+    let ui_handle_mini_replay = ui.as_weak();
+    let tracker_mini_replay = tracker.clone();
+    mini_window.on_save_replay(move || {
+        if let Some(ui) = ui_handle_mini_replay.upgrade() {
+            match recorder::save_replay(&tracker_mini_replay, &ui.get_app_id()) {
+                Ok(()) => println!("Saving replay"),
+                Err(e) => println!("Warning: Could not save replay: {}", e),
             }
         }
     });
 
-    // Clear exe 2 Callback
-    let ui_handle_clear2 = ui.as_weak();
-    let config_clear2 = Arc::clone(&config);
-    ui.on_clear_exe2(move || {
-        if let Some(ui) = ui_handle_clear2.upgrade() {
-            let app_id = ui.get_app_id().to_string();
-            ui.set_exe2_path(SharedString::new());
+    let ui_handle_mini_expand = ui.as_weak();
+    let mini_handle_expand = mini_window.as_weak();
+    let expand = move || {
+        if let Some(ui) = ui_handle_mini_expand.upgrade() {
+            let _ = ui.show();
+        }
+        if let Some(mini) = mini_handle_expand.upgrade() {
+            let _ = mini.hide();
+        }
+    };
+    mini_window.on_expand(expand.clone());
+    // Closing the mini window brings the main one back instead of quitting
+    mini_window.window().on_close_requested(move || {
+        expand();
+        slint::CloseRequestResponse::HideWindow
+    });
 
-            if !app_id.is_empty() {
-                let mut cfg = config_clear2.lock().unwrap();
-                if let Some(game_cfg) = cfg.game_configs.get_mut(&app_id) {
-                    game_cfg.exe2_path = String::new();
-                    let _ = confy::store("protonic", None, &*cfg);
+    // Session notes: the game and session the notes window is about
+    let notes_window = NotesWindow::new()?;
+    let notes_session: Rc<RefCell<(String, Option<std::time::Duration>)>> = Rc::default();
+
+    let notes_handle_save = notes_window.as_weak();
+    let notes_session_save = Rc::clone(&notes_session);
+    notes_window.on_save(move |text| {
+        let Some(window) = notes_handle_save.upgrade() else {
+            return;
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let (app_id, session) = notes_session_save.borrow().clone();
+        if let Err(e) = history::add_note(&app_id, text, session) {
+            println!("Error: Could not save the note: {}", e);
+            return;
+        }
+        let _ = window.hide();
+    });
+    let notes_handle_skip = notes_window.as_weak();
+    notes_window.on_skip(move || {
+        if let Some(window) = notes_handle_skip.upgrade() {
+            let _ = window.hide();
+        }
+    });
+
+    let notes_handle_open = notes_window.as_weak();
+    let app_names_notes = app_names.clone();
+    let open_notes = move |app_id: &str, session: Option<std::time::Duration>| {
+        let Some(window) = notes_handle_open.upgrade() else {
+            return;
+        };
+        *notes_session.borrow_mut() = (app_id.to_string(), session);
+        window.set_game_name(
+            app_names_notes
+                .get(app_id)
+                .cloned()
+                .unwrap_or_else(|| format!("App {}", app_id))
+                .into(),
+        );
+        window.set_session_summary(
+            session
+                .map(|length| format!("Session ended after {}", history::format_length(length)))
+                .unwrap_or_default()
+                .into(),
+        );
+        window.set_note(SharedString::new());
+        let rows: Vec<NoteRow> = history::notes(app_id)
+            .into_iter()
+            .map(|(timestamp, note, length)| NoteRow {
+                when: match length {
+                    Some(length) => format!(
+                        "{} · {}",
+                        journal::format_age(timestamp),
+                        history::format_length(length)
+                    ),
+                    None => journal::format_age(timestamp),
                 }
-            }
+                .into(),
+                note: note.into(),
+            })
+            .collect();
+        window.set_notes(ModelRc::from(Rc::new(VecModel::from(rows))));
+        let _ = window.show();
+    };
+    let open_notes_exited = open_notes.clone();
+
+    let ui_handle_notes = ui.as_weak();
+    ui.on_show_notes(move || {
+        if let Some(ui) = ui_handle_notes.upgrade() {
+            open_notes(&ui.get_app_id(), None);
         }
     });
 
-    // Launch logic
-    let config_launch = Arc::clone(&config);
-    ui.on_run_protonhax(move |app_id| {
-        let app_id_str = app_id.to_string();
+    let ui_handle_sessions = ui.as_weak();
+    let mini_handle_sessions = mini_window.as_weak();
+    let tracker_sessions = tracker.clone();
+    let config_sessions = Arc::clone(&config);
+    let session_timer = Timer::default();
+    let mut session_watcher = history::SessionWatcher::default();
+    let mut usage_sampler = usage::Sampler::default();
+    let mut skipped_refreshes = 0;
+    let mut refresh_sessions = move || {
+        // Background work is only paused in low-resource mode
+        if workers::paused() && skipped_refreshes + 1 < LOW_RESOURCE_SLOWDOWN {
+            skipped_refreshes += 1;
+            return;
+        }
+        skipped_refreshes = 0;
+        if let Some(ui) = ui_handle_sessions.upgrade() {
+            let cfg = config_sessions.lock().unwrap();
+            let active = backend::running_games(&native_games(&cfg));
+            let low_resource = cfg.low_resource_mode && !active.is_empty();
+            if low_resource != workers::paused() {
+                println!(
+                    "{} background work",
+                    if low_resource {
+                        "Game running, pausing"
+                    } else {
+                        "Resuming"
+                    }
+                );
+                workers::set_paused(low_resource);
+                if !low_resource {
+                    ArtLoader::start(&art_loader, &ui);
+                }
+            }
+            let exited = session_watcher.update(&active);
+            for (app_id, _) in &exited {
+                if cfg.stop_with_game && !tracker_sessions.running_names(app_id).is_empty() {
+                    println!("Game {} exited, stopping its programs", app_id);
+                    stop_in_background(&tracker_sessions, &cfg, app_id, None);
+                }
+            }
+            let ask_notes = cfg.session_notes && !safe_mode;
+            drop(cfg);
+            if ask_notes && let Some((app_id, length)) = exited.last() {
+                open_notes_exited(app_id, Some(*length));
+            }
+            let app_id = ui.get_app_id();
+            let game_running = active.iter().any(|id| *id == app_id.as_str());
+            ui.set_game_running(game_running);
+            let sessions: Vec<Session> = active
+                .into_iter()
+                .map(|id| Session {
+                    name: app_names.get(&id).cloned().unwrap_or_default().into(),
+                    app_id: id.into(),
+                })
+                .collect();
+            ui.set_sessions(ModelRc::from(Rc::new(VecModel::from(sessions))));
+            let running = tracker_sessions.running();
+            let pids: Vec<u32> = running.iter().map(|program| program.pid).collect();
+            let usage = usage_sampler.sample(&pids);
+            let injected: Vec<InjectedProgram> = running
+                .into_iter()
+                .map(|program| InjectedProgram {
+                    pid: program.pid as i32,
+                    usage: usage
+                        .get(&program.pid)
+                        .map(|usage| {
+                            format!(
+                                "{:.0}% CPU · {}",
+                                usage.cpu_percent,
+                                disk::format_size(usage.memory)
+                            )
+                        })
+                        .unwrap_or_default()
+                        .into(),
+                    // Most of a core or more
+                    busy: usage
+                        .get(&program.pid)
+                        .is_some_and(|usage| usage.cpu_percent >= BUSY_CPU_PERCENT),
+                    name: program.name.into(),
+                    game: app_names
+                        .get(&program.app_id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("App {}", program.app_id))
+                        .into(),
+                })
+                .collect();
+            ui.set_injected_programs(ModelRc::from(Rc::new(VecModel::from(injected))));
 
-        // Get config values
-        let (exe1, exe2, auto_configure) = {
-            let cfg = config_launch.lock().unwrap();
-            let (e1, e2) = if let Some(game_cfg) = cfg.game_configs.get(&app_id_str) {
-                (game_cfg.exe1_path.clone(), game_cfg.exe2_path.clone())
-            } else {
-                (String::new(), String::new())
+            if let Some(mini) = mini_handle_sessions.upgrade() {
+                let programs = tracker_sessions.running_names(&app_id);
+                let mut status = if game_running {
+                    "Game running".to_string()
+                } else {
+                    "Not running".to_string()
+                };
+                if !programs.is_empty() {
+                    status = format!("{} · {}", status, programs.join(", "));
+                }
+                mini.set_recording(
+                    programs
+                        .iter()
+                        .any(|name| name == recorder::Recorder::GpuScreen.label()),
+                );
+                mini.set_game_running(game_running);
+                mini.set_status(status.into());
+            }
+        }
+    };
+    refresh_sessions();
+    let session_refresh =
+        std::time::Duration::from_millis(config.lock().unwrap().tunables.session_refresh_ms);
+    session_timer.start(TimerMode::Repeated, session_refresh, refresh_sessions);
+
+    // Game updates: once Steam has finished updating a game, copy its files again and run
+    // its update hooks
+    let config_updates = Arc::clone(&config);
+    let installed_updates = installed.clone();
+    let update_timer = Timer::default();
+    let check_updates = move || {
+        // Steam doesn't update a game while it runs; others are caught up on afterwards
+        if workers::paused() {
+            return;
+        }
+        let cfg = config_updates.lock().unwrap();
+        let watched: Vec<(String, PathBuf)> = cfg
+            .game_configs
+            .iter()
+            .filter(|(_, game_cfg)| !game_cfg.files.is_empty() || !game_cfg.update_hooks.is_empty())
+            .filter_map(|(app_id, _)| {
+                installed_updates
+                    .get(app_id)
+                    .map(|game| (app_id.clone(), game.library.clone()))
+            })
+            .collect();
+        for app_id in updates::updated(&watched) {
+            let Some(game_cfg) = cfg.game_configs.get(&app_id) else {
+                continue;
             };
-            (e1, e2, cfg.auto_configure)
+            let (files, hooks) = (game_cfg.files.clone(), game_cfg.update_hooks.clone());
+            let notify = notifications_allowed(&cfg, &app_id);
+            let game = installed_updates.get(&app_id).cloned();
+            let game_name = app_names_updates
+                .get(&app_id)
+                .cloned()
+                .unwrap_or_else(|| format!("App {}", app_id));
+            workers::spawn(move || {
+                let mut done = Vec::new();
+                if !files.is_empty() {
+                    let prefix = game
+                        .as_ref()
+                        .map(|game| prefix_path(&game.library, &app_id));
+                    match deploy::deploy(
+                        &app_id,
+                        &files,
+                        prefix.as_deref(),
+                        game.as_ref().map(|game| game.install_dir.as_path()),
+                    ) {
+                        Ok(0) => {}
+                        Ok(count) => done.push(format!("copied {} files again", count)),
+                        Err(e) => {
+                            println!("Warning: Could not copy the game's files: {}", e);
+                            done.push(format!("could not copy its files ({})", e));
+                        }
+                    }
+                }
+                if !hooks.is_empty() {
+                    let succeeded = process::run_update_hooks(&hooks);
+                    done.push(format!(
+                        "ran {} of {} update commands",
+                        succeeded,
+                        hooks.len()
+                    ));
+                }
+                if done.is_empty() {
+                    println!(
+                        "{} was updated, its copied files are still in place",
+                        game_name
+                    );
+                    return;
+                }
+                let message = format!("Updated by Steam: {}", done.join(", "));
+                println!("{}: {}", game_name, message);
+                if notify {
+                    notify::send(&game_name, &message, None);
+                }
+            });
+        }
+    };
+    check_updates();
+    update_timer.start(
+        TimerMode::Repeated,
+        std::time::Duration::from_secs(UPDATE_CHECK_SECS),
+        check_updates,
+    );
+
+    // Stop buttons in the list of started programs
+    let config_stop_program = Arc::clone(&config);
+    let tracker_stop_program = tracker.clone();
+    ui.on_stop_program(move |pid| {
+        let app_id = tracker_stop_program
+            .running()
+            .into_iter()
+            .find(|program| program.pid as i32 == pid)
+            .map(|program| program.app_id);
+        if let Some(app_id) = app_id {
+            let cfg = config_stop_program.lock().unwrap();
+            stop_in_background(&tracker_stop_program, &cfg, &app_id, Some(pid as u32));
+        }
+    });
+
+    let config_stop_all = Arc::clone(&config);
+    let tracker_stop_all = tracker.clone();
+    ui.on_stop_all_programs(move || {
+        let cfg = config_stop_all.lock().unwrap();
+        let mut app_ids: Vec<String> = tracker_stop_all
+            .running()
+            .into_iter()
+            .map(|program| program.app_id)
+            .collect();
+        app_ids.sort();
+        app_ids.dedup();
+        for app_id in app_ids {
+            stop_in_background(&tracker_stop_all, &cfg, &app_id, None);
+        }
+    });
+
+    // Log panel, refreshed while it's open and something new was logged
+    let ui_handle_log = ui.as_weak();
+    let log_timer = Timer::default();
+    let mut shown_generation = None;
+    let refresh_log = move || {
+        if let Some(ui) = ui_handle_log.upgrade()
+            && ui.get_log_expanded()
+            && shown_generation != Some(logs::generation())
+        {
+            shown_generation = Some(logs::generation());
+            ui.set_log_text(logs::recent().into());
+        }
+    };
+    log_timer.start(
+        TimerMode::Repeated,
+        std::time::Duration::from_millis(500),
+        refresh_log,
+    );
+    let ui_handle_log_toggled = ui.as_weak();
+    ui.on_log_toggled(move |expanded| {
+        if let Some(ui) = ui_handle_log_toggled.upgrade()
+            && expanded
+        {
+            ui.set_log_text(logs::recent().into());
+        }
+    });
+    ui.on_open_logs_folder(|| {
+        if let Some(dir) = logs::logs_dir() {
+            let _ = logs::quiet(Command::new("xdg-open").arg(dir))
+                .spawn()
+                .map(process::reap_in_background);
+        }
+    });
+
+    let ui_handle_clear_recipe = ui.as_weak();
+    ui.on_clear_recipe_progress(move || {
+        if let Some(ui) = ui_handle_clear_recipe.upgrade() {
+            ui.set_recipe_progress(ModelRc::default());
+        }
+    });
+
+    // Run an extra exe in an active session
+    let tracker_session = tracker.clone();
+    let installed_session = installed.clone();
+    let overrides_session = Arc::clone(&injection_overrides);
+    let config_session = Arc::clone(&config);
+    ui.on_session_run_exe(move |app_id| {
+        let anti_cheat = installed_session
+            .get(app_id.as_str())
+            .and_then(|game| anticheat::detect(&game.install_dir));
+        if let Some(anti_cheat) = anti_cheat {
+            let game_name = app_names_session
+                .get(app_id.as_str())
+                .cloned()
+                .unwrap_or_else(|| format!("App {}", app_id));
+            let backend = config_session
+                .lock()
+                .unwrap()
+                .game_configs
+                .get(app_id.as_str())
+                .map(|game_cfg| game_cfg.backend)
+                .unwrap_or_default();
+            if !allow_injection(&app_id, &game_name, backend, anti_cheat, &overrides_session) {
+                return;
+            }
+        }
+        if let Some(path) = FileDialog::new()
+            .add_filter("Executables", &["exe"])
+            .add_filter("All Files", &["*"])
+            .pick_file()
+        {
+            let exe = portal::host_path(path).to_string_lossy().to_string();
+            let injector =
+                match injector(&config_session.lock().unwrap(), &installed_session, &app_id) {
+                    Ok(injector) => injector,
+                    Err(e) => {
+                        println!("Error: Could not launch {}: {}", exe, e);
+                        return;
+                    }
+                };
+            println!("Launching {} in session {}", exe, app_id);
+            if let Err(e) = tracker_session.spawn(
+                &mut injector.run_command(&app_id, &exe),
+                &exe_display_name(&exe),
+                &app_id,
+            ) {
+                println!("Error: Could not launch {}: {}", exe, e);
+            }
+        }
+    });
+
+    // Wine command prompt in an active session, either from the running list
+    // or the selected game's "Open cmd in prefix" button
+    let config_cmd = Arc::clone(&config);
+    let installed_cmd = installed.clone();
+    ui.on_session_open_cmd(move |app_id| {
+        let (preference, injector) = {
+            let cfg = config_cmd.lock().unwrap();
+            (
+                cfg.terminal.clone(),
+                injector(&cfg, &installed_cmd, &app_id),
+            )
+        };
+        let result = injector
+            .and_then(|injector| terminal::spawn(&preference, &injector.cmd_command(&app_id)));
+        if let Err(e) = result {
+            println!("Error: Could not open cmd for {}: {}", app_id, e);
+        }
+    });
+
+    // Common tools, found in the selected game's prefix or the usual folders
+    let presets_window = PresetsWindow::new()?;
+
+    let presets_handle_scan = presets_window.as_weak();
+    let ui_handle_presets_scan = ui.as_weak();
+    let installed_presets = installed.clone();
+    let scan_presets = move || {
+        let (Some(window), Some(ui)) = (
+            presets_handle_scan.upgrade(),
+            ui_handle_presets_scan.upgrade(),
+        ) else {
+            return;
         };
+        let app_id = ui.get_app_id().to_string();
+        let prefix = installed_presets
+            .get(&app_id)
+            .map(|game| prefix_path(&game.library, &app_id));
+        window.set_busy(true);
+        window.set_status("Looking for tools...".into());
+        let window_weak = window.as_weak();
+        workers::spawn_requested(move || {
+            let found = presets::detect(prefix.as_deref());
+            let _ = window_weak.upgrade_in_event_loop(move |window| {
+                let count = found.iter().flatten().count();
+                let rows: Vec<PresetRow> = presets::PRESETS
+                    .iter()
+                    .zip(found)
+                    .map(|(preset, path)| PresetRow {
+                        name: preset.name.into(),
+                        description: preset.description.into(),
+                        path: path
+                            .map(|path| path.to_string_lossy().into_owned())
+                            .unwrap_or_default()
+                            .into(),
+                    })
+                    .collect();
+                window.set_presets(ModelRc::from(Rc::new(VecModel::from(rows))));
+                window.set_busy(false);
+                window.set_status(
+                    match count {
+                        0 => "None of them were found, Browse... picks one by hand".to_string(),
+                        count => format!("Found {} of {}", count, presets::PRESETS.len()),
+                    }
+                    .into(),
+                );
+            });
+        });
+    };
+    presets_window.on_scan(scan_presets.clone());
 
-        if exe1.is_empty() {
-            println!("No executable selected!");
+    // Adding fills in a new program entry, like Add program... does
+    let presets_handle_add = presets_window.as_weak();
+    let ui_handle_presets_add = ui.as_weak();
+    let config_presets_add = Arc::clone(&config);
+    let add_preset = move |name: SharedString, path: String| {
+        let (Some(window), Some(ui)) = (
+            presets_handle_add.upgrade(),
+            ui_handle_presets_add.upgrade(),
+        ) else {
             return;
+        };
+        println!("Adding {} ({})", name, path);
+        update_programs(&ui, &config_presets_add, |programs| {
+            programs.push(ExeEntry::new(path));
+        });
+        window.set_status(format!("Added {} to the programs", name).into());
+    };
+
+    let presets_handle_row = presets_window.as_weak();
+    let add_preset_found = add_preset.clone();
+    presets_window.on_add(move |index| {
+        if let Some(window) = presets_handle_row.upgrade()
+            && let Some(row) = window.get_presets().row_data(index as usize)
+            && !row.path.is_empty()
+        {
+            add_preset_found(row.name, row.path.to_string());
         }
+    });
 
-        // Auto-configure launch options if enabled
-        if auto_configure {
-            match configure_launch_options(&app_id_str) {
-                Ok(msg) => println!("{}", msg),
-                Err(e) => println!("Warning: Could not configure launch options: {}", e),
+    let presets_handle_browse = presets_window.as_weak();
+    presets_window.on_browse(move |index| {
+        if let Some(window) = presets_handle_browse.upgrade()
+            && let Some(row) = window.get_presets().row_data(index as usize)
+            && let Some(path) = pick_program()
+        {
+            add_preset(row.name, path);
+        }
+    });
+
+    let presets_handle_open = presets_window.as_weak();
+    let ui_handle_presets_open = ui.as_weak();
+    ui.on_show_presets(move || {
+        let (Some(window), Some(ui)) = (
+            presets_handle_open.upgrade(),
+            ui_handle_presets_open.upgrade(),
+        ) else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        window.set_game_name(
+            app_names_presets
+                .get(&app_id)
+                .cloned()
+                .unwrap_or_else(|| format!("App {}", app_id))
+                .into(),
+        );
+        window.set_presets(ModelRc::default());
+        scan_presets();
+        let _ = window.show();
+    });
+
+    // Launch options editor, for the wrappers and variables beyond what auto-configure adds
+    let launch_options_window = LaunchOptionsWindow::new()?;
+
+    let launch_options_handle_edited = launch_options_window.as_weak();
+    launch_options_window.on_options_edited(move |options| {
+        if let Some(window) = launch_options_handle_edited.upgrade() {
+            show_launch_options(&window, &options);
+            window.set_unsaved(true);
+        }
+    });
+
+    let launch_options_handle_wrappers = launch_options_window.as_weak();
+    launch_options_window.on_wrappers_changed(move || {
+        if let Some(window) = launch_options_handle_wrappers.upgrade() {
+            apply_launch_option_toggles(&window);
+        }
+    });
+
+    let launch_options_handle_env = launch_options_window.as_weak();
+    launch_options_window.on_env_toggled(move |index, enabled| {
+        if let Some(window) = launch_options_handle_env.upgrade()
+            && let Some(mut row) = window.get_env().row_data(index as usize)
+        {
+            row.enabled = enabled;
+            window.get_env().set_row_data(index as usize, row);
+            apply_launch_option_toggles(&window);
+        }
+    });
+
+    let launch_options_handle_value = launch_options_window.as_weak();
+    launch_options_window.on_env_value_edited(move |index, value| {
+        if let Some(window) = launch_options_handle_value.upgrade()
+            && let Some(mut row) = window.get_env().row_data(index as usize)
+        {
+            row.value = value;
+            window.get_env().set_row_data(index as usize, row);
+            apply_launch_option_toggles(&window);
+        }
+    });
+
+    let launch_options_handle_reload = launch_options_window.as_weak();
+    launch_options_window.on_reload(move || {
+        if let Some(window) = launch_options_handle_reload.upgrade() {
+            load_launch_options(&window);
+        }
+    });
+
+    let launch_options_handle_save = launch_options_window.as_weak();
+    let ui_handle_launch_options_save = ui.as_weak();
+    let config_launch_options_save = Arc::clone(&config);
+    launch_options_window.on_save(move || {
+        let Some(window) = launch_options_handle_save.upgrade() else {
+            return;
+        };
+        let app_id = window.get_app_id().to_string();
+        let options = window.get_options().trim().to_string();
+        if config_launch_options_save.lock().unwrap().locked {
+            return;
+        }
+        let window_handle = launch_options_handle_save.clone();
+        let ui_handle = ui_handle_launch_options_save.clone();
+        let config = Arc::clone(&config_launch_options_save);
+        let (edited_app_id, edited_options) = (app_id.clone(), options.clone());
+        with_steam_closed("the new launch options", move || {
+            set_launch_options(&edited_app_id, &edited_options, "editor")
+        }, move |saved| {
+        let (Some(saved), Some(window), Some(ui)) =
+            (saved, window_handle.upgrade(), ui_handle.upgrade())
+        else {
+            return;
+        };
+        let cfg = config.lock().unwrap();
+        let status = match saved {
+            Ok(()) => {
+                println!("Saved launch options for {}: '{}'", app_id, options);
+                window.set_unsaved(false);
+                let template = launch_template(&cfg, &app_id);
+                if cfg.auto_configure && launch_options_pending(&app_id, &template) {
+                    format!(
+                        "Saved. Auto-configure adds '{}' back on the next launch from Protonic unless you turn it off",
+                        template
+                    )
+                } else {
+                    "Saved".to_string()
+                }
+            }
+            Err(e) => {
+                println!("Error: Could not save launch options for {}: {}", app_id, e);
+                format!("Could not save: {}", e)
             }
+        };
+        window.set_status(status.into());
+        if cfg.auto_configure && ui.get_app_id() == app_id.as_str() {
+            ui.set_launch_options_status(launch_options_status(&cfg, &app_id).into());
         }
+        });
+    });
 
-        // Play launch game audio
-        play_audio(AUDIO_LAUNCH_GAME);
+    let launch_options_handle_open = launch_options_window.as_weak();
+    let ui_handle_launch_options_open = ui.as_weak();
+    let config_launch_options_open = Arc::clone(&config);
+    ui.on_show_launch_options_editor(move || {
+        let (Some(window), Some(ui)) = (
+            launch_options_handle_open.upgrade(),
+            ui_handle_launch_options_open.upgrade(),
+        ) else {
+            return;
+        };
+        let app_id = ui.get_app_id().to_string();
+        if app_id.is_empty() {
+            return;
+        }
+        window.set_game_name(
+            app_names_launch_options
+                .get(&app_id)
+                .cloned()
+                .unwrap_or_else(|| format!("App {}", app_id))
+                .into(),
+        );
+        window.set_app_id(app_id.into());
+        window.set_locked(config_launch_options_open.lock().unwrap().locked);
+        load_launch_options(&window);
+        let _ = window.show();
+    });
 
-        println!("Launching Steam Game {}...", app_id_str);
-        let _ = Command::new("steam")
-            .arg(format!("steam://run/{}", app_id_str))
-            .spawn();
+    // Windows processes inside a running game's prefix, to check on injected programs
+    let processes_window = ProcessesWindow::new()?;
+    // The game the window shows and how to reach its prefix
+    let processes_game: Rc<RefCell<Option<(String, backend::Injector)>>> = Rc::default();
 
+    let processes_handle_refresh = processes_window.as_weak();
+    let processes_game_refresh = Rc::clone(&processes_game);
+    let refresh_processes = move || {
+        let Some(window) = processes_handle_refresh.upgrade() else {
+            return;
+        };
+        let Some((app_id, injector)) = processes_game_refresh.borrow().clone() else {
+            return;
+        };
+        window.set_busy(true);
+        window.set_status("Listing processes...".into());
+        let window_weak = window.as_weak();
         thread::spawn(move || {
-            let device_state = DeviceState::new();
-            println!("Waiting for F1...");
-            loop {
-                let keys = device_state.get_keys();
-                if keys.contains(&Keycode::F1) {
-                    // Play program launch audio
-                    play_audio(AUDIO_LAUNCH_PROGRAM);
-
-                    // Launch exe 1
-                    println!("Launching: {}", exe1);
-                    let _ = Command::new("protonhax")
-                        .arg("run")
-                        .arg(&app_id_str)
-                        .arg(&exe1)
-                        .spawn();
-
-                    // Launch exe 2 (if user set one)
-                    if !exe2.is_empty() {
-                        println!("Launching: {}", exe2);
-                        // Small delay between launches
-                        thread::sleep(std::time::Duration::from_millis(500));
-                        let _ = Command::new("protonhax")
-                            .arg("run")
-                            .arg(&app_id_str)
-                            .arg(&exe2)
-                            .spawn();
+            let listed = tasklist::list(&injector, &app_id);
+            let _ = window_weak.upgrade_in_event_loop(move |window| {
+                window.set_busy(false);
+                match listed {
+                    Ok(processes) => {
+                        window.set_status(SharedString::new());
+                        let rows: Vec<ProcessRow> = processes
+                            .into_iter()
+                            .map(|process| ProcessRow {
+                                name: process.name.into(),
+                                pid: process.pid.to_string().into(),
+                                memory: process.memory.into(),
+                            })
+                            .collect();
+                        window.set_processes(ModelRc::new(VecModel::from(rows)));
+                    }
+                    Err(e) => {
+                        println!("Warning: Could not list processes of {}: {}", app_id, e);
+                        window.set_status(format!("Could not list processes: {}", e).into());
                     }
-                    break;
                 }
-                thread::sleep(std::time::Duration::from_millis(100));
-            }
+            });
         });
+    };
+    processes_window.on_refresh(refresh_processes.clone());
+
+    let processes_handle_kill = processes_window.as_weak();
+    let processes_game_kill = Rc::clone(&processes_game);
+    processes_window.on_kill(move |pid| {
+        let Some(window) = processes_handle_kill.upgrade() else {
+            return;
+        };
+        let Some((app_id, injector)) = processes_game_kill.borrow().clone() else {
+            return;
+        };
+        let Ok(pid) = pid.parse::<u32>() else {
+            return;
+        };
+        window.set_busy(true);
+        let window_weak = window.as_weak();
+        thread::spawn(move || {
+            let killed = tasklist::kill(&injector, &app_id, pid);
+            let _ = window_weak.upgrade_in_event_loop(move |window| match killed {
+                Ok(()) => {
+                    println!("Killed process {} in {}", pid, app_id);
+                    window.invoke_refresh();
+                }
+                Err(e) => {
+                    println!("Error: Could not kill process {} in {}: {}", pid, app_id, e);
+                    window.set_busy(false);
+                    window.set_status(format!("Could not kill process {}: {}", pid, e).into());
+                }
+            });
+        });
+    });
+
+    let processes_handle_open = processes_window.as_weak();
+    let config_processes = Arc::clone(&config);
+    let installed_processes = installed.clone();
+    ui.on_session_show_processes(move |app_id| {
+        let Some(window) = processes_handle_open.upgrade() else {
+            return;
+        };
+        let injector = match injector(
+            &config_processes.lock().unwrap(),
+            &installed_processes,
+            &app_id,
+        ) {
+            Ok(injector) => injector,
+            Err(e) => {
+                println!("Error: Could not list processes of {}: {}", app_id, e);
+                return;
+            }
+        };
+        window.set_game_name(
+            app_names_processes
+                .get(app_id.as_str())
+                .cloned()
+                .unwrap_or_else(|| format!("App {}", app_id))
+                .into(),
+        );
+        window.set_processes(ModelRc::default());
+        *processes_game.borrow_mut() = Some((app_id.to_string(), injector));
+        refresh_processes();
+        let _ = window.show();
+    });
+
+    // Theme changes apply to the open windows right away; the overlay picks them up the
+    // next time it's shown
+    let ui_handle_theme = ui.as_weak();
+    let tools_handle_theme = tools_window.as_weak();
+    let inspector_handle_theme = inspector_window.as_weak();
+    let journal_handle_theme = journal_window.as_weak();
+    let bulk_handle_theme = bulk_window.as_weak();
+    let restyle = move || {
+        if let Some(ui) = ui_handle_theme.upgrade() {
+            theme::apply(&ui);
+        }
+        if let Some(window) = tools_handle_theme.upgrade() {
+            theme::apply(&window);
+        }
+        if let Some(window) = inspector_handle_theme.upgrade() {
+            theme::apply(&window);
+        }
+        if let Some(window) = journal_handle_theme.upgrade() {
+            theme::apply(&window);
+        }
+        if let Some(window) = bulk_handle_theme.upgrade() {
+            theme::apply(&window);
+        }
+    };
+
+    let ui_handle_accent = ui.as_weak();
+    let config_accent = Arc::clone(&config);
+    let restyle_accent = restyle.clone();
+    ui.on_accent_edited(move |text| {
+        let Some(ui) = ui_handle_accent.upgrade() else {
+            return;
+        };
+        // Half-typed colors are only reported, the last good one stays
+        if let Err(e) = theme::parse_color(&text) {
+            ui.set_accent_error(e.into());
+            return;
+        }
+        ui.set_accent_error(SharedString::new());
+        let mut cfg = config_accent.lock().unwrap();
+        cfg.accent_color = format!("#{}", text.trim().trim_start_matches('#').to_lowercase());
+        theme::set(&cfg.accent_color, cfg.corner_radius);
+        save_config(&cfg);
+        restyle_accent();
+    });
+
+    let config_radius = Arc::clone(&config);
+    ui.on_corner_radius_selected(move |choice| {
+        let Ok(radius) = choice.trim_end_matches(" px").parse() else {
+            return;
+        };
+        let mut cfg = config_radius.lock().unwrap();
+        cfg.corner_radius = radius;
+        theme::set(&cfg.accent_color, cfg.corner_radius);
+        save_config(&cfg);
+        restyle();
     });
 
+    // Launch once the window is up, like pressing Launch
+    if let Some(startup) = startup.filter(|startup| startup.launch) {
+        let ui_handle_startup = ui.as_weak();
+        Timer::single_shot(std::time::Duration::ZERO, move || {
+            if let Some(ui) = ui_handle_startup.upgrade() {
+                ui.invoke_run_protonhax(startup.app_id.into());
+            }
+        });
+    }
+
     ui.run()?;
+
+    let (kill_on_exit, kill_grace_ms) = {
+        let cfg = config.lock().unwrap();
+        (cfg.kill_on_exit, cfg.tunables.kill_grace_ms)
+    };
+    if kill_on_exit {
+        let stopped = tracker.kill_all(std::time::Duration::from_millis(kill_grace_ms));
+        let cfg = config.lock().unwrap();
+        for app_id in stopped {
+            if let Some(game_cfg) = cfg.game_configs.get(&app_id) {
+                process::run_cleanup_hooks(&game_cfg.cleanup_hooks);
+            }
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,133 @@
+//! Importers for setups made with other tools: SteamTinkerLaunch's per-game configs,
+//! and the winetricks verbs protontricks has installed into prefixes.
+
+use crate::winetricks;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Proton switches SteamTinkerLaunch stores under their own names as "0"/"1"
+const PROTON_SWITCHES: [&str; 9] = [
+    "PROTON_LOG",
+    "PROTON_NO_D3D10",
+    "PROTON_NO_D3D11",
+    "PROTON_NO_ESYNC",
+    "PROTON_NO_FSYNC",
+    "PROTON_ENABLE_NVAPI",
+    "PROTON_FORCE_LARGE_ADDRESS_AWARE",
+    "PROTON_HIDE_NVIDIA_GPU",
+    "PROTON_USE_WINED3D",
+];
+
+/// What SteamTinkerLaunch had set up for one game
+#[derive(Debug, Default)]
+pub struct ImportedGame {
+    pub app_id: String,
+    /// Custom commands it started alongside the game
+    pub programs: Vec<String>,
+    /// Wrapper template rebuilt from its tool toggles and Proton switches,
+    /// None when it only used the defaults
+    pub template: Option<String>,
+}
+
+fn stl_config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("steamtinkerlaunch").join("gamecfgs").join("id"))
+}
+
+/// Read a SteamTinkerLaunch config: shell assignments like KEY="value"
+fn parse_stl_config(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.trim().to_string(),
+                value.trim().trim_matches('"').to_string(),
+            )
+        })
+        .collect()
+}
+
+fn stl_game(app_id: &str, values: &HashMap<String, String>) -> ImportedGame {
+    let enabled = |key: &str| values.get(key).is_some_and(|value| value == "1");
+
+    let mut programs = Vec::new();
+    if enabled("RUN_CUSTOMCMD")
+        && let Some(command) = values.get("CUSTOMCMD")
+        && !command.is_empty()
+        && command != "none"
+    {
+        programs.push(command.clone());
+    }
+
+    // Wrappers go outside protonhax, which has to stay next to the game
+    let mut parts: Vec<String> = PROTON_SWITCHES
+        .iter()
+        .filter(|key| enabled(key))
+        .map(|key| format!("{}=1", key))
+        .collect();
+    if let Some(hud) = values.get("DXVK_HUD")
+        && !hud.is_empty()
+        && hud != "0"
+        && hud != "none"
+    {
+        parts.push(format!("DXVK_HUD={}", hud));
+    }
+    if enabled("USEGAMESCOPE") {
+        let args = values
+            .get("GAMESCOPE_ARGS")
+            .map(|args| args.trim().trim_end_matches("--").trim())
+            .filter(|args| !args.is_empty() && *args != "none");
+        parts.push(match args {
+            Some(args) => format!("gamescope {} --", args),
+            None => "gamescope --".to_string(),
+        });
+    }
+    if enabled("USEGAMEMODERUN") {
+        parts.push("gamemoderun".to_string());
+    }
+    if enabled("USEMANGOHUD") {
+        parts.push("mangohud".to_string());
+    }
+    let template = (!parts.is_empty())
+        .then(|| format!("{} {}", parts.join(" "), crate::wrapper::DEFAULT_TEMPLATE));
+
+    ImportedGame {
+        app_id: app_id.to_string(),
+        programs,
+        template,
+    }
+}
+
+/// Every game SteamTinkerLaunch has a config for that set up something Protonic can use
+pub fn steamtinkerlaunch_games() -> Vec<ImportedGame> {
+    let Some(entries) = stl_config_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut games: Vec<ImportedGame> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "conf"))
+        .filter_map(|path| {
+            let app_id = path.file_stem()?.to_string_lossy().into_owned();
+            if !app_id.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let content = fs::read_to_string(&path).ok()?;
+            Some(stl_game(&app_id, &parse_stl_config(&content)))
+        })
+        .filter(|game| !game.programs.is_empty() || game.template.is_some())
+        .collect();
+    games.sort_by(|a, b| a.app_id.cmp(&b.app_id));
+    games
+}
+
+/// Verbs protontricks (or winetricks) already installed into a prefix, as a hint for
+/// what the game's programs need
+pub fn protontricks_verbs(prefix: &Path) -> Vec<String> {
+    let mut verbs: Vec<String> = winetricks::installed_verbs(prefix).into_iter().collect();
+    verbs.sort();
+    verbs
+}
@@ -0,0 +1,95 @@
+//! Sorting and searching game names the way people read them: by the user's locale's
+//! collation rules (so "Ōkami" sorts with the O's and CJK titles don't end up scattered
+//! by byte value), and matching searches regardless of accents, case or full-width forms.
+
+use icu_collator::{Collator, CollatorOptions, Numeric};
+use icu_locid::Locale;
+use icu_normalizer::DecomposingNormalizer;
+use std::cmp::Ordering;
+use std::env;
+
+// Left out of the sort order when ignoring leading articles ("The Witcher" sorts under W)
+const ARTICLES: [&str; 3] = ["the", "a", "an"];
+
+/// The locale names are collated for, from LC_ALL / LC_COLLATE / LANG like other programs
+fn collation_locale() -> Locale {
+    ["LC_ALL", "LC_COLLATE", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| {
+            // "de_DE.UTF-8@euro" -> "de-DE"
+            let tag = value.split(['.', '@']).next().unwrap_or_default();
+            if tag == "C" || tag == "POSIX" {
+                return None;
+            }
+            tag.replace('_', "-").parse().ok()
+        })
+        .unwrap_or(Locale::UND)
+}
+
+/// Compares game names for the game list
+pub struct NameOrder {
+    collator: Collator,
+    ignore_articles: bool,
+}
+
+impl NameOrder {
+    pub fn new(ignore_articles: bool) -> Result<Self, String> {
+        let mut options = CollatorOptions::new();
+        // "Game 2" before "Game 10"
+        options.numeric = Some(Numeric::On);
+        let locale = collation_locale();
+        let collator = Collator::try_new(&locale.clone().into(), options)
+            .or_else(|_| Collator::try_new(&Locale::UND.into(), options))
+            .map_err(|e| format!("No collation rules for {}: {}", locale, e))?;
+        Ok(Self {
+            collator,
+            ignore_articles,
+        })
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        if self.ignore_articles {
+            self.collator
+                .compare(without_article(a), without_article(b))
+                .then_with(|| self.collator.compare(a, b))
+        } else {
+            self.collator.compare(a, b)
+        }
+    }
+}
+
+/// A name without a leading "The", "A" or "An"
+fn without_article(name: &str) -> &str {
+    name.split_once(' ')
+        .filter(|(first, rest)| {
+            !rest.trim().is_empty()
+                && ARTICLES
+                    .iter()
+                    .any(|article| first.eq_ignore_ascii_case(article))
+        })
+        .map(|(_, rest)| rest.trim_start())
+        .unwrap_or(name)
+}
+
+/// Text reduced to what a search compares: compatibility-decomposed (full-width letters
+/// become plain ones), without Latin accents, and lowercase
+fn fold(text: &str) -> String {
+    DecomposingNormalizer::new_nfkd()
+        .normalize(text)
+        .chars()
+        .filter(|c| !('\u{0300}'..='\u{036F}').contains(c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Whether a game name is the search text, give or take accents and case
+pub fn is_same(name: &str, search: &str) -> bool {
+    fold(name) == fold(search.trim())
+}
+
+/// Whether a game name matches the search text, e.g. "pokemon" finds "Pokémon"
+pub fn matches(name: &str, search: &str) -> bool {
+    fold(name).contains(&fold(search.trim()))
+}
@@ -0,0 +1,30 @@
+//! Desktop notifications over D-Bus (org.freedesktop.Notifications), shown with the
+//! game's icon.
+
+use notify_rust::Notification;
+use std::path::Path;
+use std::sync::Once;
+use std::thread;
+
+// Notifications are sent for every launch, so a desktop without a notification server
+// is only reported once
+static FAILURE_REPORTED: Once = Once::new();
+
+/// Show a notification, in the background since the D-Bus call waits for the server
+pub fn send(summary: &str, body: &str, icon: Option<&Path>) {
+    let mut notification = Notification::new();
+    notification.appname("Protonic").summary(summary).body(body);
+    if let Some(icon) = icon {
+        notification.icon(&icon.to_string_lossy());
+    }
+    thread::spawn(move || {
+        if let Err(e) = notification.show() {
+            FAILURE_REPORTED.call_once(|| {
+                println!(
+                    "Warning: Could not show notification ({}), is a notification daemon running?",
+                    e
+                );
+            });
+        }
+    });
+}
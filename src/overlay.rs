@@ -0,0 +1,109 @@
+//! A small status box over the game ("Programs started", "Cheat Engine stopped
+//! unexpectedly"), for when desktop notifications don't make it over a fullscreen game.
+//! It's a frameless always-on-top window that hides itself after a few seconds. Under
+//! gamescope it's also marked as an external overlay, the way MangoApp is, so gamescope
+//! draws it over the game. Slint can't open wlroots layer-shell surfaces, so on other
+//! Wayland compositors it's a regular always-on-top window.
+
+use crate::{OverlayWindow, theme};
+use slint::{ComponentHandle, LogicalPosition, Timer, TimerMode};
+use std::cell::RefCell;
+use std::env;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+// Title of OverlayWindow in overlay.slint, which gamescope's window is found by
+const WINDOW_TITLE: &str = "Protonic overlay";
+const SHOW_FOR: Duration = Duration::from_secs(6);
+// Distance from the top left corner of the screen
+const MARGIN: f32 = 24.0;
+// The window has to be mapped before xdotool can find it
+const MAP_DELAY: Duration = Duration::from_millis(300);
+
+thread_local! {
+    // Created the first time something is shown, on the UI thread
+    static OVERLAY: RefCell<Option<(OverlayWindow, Timer)>> = const { RefCell::new(None) };
+}
+
+/// Whether Protonic runs inside a gamescope session (e.g. the Steam Deck's game mode)
+fn in_gamescope() -> bool {
+    env::var_os("GAMESCOPE_WAYLAND_DISPLAY").is_some()
+        || env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop == "gamescope")
+}
+
+/// Ask gamescope to draw the overlay over the game, through the atom it looks for on
+/// external overlay windows
+fn mark_for_gamescope() -> Result<(), String> {
+    let output = Command::new("xdotool")
+        .args(["search", "--name", &format!("^{}$", WINDOW_TITLE)])
+        .output()
+        .map_err(|e| format!("Failed to run xdotool: {}", e))?;
+    let windows = String::from_utf8_lossy(&output.stdout).into_owned();
+    if windows.trim().is_empty() {
+        return Err("its window wasn't found".to_string());
+    }
+    for window in windows.split_whitespace() {
+        let status = Command::new("xprop")
+            .args(["-id", window, "-f", "GAMESCOPE_EXTERNAL_OVERLAY", "32c"])
+            .args(["-set", "GAMESCOPE_EXTERNAL_OVERLAY", "1"])
+            .status()
+            .map_err(|e| format!("Failed to run xprop: {}", e))?;
+        if !status.success() {
+            return Err(format!("xprop failed ({})", status));
+        }
+    }
+    Ok(())
+}
+
+fn show_now(heading: &str, text: &str, warning: bool) {
+    OVERLAY.with(|overlay| {
+        let mut overlay = overlay.borrow_mut();
+        let created = overlay.is_none();
+        if created {
+            match OverlayWindow::new() {
+                Ok(window) => *overlay = Some((window, Timer::default())),
+                Err(e) => {
+                    println!("Warning: Could not open the overlay: {}", e);
+                    return;
+                }
+            }
+        }
+        let Some((window, timer)) = overlay.as_ref() else {
+            return;
+        };
+        // Picks up theme changes since it was last shown
+        theme::apply(window);
+        window.set_heading(heading.into());
+        window.set_text(text.into());
+        window.set_warning(warning);
+        window
+            .window()
+            .set_position(LogicalPosition::new(MARGIN, MARGIN));
+        if let Err(e) = window.show() {
+            println!("Warning: Could not show the overlay: {}", e);
+            return;
+        }
+        if created && in_gamescope() {
+            thread::spawn(|| {
+                thread::sleep(MAP_DELAY);
+                if let Err(e) = mark_for_gamescope() {
+                    println!("Warning: Overlay not shown over gamescope games: {}", e);
+                }
+            });
+        }
+        let window = window.as_weak();
+        timer.start(TimerMode::SingleShot, SHOW_FOR, move || {
+            if let Some(window) = window.upgrade() {
+                let _ = window.hide();
+            }
+        });
+    });
+}
+
+/// Show a status line over the game for a few seconds. Works from any thread.
+pub fn show(heading: &str, text: &str, warning: bool) {
+    let heading = heading.to_string();
+    let text = text.to_string();
+    let _ = slint::invoke_from_event_loop(move || show_now(&heading, &text, warning));
+}
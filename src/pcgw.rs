@@ -0,0 +1,176 @@
+//! PCGamingWiki lookup for the selected game: its page (where most companion tools and
+//! fixes are documented) opened in the browser, and with online metadata allowed, a
+//! summary of the fixes it lists, cached on disk like the store details.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const API_URL: &str = "https://www.pcgamingwiki.com/w/api.php";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Top-level sections whose subsections are worth listing
+const FIX_SECTIONS: [&str; 3] = [
+    "Essential improvements",
+    "Issues fixed",
+    "Issues unresolved",
+];
+
+/// The game's page, found by PCGamingWiki from the Steam app ID
+pub fn page_url(app_id: &str) -> String {
+    format!(
+        "https://www.pcgamingwiki.com/api/appid.php?appid={}",
+        app_id
+    )
+}
+
+/// What the page lists under each of FIX_SECTIONS
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Summary {
+    pub page: String,
+    pub sections: Vec<(String, Vec<String>)>,
+}
+
+impl Summary {
+    /// One line per section, e.g. "Essential improvements: Skip intro videos, Widescreen"
+    pub fn to_text(&self) -> String {
+        if self.sections.is_empty() {
+            return format!("PCGamingWiki lists no fixes for {}", self.page);
+        }
+        self.sections
+            .iter()
+            .map(|(section, entries)| format!("{}: {}", section, entries.join(", ")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn cache_path(app_id: &str) -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| {
+        d.join("protonic")
+            .join("pcgw")
+            .join(format!("{}.json", app_id))
+    })
+}
+
+/// A summary fetched before, if any
+pub fn cached(app_id: &str) -> Option<Summary> {
+    let text = fs::read_to_string(cache_path(app_id)?).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+#[derive(Deserialize)]
+struct CargoResponse {
+    cargoquery: Vec<CargoRow>,
+}
+
+#[derive(Deserialize)]
+struct CargoRow {
+    title: CargoTitle,
+}
+
+#[derive(Deserialize)]
+struct CargoTitle {
+    #[serde(rename = "Page")]
+    page: String,
+}
+
+#[derive(Deserialize)]
+struct ParseResponse {
+    parse: ParsedPage,
+}
+
+#[derive(Deserialize)]
+struct ParsedPage {
+    sections: Vec<Section>,
+}
+
+#[derive(Deserialize)]
+struct Section {
+    toclevel: u32,
+    line: String,
+    // "2.1" for the first subsection of the second section
+    number: String,
+}
+
+/// Section titles can carry markup, e.g. "<i>Skyrim</i> Script Extender"
+fn strip_tags(text: &str) -> String {
+    let mut plain = String::new();
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+    plain.trim().to_string()
+}
+
+fn api_get(params: &[(&str, &str)]) -> Result<ureq::Response, String> {
+    let mut request = ureq::get(API_URL).timeout(REQUEST_TIMEOUT);
+    for (name, value) in params {
+        request = request.query(name, value);
+    }
+    request
+        .query("format", "json")
+        .call()
+        .map_err(|e| format!("PCGamingWiki request failed: {}", e))
+}
+
+/// Look the game's page up and summarize its fixes, caching the result
+pub fn fetch(app_id: &str) -> Result<Summary, String> {
+    if !app_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("'{}' is not a numeric app ID", app_id));
+    }
+    let condition = format!("Infobox_game.Steam_AppID HOLDS \"{}\"", app_id);
+    let found: CargoResponse = api_get(&[
+        ("action", "cargoquery"),
+        ("tables", "Infobox_game"),
+        ("fields", "Infobox_game._pageName=Page"),
+        ("where", &condition),
+    ])?
+    .into_json()
+    .map_err(|e| format!("Unexpected PCGamingWiki response: {}", e))?;
+    let page = found
+        .cargoquery
+        .into_iter()
+        .next()
+        .map(|row| row.title.page)
+        .ok_or_else(|| format!("PCGamingWiki has no page for app {}", app_id))?;
+
+    let parsed: ParseResponse =
+        api_get(&[("action", "parse"), ("page", &page), ("prop", "sections")])?
+            .into_json()
+            .map_err(|e| format!("Unexpected PCGamingWiki response: {}", e))?;
+    let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+    for top in parsed
+        .parse
+        .sections
+        .iter()
+        .filter(|s| s.toclevel == 1 && FIX_SECTIONS.contains(&strip_tags(&s.line).as_str()))
+    {
+        let prefix = format!("{}.", top.number);
+        let entries: Vec<String> = parsed
+            .parse
+            .sections
+            .iter()
+            .filter(|s| s.toclevel == 2 && s.number.starts_with(&prefix))
+            .map(|s| strip_tags(&s.line))
+            .collect();
+        if !entries.is_empty() {
+            sections.push((strip_tags(&top.line), entries));
+        }
+    }
+
+    let summary = Summary { page, sections };
+    if let Some(path) = cache_path(app_id) {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&summary).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())?;
+    }
+    Ok(summary)
+}
@@ -0,0 +1,102 @@
+//! Flatpak support for file dialogs. rfd asks the xdg-desktop-portal file chooser, which
+//! inside the sandbox hands back files through the document portal
+//! (`$XDG_RUNTIME_DIR/doc/<id>/<name>`). Those paths only exist inside the sandbox, while
+//! protonhax and Wine run on the host, so they're translated back to the real file.
+
+use std::env;
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Extended attribute newer document portals (1.16+) put on exported files
+const HOST_PATH_XATTR: &str = "user.document-portal.host-path";
+
+/// Whether Protonic is running inside a Flatpak sandbox
+fn in_flatpak() -> bool {
+    Path::new("/.flatpak-info").exists()
+}
+
+/// Mount point of the document portal
+fn documents_dir() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join("doc"))
+}
+
+/// The ID of a document portal file: the first directory below the mount point
+fn document_id(path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(documents_dir()?).ok()?;
+    relative
+        .components()
+        .next()
+        .map(|id| id.as_os_str().to_string_lossy().into_owned())
+}
+
+fn host_path_from_xattr(path: &Path) -> Option<PathBuf> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let c_name = CString::new(HOST_PATH_XATTR).ok()?;
+    let mut buffer = vec![0u8; 4096];
+    // SAFETY: both strings are NUL-terminated and the buffer length is passed along
+    let len = unsafe {
+        libc::getxattr(
+            c_path.as_ptr(),
+            c_name.as_ptr(),
+            buffer.as_mut_ptr().cast(),
+            buffer.len(),
+        )
+    };
+    if len <= 0 {
+        return None;
+    }
+    buffer.truncate(len as usize);
+    // The attribute value may include the trailing NUL
+    if buffer.last() == Some(&0) {
+        buffer.pop();
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&buffer).into_owned()))
+}
+
+/// Ask the document portal over D-Bus (GetHostPaths, portal version 5).
+/// gdbus prints the answer as `({'<id>': b'/host/path'},)`.
+fn host_path_from_dbus(id: &str) -> Option<PathBuf> {
+    let output = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.portal.Documents",
+            "--object-path",
+            "/org/freedesktop/portal/documents",
+            "--method",
+            "org.freedesktop.portal.Documents.GetHostPaths",
+        ])
+        .arg(format!("['{}']", id))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let start = text.find("b'")? + 2;
+    let end = start + text[start..].find('\'')?;
+    let path = &text[start..end];
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// The path a picked file has on the host. Paths that didn't come through the
+/// document portal are returned unchanged.
+pub fn host_path(path: PathBuf) -> PathBuf {
+    if !in_flatpak() {
+        return path;
+    }
+    let Some(id) = document_id(&path) else {
+        return path;
+    };
+    if let Some(host) = host_path_from_xattr(&path).or_else(|| host_path_from_dbus(&id)) {
+        return host;
+    }
+    println!(
+        "Warning: Could not find the host path of {}, it may not work outside the sandbox",
+        path.display()
+    );
+    path
+}
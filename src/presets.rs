@@ -0,0 +1,161 @@
+//! Companion tools most people use, with the executable names they're installed under, so
+//! they can be found in a game's prefix or the usual folders on this computer instead of
+//! browsing for them every time.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A well-known tool
+pub struct Preset {
+    pub name: &'static str,
+    // What it's for, shown under its name
+    pub description: &'static str,
+    // File names it's installed under, matched ignoring case; `*` matches anything
+    exe_names: &'static [&'static str],
+}
+
+pub const PRESETS: [Preset; 9] = [
+    Preset {
+        name: "Cheat Engine",
+        description: "Memory scanner and trainer tables",
+        exe_names: &[
+            "cheatengine-x86_64-SSE4-AVX2.exe",
+            "cheatengine-x86_64.exe",
+            "Cheat Engine.exe",
+        ],
+    },
+    Preset {
+        name: "ReShade setup",
+        description: "Installs ReShade's post-processing into the game",
+        exe_names: &["ReShade_Setup_*.exe"],
+    },
+    Preset {
+        name: "r2modman",
+        description: "Thunderstore mod manager",
+        exe_names: &["r2modman.exe"],
+    },
+    Preset {
+        name: "Special K",
+        description: "Frame pacing, HDR and more (SKIF)",
+        exe_names: &["SKIF.exe"],
+    },
+    Preset {
+        name: "Mod Organizer 2",
+        description: "Mod manager for Bethesda games and more",
+        exe_names: &["ModOrganizer.exe"],
+    },
+    Preset {
+        name: "Vortex",
+        description: "Nexus Mods' mod manager",
+        exe_names: &["Vortex.exe"],
+    },
+    Preset {
+        name: "WeMod",
+        description: "Trainers and mods",
+        exe_names: &["WeMod.exe"],
+    },
+    Preset {
+        name: "Thunderstore Mod Manager",
+        description: "Thunderstore's own mod manager",
+        exe_names: &["Thunderstore Mod Manager.exe"],
+    },
+    Preset {
+        name: "FLiNG trainer",
+        description: "Standalone trainers from FLiNG",
+        exe_names: &["*Trainer*FLiNG*.exe", "*FLiNG*Trainer*.exe"],
+    },
+];
+
+// Folders deeper than this below a starting point aren't looked in; installs sit near
+// the top of Program Files or Downloads
+const MAX_DEPTH: usize = 4;
+
+/// Whether `name` matches `pattern`, ignoring case, with `*` matching any text
+fn matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let [first, middle @ .., last] = parts.as_slice() else {
+        return pattern == name;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Where tools are usually installed or downloaded to: inside the game's prefix
+/// (compatdata/<app id>), then on this computer
+fn search_dirs(prefix: Option<&Path>) -> Vec<PathBuf> {
+    let mut places = Vec::new();
+    if let Some(prefix) = prefix {
+        let drive_c = prefix.join("pfx").join("drive_c");
+        let user = drive_c.join("users").join("steamuser");
+        places.extend([
+            drive_c.join("Program Files"),
+            drive_c.join("Program Files (x86)"),
+            user.join("AppData").join("Local"),
+            user.join("AppData").join("Roaming"),
+            user.join("Downloads"),
+            user.join("Desktop"),
+        ]);
+    }
+    if let Some(home) = dirs::home_dir() {
+        places.extend(["Downloads", "Desktop", "Games", "Applications"].map(|dir| home.join(dir)));
+    }
+    // Usually ~/Downloads again
+    if let Some(downloads) = dirs::download_dir()
+        && !places.contains(&downloads)
+    {
+        places.push(downloads);
+    }
+    places
+}
+
+/// Executables under `dir` matching a preset, as (preset index, path)
+fn scan(dir: &Path, depth: usize, found: &mut Vec<(usize, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // DirEntry::file_type doesn't follow symlinks, which keeps dosdevices out
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if depth < MAX_DEPTH {
+                scan(&entry.path(), depth + 1, found);
+            }
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(index) = PRESETS.iter().position(|preset| {
+            preset
+                .exe_names
+                .iter()
+                .any(|pattern| matches(pattern, &name))
+        }) {
+            found.push((index, entry.path()));
+        }
+    }
+}
+
+/// Where each preset was found, by index into PRESETS; the game's prefix is looked in
+/// first, and the first copy found wins
+pub fn detect(prefix: Option<&Path>) -> Vec<Option<PathBuf>> {
+    let mut found = Vec::new();
+    for dir in search_dirs(prefix) {
+        scan(&dir, 0, &mut found);
+    }
+    let mut located = vec![None; PRESETS.len()];
+    for (index, path) in found {
+        located[index].get_or_insert(path);
+    }
+    located
+}
@@ -0,0 +1,319 @@
+use crate::history;
+use std::io;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Child, Command, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How often the reaper checks tracked programs for exits
+const REAP_INTERVAL: Duration = Duration::from_millis(500);
+
+// Signals a program only dies of when it crashed
+const CRASH_SIGNALS: [libc::c_int; 5] = [
+    libc::SIGSEGV,
+    libc::SIGABRT,
+    libc::SIGBUS,
+    libc::SIGILL,
+    libc::SIGFPE,
+];
+
+/// A program Protonic started on behalf of a game
+pub struct TrackedProcess {
+    pub name: String,
+    pub app_id: String,
+    child: Child,
+    // How the program itself exited, while what it started is still running in its group
+    leader_status: Option<ExitStatus>,
+}
+
+impl TrackedProcess {
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+}
+
+/// A tracked program as listed in the window
+pub struct RunningProgram {
+    pub pid: u32,
+    pub name: String,
+    pub app_id: String,
+}
+
+/// A tracked program that has exited on its own
+pub struct ExitReport {
+    pub name: String,
+    pub app_id: String,
+    pub pid: u32,
+    pub status: ExitStatus,
+}
+
+impl ExitReport {
+    /// Human readable summary, e.g. "trainer.exe (pid 1234) exited with code 1"
+    pub fn describe(&self) -> String {
+        let how = match (self.status.code(), self.status.signal()) {
+            (Some(code), _) => format!("exited with code {}", code),
+            (None, Some(signal)) => format!("was killed by signal {}", signal),
+            (None, None) => "exited".to_string(),
+        };
+        format!("{} (pid {}) {}", self.name, self.pid, how)
+    }
+}
+
+/// Keeps hold of every injected program so it (and anything it spawned) can be stopped later
+#[derive(Clone, Default)]
+pub struct ProcessTracker {
+    processes: Arc<Mutex<Vec<TrackedProcess>>>,
+}
+
+impl ProcessTracker {
+    /// Spawn a command in its own process group and start tracking it.
+    /// The child becomes the group leader, so helpers it launches (injectors
+    /// starting payloads etc.) share its group and can be killed together. Its output goes
+    /// to its own log file, since it may outlive Protonic.
+    pub fn spawn(&self, command: &mut Command, name: &str, app_id: &str) -> io::Result<u32> {
+        let child = crate::logs::to_file(command, name)
+            .process_group(0)
+            .spawn()?;
+        let pid = child.id();
+        history::record(
+            app_id,
+            history::Event::ProgramStarted {
+                name: name.to_string(),
+            },
+        );
+        self.processes.lock().unwrap().push(TrackedProcess {
+            name: name.to_string(),
+            app_id: app_id.to_string(),
+            child,
+            leader_status: None,
+        });
+        Ok(pid)
+    }
+
+    /// Start a background thread that wait()s on tracked programs as they exit,
+    /// so they don't stay around as zombies, and hands each exit to `on_exit`. Launchers
+    /// and injectors often exit while what they started keeps running in their process
+    /// group, so a program stays tracked (and can be stopped) until its group is empty.
+    pub fn start_reaper(&self, on_exit: impl Fn(ExitReport) + Send + 'static) {
+        let processes = Arc::clone(&self.processes);
+        thread::spawn(move || {
+            loop {
+                let mut exited = Vec::new();
+                processes.lock().unwrap().retain_mut(|process| {
+                    let status = match process.leader_status {
+                        Some(status) => status,
+                        None => match process.child.try_wait() {
+                            Ok(Some(status)) => {
+                                process.leader_status = Some(status);
+                                status
+                            }
+                            Ok(None) => return true,
+                            // Already collected elsewhere; nothing left to track
+                            Err(_) => return false,
+                        },
+                    };
+                    if group_alive(process.pid() as libc::pid_t) {
+                        return true;
+                    }
+                    // Programs we stop are no longer tracked, so this wasn't us. Exit
+                    // codes are left alone: plenty of tools exit with 1 on purpose.
+                    if status
+                        .signal()
+                        .is_some_and(|signal| CRASH_SIGNALS.contains(&signal))
+                    {
+                        history::record(
+                            &process.app_id,
+                            history::Event::ProgramCrashed {
+                                name: process.name.clone(),
+                            },
+                        );
+                    }
+                    exited.push(ExitReport {
+                        name: process.name.clone(),
+                        app_id: process.app_id.clone(),
+                        pid: process.pid(),
+                        status,
+                    });
+                    false
+                });
+
+                for report in exited {
+                    on_exit(report);
+                }
+                thread::sleep(REAP_INTERVAL);
+            }
+        });
+    }
+
+    /// Stop every tracked program, giving each `grace` to exit before it is killed.
+    /// Returns the app IDs whose programs were stopped, so their cleanup hooks can run.
+    pub fn kill_all(&self, grace: Duration) -> Vec<String> {
+        let processes: Vec<TrackedProcess> = self.processes.lock().unwrap().drain(..).collect();
+        let mut app_ids: Vec<String> = Vec::new();
+        for mut process in processes {
+            kill_tree(&mut process, grace);
+            if !app_ids.contains(&process.app_id) {
+                app_ids.push(process.app_id);
+            }
+        }
+        app_ids
+    }
+
+    /// Every program still running, oldest first
+    pub fn running(&self) -> Vec<RunningProgram> {
+        self.processes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| RunningProgram {
+                pid: p.pid(),
+                name: p.name.clone(),
+                app_id: p.app_id.clone(),
+            })
+            .collect()
+    }
+
+    /// Names of the programs still running for a game
+    pub fn running_names(&self, app_id: &str) -> Vec<String> {
+        self.processes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.app_id == app_id)
+            .map(|p| p.name.clone())
+            .collect()
+    }
+
+    /// Stop the programs started for one game. Returns whether there were any.
+    pub fn kill_app(&self, app_id: &str, grace: Duration) -> bool {
+        let processes: Vec<TrackedProcess> = {
+            let mut tracked = self.processes.lock().unwrap();
+            let (matching, rest) = tracked.drain(..).partition(|p| p.app_id == app_id);
+            *tracked = rest;
+            matching
+        };
+        let stopped = !processes.is_empty();
+        for mut process in processes {
+            kill_tree(&mut process, grace);
+        }
+        stopped
+    }
+
+    /// Stop a single tracked program. Returns false if it had already exited.
+    pub fn kill_pid(&self, pid: u32, grace: Duration) -> bool {
+        let mut process = {
+            let mut tracked = self.processes.lock().unwrap();
+            match tracked.iter().position(|p| p.pid() == pid) {
+                Some(index) => tracked.remove(index),
+                None => return false,
+            }
+        };
+        kill_tree(&mut process, grace);
+        true
+    }
+
+    /// Send a signal to a game's programs called `name`. Returns whether any got it.
+    pub fn signal_named(&self, app_id: &str, name: &str, signal: libc::c_int) -> bool {
+        let pids: Vec<u32> = self
+            .processes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.app_id == app_id && p.name == name)
+            .map(|p| p.pid())
+            .collect();
+        let mut sent = false;
+        for pid in pids {
+            // SAFETY: kill only sends a signal to the program we started
+            sent |= unsafe { libc::kill(pid as libc::pid_t, signal) == 0 };
+        }
+        sent
+    }
+}
+
+/// Send a signal to a whole process group
+fn signal_group(pgid: libc::pid_t, signal: libc::c_int) -> bool {
+    // SAFETY: killpg only sends a signal; an already-gone group just returns ESRCH
+    unsafe { libc::killpg(pgid, signal) == 0 }
+}
+
+/// Whether anything is left in a process group. Only ESRCH means it's gone; EPERM means
+/// a member changed its user, e.g. through a setuid helper.
+fn group_alive(pgid: libc::pid_t) -> bool {
+    signal_group(pgid, 0) || io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+/// Terminate a process group: SIGTERM first so tools can restore gamma/hooks,
+/// then SIGKILL if anything is still around after the grace period.
+/// The leader's pid doubles as the group id because we spawned it with `process_group(0)`.
+fn kill_tree(process: &mut TrackedProcess, grace: Duration) {
+    let pgid = process.pid() as libc::pid_t;
+    println!(
+        "Stopping {} (process group {}) with SIGTERM",
+        process.name, pgid
+    );
+    if !signal_group(pgid, libc::SIGTERM) {
+        let _ = process.child.wait();
+        return;
+    }
+
+    let deadline = Instant::now() + grace;
+    loop {
+        // Reap the leader as soon as it exits; signal 0 then tells us if any
+        // of its children are still alive in the group
+        let _ = process.child.try_wait();
+        if !signal_group(pgid, 0) {
+            println!("{} exited cleanly", process.name);
+            return;
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    println!(
+        "{} still running after {} ms, escalating to SIGKILL",
+        process.name,
+        grace.as_millis()
+    );
+    signal_group(pgid, libc::SIGKILL);
+    // Collect the leader's exit status so it doesn't linger as a zombie
+    let _ = process.child.wait();
+}
+
+/// Wait for an untracked child (like the `steam` launcher) in the background so it gets reaped
+pub fn reap_in_background(mut child: Child) {
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+}
+
+/// Run cleanup commands (reset gamma, restart the compositor...) one after another through `sh -c`
+pub fn run_cleanup_hooks(hooks: &[String]) {
+    run_hooks("Cleanup", hooks);
+}
+
+/// Run the commands that redo what a game update undid. Returns how many succeeded.
+pub fn run_update_hooks(hooks: &[String]) -> usize {
+    run_hooks("Update", hooks)
+}
+
+fn run_hooks(kind: &str, hooks: &[String]) -> usize {
+    let mut succeeded = 0;
+    for hook in hooks.iter().map(|h| h.trim()).filter(|h| !h.is_empty()) {
+        println!("Running {} hook: {}", kind.to_lowercase(), hook);
+        match Command::new("sh").arg("-c").arg(hook).status() {
+            Ok(status) if status.success() => succeeded += 1,
+            Ok(status) => println!("Warning: {} hook failed ({}): {}", kind, status, hook),
+            Err(e) => println!(
+                "Warning: Could not run {} hook '{}': {}",
+                kind.to_lowercase(),
+                hook,
+                e
+            ),
+        }
+    }
+    succeeded
+}
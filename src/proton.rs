@@ -0,0 +1,353 @@
+//! Running programs in a game's prefix with the game's own Proton, for systems without
+//! protonhax. The Proton build comes from the prefix's `config_info` (written by the
+//! Proton that last ran it) or else from Steam's compatibility tool setting, and the
+//! game is seen running by the SteamAppId Steam puts in its environment. Also the
+//! compatibility tool Steam is set to run each game with, which can be changed here.
+
+use crate::{backup, steam, vdf};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+// Set on everything we start, so our own programs don't count as the game running
+const INJECTED_VAR: &str = "PROTONIC_INJECTED";
+
+/// A game's Proton and prefix
+#[derive(Debug, Clone)]
+pub struct Runtime {
+    // Folder with the `proton` script
+    pub proton: PathBuf,
+    // steamapps/compatdata/<app id>
+    pub compat_data: PathBuf,
+    pub steam_root: PathBuf,
+}
+
+impl Runtime {
+    /// The Proton that runs the game whose prefix is `compat_data`
+    pub fn find(app_id: &str, compat_data: &Path) -> Result<Runtime, String> {
+        let steam_dir = steam::locate()?;
+        let proton = from_config_info(compat_data)
+            .or_else(|| from_compat_tool(&steam_dir, app_id))
+            .ok_or_else(|| {
+                format!(
+                    "Could not find the Proton version of game {}. Start it from Steam once, \
+                     or pick one under Properties → Compatibility.",
+                    app_id
+                )
+            })?;
+        Ok(Runtime {
+            proton,
+            compat_data: compat_data.to_path_buf(),
+            steam_root: steam_dir.path().to_path_buf(),
+        })
+    }
+
+    /// `proton runinprefix <exe>`: runs in the game's prefix (and so its wineserver)
+    /// without Proton setting the prefix up again
+    pub fn run_command(&self, app_id: &str, exe: &str) -> Command {
+        let mut command = Command::new(self.proton.join("proton"));
+        command
+            .arg("runinprefix")
+            .arg(exe)
+            .env("STEAM_COMPAT_DATA_PATH", &self.compat_data)
+            .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &self.steam_root)
+            .env("SteamAppId", app_id)
+            .env("SteamGameId", app_id)
+            .env(INJECTED_VAR, "1");
+        command
+    }
+
+    /// The Wine build inside this Proton (`files/` in current ones, `dist/` in older ones)
+    pub fn wine_dir(&self) -> Option<PathBuf> {
+        ["files", "dist"]
+            .into_iter()
+            .map(|dir| self.proton.join(dir))
+            .find(|dir| dir.join("bin").join("wine").is_file())
+    }
+
+    /// Wine's command prompt in the game's prefix
+    pub fn cmd_command(&self, app_id: &str) -> Command {
+        self.run_command(app_id, "cmd")
+    }
+}
+
+/// config_info's first line is the Proton version, the others are paths inside the
+/// Proton build that made the prefix, e.g. ".../Proton 9.0/files/share/fonts/"
+fn from_config_info(compat_data: &Path) -> Option<PathBuf> {
+    let info = fs::read_to_string(compat_data.join("config_info")).ok()?;
+    info.lines().skip(1).find_map(|line| {
+        let end = line.find("/files/").or_else(|| line.find("/dist/"))?;
+        let dir = PathBuf::from(&line[..end]);
+        dir.join("proton").is_file().then_some(dir)
+    })
+}
+
+/// The compatibility tool Steam is set to use for the game (or for all games)
+fn from_compat_tool(steam_dir: &steamlocate::SteamDir, app_id: &str) -> Option<PathBuf> {
+    let mapping = steam_dir.compat_tool_mapping().ok()?;
+    let name = app_id
+        .parse()
+        .ok()
+        .and_then(|id: u32| mapping.get(&id))
+        .or_else(|| mapping.get(&0))
+        .and_then(|tool| tool.name.clone())
+        .filter(|name| !name.is_empty())?;
+
+    // Custom builds (GE-Proton etc.) describe themselves in compatibilitytools.d
+    let custom = custom_tool_dirs(steam_dir.path())
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .find_map(|entry| custom_tool(&entry.path(), &name));
+    if custom.is_some() {
+        return custom;
+    }
+
+    // Valve's builds are apps in a library, e.g. proton_9 in "Proton 9.0". Names like
+    // proton_10 don't say where the major version ends, so the folders are matched by
+    // their own name instead
+    steam_dir
+        .library_paths()
+        .ok()?
+        .into_iter()
+        .filter_map(|library| fs::read_dir(library.join("steamapps").join("common")).ok())
+        .flat_map(|entries| entries.flatten())
+        .find(|entry| {
+            official_name(&entry.file_name().to_string_lossy()).as_deref() == Some(name.as_str())
+                && entry.path().join("proton").is_file()
+        })
+        .map(|entry| entry.path())
+}
+
+/// A compatibility tool installed for Steam
+#[derive(Debug, Clone)]
+pub struct CompatTool {
+    // What Steam calls it in CompatToolMapping, e.g. "proton_9" or "GE-Proton9-20"
+    pub name: String,
+    // What Steam shows, e.g. "Proton 9.0"
+    pub label: String,
+}
+
+/// Folders that hold custom tools, each in a folder with a compatibilitytool.vdf
+fn custom_tool_dirs(steam_root: &Path) -> [PathBuf; 2] {
+    [
+        steam_root.join("compatibilitytools.d"),
+        PathBuf::from("/usr/share/steam/compatibilitytools.d"),
+    ]
+}
+
+/// Valve's Proton builds in the Steam libraries and the custom ones in
+/// compatibilitytools.d, sorted by label
+pub fn installed_tools() -> Vec<CompatTool> {
+    let Ok(steam_dir) = steam::locate() else {
+        return Vec::new();
+    };
+    let mut tools = Vec::new();
+    for library in steam_dir.library_paths().unwrap_or_default() {
+        let Ok(entries) = fs::read_dir(library.join("steamapps").join("common")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let folder = entry.file_name().to_string_lossy().into_owned();
+            if let Some(name) = official_name(&folder)
+                && entry.path().join("proton").is_file()
+            {
+                tools.push(CompatTool {
+                    name,
+                    label: folder,
+                });
+            }
+        }
+    }
+    for dir in custom_tool_dirs(steam_dir.path()) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Some(root) = fs::read_to_string(entry.path().join("compatibilitytool.vdf"))
+                .ok()
+                .and_then(|text| vdf::parse(&text).ok())
+            else {
+                continue;
+            };
+            let Some(vdf::Node::Section(declared)) =
+                root.get_path(&["compatibilitytools", "compat_tools"])
+            else {
+                continue;
+            };
+            for (name, tool) in declared {
+                let label = match tool.get("display_name") {
+                    Some(vdf::Node::Value(label)) if !label.is_empty() => label.clone(),
+                    _ => name.clone(),
+                };
+                tools.push(CompatTool {
+                    name: name.clone(),
+                    label,
+                });
+            }
+        }
+    }
+    tools.sort_by(|a, b| a.label.cmp(&b.label));
+    tools.dedup_by(|a, b| a.name == b.name);
+    tools
+}
+
+/// The tool Steam is set to run the game with under Properties → Compatibility,
+/// None when the game uses Steam's default
+pub fn compat_tool(app_id: &str) -> Option<String> {
+    let id: u32 = app_id.parse().ok()?;
+    steam::locate()
+        .ok()?
+        .compat_tool_mapping()
+        .ok()?
+        .get(&id)
+        .and_then(|tool| tool.name.clone())
+        .filter(|name| !name.is_empty())
+}
+
+// Where config.vdf keeps the per-game tools
+const COMPAT_TOOL_MAPPING_PATH: [&str; 5] = [
+    "InstallConfigStore",
+    "Software",
+    "Valve",
+    "Steam",
+    "CompatToolMapping",
+];
+// The priority Steam gives a tool picked by the user
+const USER_PRIORITY: &str = "250";
+
+/// Set the tool Steam runs the game with in config.vdf, or go back to Steam's default
+/// with None. Steam only reads the file when it starts.
+pub fn set_compat_tool(app_id: &str, name: Option<&str>) -> Result<(), String> {
+    let steam_dir = steam::locate()?;
+    let path = steam_dir.path().join("config").join("config.vdf");
+    let text =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read config.vdf: {}", e))?;
+    let mut root = vdf::parse(&text).map_err(|e| format!("Could not parse config.vdf: {}", e))?;
+    let steam = root
+        .get_path_mut(&COMPAT_TOOL_MAPPING_PATH[..4])
+        .ok_or_else(|| "config.vdf has no Steam section".to_string())?;
+    if steam.get(COMPAT_TOOL_MAPPING_PATH[4]).is_none() {
+        steam.set(COMPAT_TOOL_MAPPING_PATH[4], vdf::Node::Section(Vec::new()));
+    }
+    let mapping = root
+        .get_path_mut(&COMPAT_TOOL_MAPPING_PATH)
+        .ok_or_else(|| "config.vdf's CompatToolMapping is not a section".to_string())?;
+    // Steam itself leaves an entry with an empty name when the override is turned off
+    mapping.set(
+        app_id,
+        vdf::Node::Section(vec![
+            (
+                "name".to_string(),
+                vdf::Node::Value(name.unwrap_or_default().to_string()),
+            ),
+            ("config".to_string(), vdf::Node::Value(String::new())),
+            (
+                "priority".to_string(),
+                vdf::Node::Value(USER_PRIORITY.to_string()),
+            ),
+        ]),
+    );
+    let new_content = vdf::serialize(&root);
+    if vdf::parse(&new_content).as_ref() != Ok(&root) {
+        return Err("Refusing to write config.vdf, it would not parse back".to_string());
+    }
+    let snapshot = backup::snapshot_config(&path)?;
+    if let Err(e) = fs::write(&path, new_content) {
+        backup::restore_snapshot(&snapshot, &path)?;
+        return Err(format!(
+            "Writing config.vdf failed ({}), restored the previous version",
+            e
+        ));
+    }
+    Ok(())
+}
+
+/// The install folder of the tool called `name` if `dir` holds it
+fn custom_tool(dir: &Path, name: &str) -> Option<PathBuf> {
+    let text = fs::read_to_string(dir.join("compatibilitytool.vdf")).ok()?;
+    let root = vdf::parse(&text).ok()?;
+    let tool = root.get_path(&["compatibilitytools", "compat_tools", name])?;
+    let install_path = match tool.get("install_path") {
+        Some(vdf::Node::Value(path)) => path.clone(),
+        _ => ".".to_string(),
+    };
+    let found = dir.join(install_path);
+    found.join("proton").is_file().then_some(found)
+}
+
+/// Steam's name for one of Valve's Proton builds from its folder: "Proton 9.0" is
+/// proton_9, "Proton 10.0" proton_10, "Proton 6.3" proton_63 and "Proton 5.13" proton_513
+fn official_name(folder: &str) -> Option<String> {
+    match folder {
+        "Proton - Experimental" => return Some("proton_experimental".to_string()),
+        "Proton Hotfix" => return Some("proton_hotfix".to_string()),
+        _ => {}
+    }
+    let (major, minor) = folder.strip_prefix("Proton ")?.split_once('.')?;
+    let is_number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if !is_number(major) || !is_number(minor) {
+        return None;
+    }
+    let minor = if minor == "0" { "" } else { minor };
+    Some(format!("proton_{}{}", major, minor))
+}
+
+/// The Proton games' processes running now, as (app ID, /proc/<pid>). Steam gives the
+/// game's processes SteamAppId and STEAM_COMPAT_DATA_PATH; ours are marked and skipped.
+fn game_processes() -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .chars()
+                .all(|c| c.is_ascii_digit())
+        })
+        .filter_map(|entry| {
+            let environ = fs::read(entry.path().join("environ")).ok()?;
+            let vars: Vec<&[u8]> = environ.split(|&b| b == 0).collect();
+            let has = |name: &str| {
+                vars.iter()
+                    .any(|var| var.starts_with(format!("{}=", name).as_bytes()))
+            };
+            if !has("STEAM_COMPAT_DATA_PATH") || has(INJECTED_VAR) {
+                return None;
+            }
+            vars.iter()
+                .find_map(|var| var.strip_prefix(b"SteamAppId="))
+                .map(|id| String::from_utf8_lossy(id).into_owned())
+                .filter(|id| !id.is_empty() && id != "0")
+                .map(|app_id| (app_id, entry.path()))
+        })
+        .collect()
+}
+
+/// App IDs of the Proton games running now
+pub fn running_games() -> BTreeSet<String> {
+    game_processes()
+        .into_iter()
+        .map(|(app_id, _)| app_id)
+        .collect()
+}
+
+/// When the game's oldest running process started, which tells one run of the game from
+/// the next like a protonhax session does
+pub fn session_started(app_id: &str) -> Option<SystemTime> {
+    game_processes()
+        .into_iter()
+        .filter(|(id, _)| id == app_id)
+        .filter_map(|(_, dir)| fs::metadata(dir).and_then(|meta| meta.modified()).ok())
+        .min()
+}
+
+/// Whether any Proton build is installed for the native backend to use
+pub fn any_installed() -> bool {
+    !installed_tools().is_empty()
+}
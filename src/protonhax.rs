@@ -0,0 +1,176 @@
+//! Helpers around the protonhax CLI and the session directories it keeps, and an
+//! installer for when it's missing.
+
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+// The script in protonhax's repository, as GitHub's API describes it
+const SCRIPT_API_URL: &str = "https://api.github.com/repos/jcnils/protonhax/contents/protonhax";
+const REPO_URL: &str = "https://github.com/jcnils/protonhax";
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+// The script is a few KB
+const MAX_SCRIPT_BYTES: u64 = 1024 * 1024;
+
+/// protonhax writes one directory per running game (named after its app ID)
+/// under `$XDG_RUNTIME_DIR/protonhax`, the same place `protonhax ls` reads from
+fn sessions_dir() -> PathBuf {
+    let runtime_dir = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        // SAFETY: getuid has no preconditions and can't fail
+        .unwrap_or_else(|| PathBuf::from(format!("/run/user/{}", unsafe { libc::getuid() })));
+    runtime_dir.join("protonhax")
+}
+
+/// App IDs of all active protonhax sessions, whoever started the game
+pub fn active_sessions() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(sessions_dir()) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    sessions.sort();
+    sessions
+}
+
+/// When a game's current protonhax session started, which tells one run of the game
+/// from the next
+pub fn session_started(app_id: &str) -> Option<SystemTime> {
+    let metadata = fs::metadata(sessions_dir().join(app_id)).ok()?;
+    metadata.created().or_else(|_| metadata.modified()).ok()
+}
+
+/// `protonhax run <appid> <exe>`: start a Windows program inside the game's session
+pub fn run_command(app_id: &str, exe: &str) -> Command {
+    let mut command = Command::new("protonhax");
+    command.arg("run").arg(app_id).arg(exe);
+    command
+}
+
+/// `protonhax cmd <appid>`: a Wine command prompt inside the game's session
+pub fn cmd_command(app_id: &str) -> Command {
+    let mut command = Command::new("protonhax");
+    command.arg("cmd").arg(app_id);
+    command
+}
+
+#[derive(Deserialize)]
+struct RepoFile {
+    // Git's SHA-1 of the file
+    sha: String,
+    download_url: String,
+}
+
+/// Where the installer puts protonhax: ~/.local/bin, or $XDG_BIN_HOME
+pub fn install_dir() -> Option<PathBuf> {
+    dirs::executable_dir()
+}
+
+/// The SHA-1 git gives a file's contents (`git hash-object`)
+fn git_blob_sha1(bytes: &[u8]) -> Result<String, String> {
+    let mut child = Command::new("sha1sum")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run sha1sum: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(format!("blob {}\0", bytes.len()).as_bytes())
+            .and_then(|_| stdin.write_all(bytes))
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(String::from)
+        .ok_or_else(|| "sha1sum gave no output".to_string())
+}
+
+/// Download the protonhax script from its repository into install_dir(), checked
+/// against the checksum GitHub lists for it. Returns what to tell the user.
+pub fn install() -> Result<String, String> {
+    let dir = install_dir().ok_or_else(|| "Could not find ~/.local/bin".to_string())?;
+    let file: RepoFile = ureq::get(SCRIPT_API_URL)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .set("Accept", "application/vnd.github+json")
+        .set(
+            "User-Agent",
+            concat!("protonic/", env!("CARGO_PKG_VERSION")),
+        )
+        .call()
+        .map_err(|e| format!("GitHub request for protonhax failed: {}", e))?
+        .into_json()
+        .map_err(|e| format!("Unexpected GitHub response for protonhax: {}", e))?;
+
+    let mut script = Vec::new();
+    ureq::get(&file.download_url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .call()
+        .map_err(|e| format!("Download failed: {}", e))?
+        .into_reader()
+        .take(MAX_SCRIPT_BYTES)
+        .read_to_end(&mut script)
+        .map_err(|e| format!("Download failed: {}", e))?;
+    let actual = git_blob_sha1(&script)?;
+    if !actual.eq_ignore_ascii_case(&file.sha) {
+        return Err(format!(
+            "Checksum mismatch for protonhax: expected {}, got {}",
+            file.sha, actual
+        ));
+    }
+    if !script.starts_with(b"#!") {
+        return Err("The download isn't the protonhax script".to_string());
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join("protonhax");
+    // Written next to it first, so a failed write doesn't leave half a script
+    let partial = dir.join(".protonhax.partial");
+    fs::write(&partial, &script).map_err(|e| e.to_string())?;
+    fs::set_permissions(&partial, fs::Permissions::from_mode(0o755))
+        .and_then(|_| fs::rename(&partial, &path))
+        .map_err(|e| format!("Failed to install {}: {}", path.display(), e))?;
+
+    let in_path =
+        env::var_os("PATH").is_some_and(|paths| env::split_paths(&paths).any(|p| p == dir));
+    Ok(if in_path {
+        format!("Installed protonhax to {}", path.display())
+    } else {
+        format!(
+            "Installed protonhax to {}, but that folder isn't in your PATH. Add it in your \
+             shell profile and log in again so Steam and Protonic can find it.",
+            path.display()
+        )
+    })
+}
+
+/// How this distribution packages protonhax, from /etc/os-release
+pub fn package_hint() -> String {
+    let os_release = fs::read_to_string("/etc/os-release").unwrap_or_default();
+    let ids: Vec<&str> = os_release
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("ID=")
+                .or_else(|| line.strip_prefix("ID_LIKE="))
+        })
+        .flat_map(|value| value.trim_matches('"').split_whitespace())
+        .collect();
+    if ids.contains(&"arch") {
+        "It's also in the AUR as protonhax-git.".to_string()
+    } else {
+        format!(
+            "Or copy the protonhax script from {} into a folder in your PATH.",
+            REPO_URL
+        )
+    }
+}
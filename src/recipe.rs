@@ -0,0 +1,702 @@
+//! Launch recipes: ordered steps run when the hotkey is pressed, for tools that need more
+//! than "start these two programs".
+
+use crate::backend::Injector;
+use crate::process::ProcessTracker;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How often we look for a window while waiting for it
+const WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// Pause between attempts of a step marked [retry N]
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// One step of a recipe
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Step {
+    /// Start a program inside the game's Proton session
+    RunExe {
+        path: String,
+    },
+    /// Wait until a window whose title contains `title` shows up
+    WaitForWindow {
+        title: String,
+        timeout_secs: u64,
+    },
+    /// Run a shell command on the host and wait for it
+    RunHook {
+        command: String,
+    },
+    Sleep {
+        millis: u64,
+    },
+    /// Press a key combination in the focused window, like "Return" or "ctrl+shift+s"
+    SendKeys {
+        keys: String,
+    },
+    /// Type text into the focused window
+    TypeText {
+        text: String,
+    },
+    /// Run the inner steps at the same time and wait for all of them.
+    /// Written as a `parallel` ... `end` block.
+    Parallel {
+        steps: Vec<GuardedStep>,
+    },
+}
+
+/// Something checked before running a step
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum Condition {
+    /// A file or directory exists, relative to the game's Wine prefix (compatdata/<appid>/pfx)
+    FileExists { path: String },
+    /// A process with this name is running, e.g. "setup.exe"
+    ProcessRunning { name: String },
+}
+
+impl Condition {
+    fn to_text(&self) -> String {
+        match self {
+            Condition::FileExists { path } => format!("exists {}", path),
+            Condition::ProcessRunning { name } => format!("running {}", name),
+        }
+    }
+
+    fn parse(text: &str) -> Result<Condition, String> {
+        let (check, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return Err(format!("'{}' needs something to check", check));
+        }
+        match check {
+            "exists" => Ok(Condition::FileExists {
+                path: rest.to_string(),
+            }),
+            "running" => Ok(Condition::ProcessRunning {
+                name: rest.to_string(),
+            }),
+            other => Err(format!("Unknown condition '{}'", other)),
+        }
+    }
+
+    fn holds(&self, prefix: Option<&Path>) -> Result<bool, String> {
+        match self {
+            Condition::FileExists { path } => {
+                let prefix = prefix.ok_or_else(|| {
+                    "The game isn't installed here, so it has no prefix to check".to_string()
+                })?;
+                Ok(prefix
+                    .join("pfx")
+                    .join(path.trim_start_matches('/'))
+                    .exists())
+            }
+            Condition::ProcessRunning { name } => Ok(process_running(name)),
+        }
+    }
+}
+
+/// What happens when a step fails. Written as a `[continue]` or `[retry N]` prefix in the editor.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Stop the recipe
+    #[default]
+    Abort,
+    /// Log the failure and go on with the next step
+    Continue,
+    /// Try again up to this many more times, then stop the recipe
+    Retry(u32),
+}
+
+impl FailurePolicy {
+    fn is_abort(&self) -> bool {
+        *self == FailurePolicy::Abort
+    }
+
+    fn parse(text: &str) -> Result<FailurePolicy, String> {
+        match text.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["abort"] => Ok(FailurePolicy::Abort),
+            ["continue"] => Ok(FailurePolicy::Continue),
+            ["retry", times] => times
+                .parse()
+                .map(FailurePolicy::Retry)
+                .map_err(|_| format!("'{}' isn't a number of retries", times)),
+            _ => Err(format!(
+                "Unknown failure policy '[{}]', use [continue] or [retry N]",
+                text
+            )),
+        }
+    }
+}
+
+/// A step with an optional guard. Written as `if <condition>: <step>` or
+/// `unless <condition>: <step>` in the editor.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GuardedStep {
+    #[serde(flatten)]
+    pub step: Step,
+    /// Only run the step when this holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Condition>,
+    /// Skip the step when this holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unless: Option<Condition>,
+    #[serde(default, skip_serializing_if = "FailurePolicy::is_abort")]
+    pub on_failure: FailurePolicy,
+}
+
+impl GuardedStep {
+    pub fn to_line(&self) -> String {
+        let policy = match self.on_failure {
+            FailurePolicy::Abort => String::new(),
+            FailurePolicy::Continue => "[continue] ".to_string(),
+            FailurePolicy::Retry(times) => format!("[retry {}] ", times),
+        };
+        match (&self.when, &self.unless) {
+            (Some(condition), _) => format!(
+                "{}if {}: {}",
+                policy,
+                condition.to_text(),
+                self.step.to_line()
+            ),
+            (None, Some(condition)) => format!(
+                "{}unless {}: {}",
+                policy,
+                condition.to_text(),
+                self.step.to_line()
+            ),
+            (None, None) => format!("{}{}", policy, self.step.to_line()),
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<GuardedStep, String> {
+        if let Some(rest) = line.strip_prefix('[') {
+            let (policy, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| "Missing ']' after the failure policy".to_string())?;
+            return Ok(GuardedStep {
+                on_failure: FailurePolicy::parse(policy)?,
+                ..GuardedStep::parse_line(rest.trim())?
+            });
+        }
+
+        let guard = |rest: &str| -> Result<(Condition, Step), String> {
+            let (condition, step) = rest
+                .split_once(':')
+                .ok_or_else(|| "A condition needs ':' before its step".to_string())?;
+            Ok((
+                Condition::parse(condition.trim())?,
+                Step::parse_line(step.trim())?,
+            ))
+        };
+        if let Some(rest) = line.strip_prefix("if ") {
+            let (condition, step) = guard(rest)?;
+            Ok(GuardedStep {
+                step,
+                when: Some(condition),
+                unless: None,
+                on_failure: FailurePolicy::Abort,
+            })
+        } else if let Some(rest) = line.strip_prefix("unless ") {
+            let (condition, step) = guard(rest)?;
+            Ok(GuardedStep {
+                step,
+                when: None,
+                unless: Some(condition),
+                on_failure: FailurePolicy::Abort,
+            })
+        } else {
+            Ok(GuardedStep {
+                step: Step::parse_line(line)?,
+                when: None,
+                unless: None,
+                on_failure: FailurePolicy::Abort,
+            })
+        }
+    }
+
+    /// Whether the guard lets the step run
+    fn should_run(&self, prefix: Option<&Path>) -> Result<bool, String> {
+        if let Some(condition) = &self.when
+            && !condition.holds(prefix)?
+        {
+            return Ok(false);
+        }
+        if let Some(condition) = &self.unless
+            && condition.holds(prefix)?
+        {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
+impl Step {
+    /// The step as one line of the recipe editor
+    pub fn to_line(&self) -> String {
+        match self {
+            Step::RunExe { path } => format!("run {}", path),
+            Step::WaitForWindow {
+                title,
+                timeout_secs,
+            } => format!("wait_window {} {}", timeout_secs, title),
+            Step::RunHook { command } => format!("hook {}", command),
+            Step::Sleep { millis } => format!("sleep {}", millis),
+            Step::SendKeys { keys } => format!("keys {}", keys),
+            Step::TypeText { text } => format!("type {}", text),
+            Step::Parallel { .. } => "parallel".to_string(),
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<Step, String> {
+        let (action, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let need = |what: &str| -> Result<(), String> {
+            if rest.is_empty() {
+                Err(format!("'{}' needs {}", action, what))
+            } else {
+                Ok(())
+            }
+        };
+        match action {
+            "run" => {
+                need("a program path")?;
+                Ok(Step::RunExe {
+                    path: rest.to_string(),
+                })
+            }
+            "wait_window" => {
+                let (timeout, title) = rest.split_once(char::is_whitespace).ok_or_else(|| {
+                    "'wait_window' needs a timeout in seconds and a title".to_string()
+                })?;
+                Ok(Step::WaitForWindow {
+                    title: title.trim().to_string(),
+                    timeout_secs: timeout
+                        .parse()
+                        .map_err(|_| format!("'{}' isn't a number of seconds", timeout))?,
+                })
+            }
+            "hook" => {
+                need("a command")?;
+                Ok(Step::RunHook {
+                    command: rest.to_string(),
+                })
+            }
+            "sleep" => Ok(Step::Sleep {
+                millis: rest
+                    .parse()
+                    .map_err(|_| format!("'{}' isn't a number of milliseconds", rest))?,
+            }),
+            "keys" => {
+                need("a key combination")?;
+                Ok(Step::SendKeys {
+                    keys: rest.to_string(),
+                })
+            }
+            "type" => {
+                need("some text")?;
+                Ok(Step::TypeText {
+                    text: rest.to_string(),
+                })
+            }
+            "parallel" if rest.is_empty() => Ok(Step::Parallel { steps: Vec::new() }),
+            "parallel" => Err("'parallel' goes on its own line, followed by its steps".to_string()),
+            other => Err(format!("Unknown step '{}'", other)),
+        }
+    }
+}
+
+/// Parse the recipe editor's text, one step per line. Blank lines and `#` comments are ignored.
+pub fn parse(text: &str) -> Result<Vec<GuardedStep>, String> {
+    let mut steps = Vec::new();
+    // Parallel block being filled, with the line it started on
+    let mut block: Option<(usize, GuardedStep)> = None;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |e: String| format!("Line {}: {}", index + 1, e);
+        if line == "end" {
+            let (_, finished) = block
+                .take()
+                .ok_or_else(|| error("'end' without 'parallel'".to_string()))?;
+            steps.push(finished);
+            continue;
+        }
+        let step = GuardedStep::parse_line(line).map_err(error)?;
+        match (&mut block, matches!(step.step, Step::Parallel { .. })) {
+            (Some(_), true) => {
+                return Err(error("Parallel blocks can't be nested".to_string()));
+            }
+            (Some((_, open)), false) => {
+                if let Step::Parallel { steps } = &mut open.step {
+                    steps.push(step);
+                }
+            }
+            (None, true) => block = Some((index, step)),
+            (None, false) => steps.push(step),
+        }
+    }
+    if let Some((start, _)) = block {
+        return Err(format!(
+            "Line {}: 'parallel' is missing its 'end'",
+            start + 1
+        ));
+    }
+    Ok(steps)
+}
+
+pub fn to_text(steps: &[GuardedStep]) -> String {
+    let mut lines = Vec::new();
+    for guarded in steps {
+        lines.push(guarded.to_line());
+        if let Step::Parallel { steps } = &guarded.step {
+            lines.extend(steps.iter().map(|inner| format!("  {}", inner.to_line())));
+            lines.push("end".to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// Rewrite the program path of every `run` step, including those in parallel blocks
+pub fn resolve_programs(
+    steps: &mut [GuardedStep],
+    resolve: &dyn Fn(&str) -> Result<String, String>,
+) -> Result<(), String> {
+    for guarded in steps {
+        match &mut guarded.step {
+            Step::RunExe { path } => *path = resolve(path)?,
+            Step::Parallel { steps } => resolve_programs(steps, resolve)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether a window with `title` in its name exists (X11/XWayland, through xdotool)
+fn window_exists(title: &str) -> Result<bool, String> {
+    let output = Command::new("xdotool")
+        .arg("search")
+        .arg("--name")
+        .arg(title)
+        .output()
+        .map_err(|e| format!("Waiting for windows needs xdotool: {}", e))?;
+    Ok(!output.stdout.is_empty())
+}
+
+/// Whether a process called `name` is running. Wine programs show up with their
+/// Windows path as the command line, so the file name of argv[0] is compared too.
+pub fn process_running(name: &str) -> bool {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let dir = entry.path();
+        let comm = fs::read_to_string(dir.join("comm")).unwrap_or_default();
+        if comm.trim() == name {
+            return true;
+        }
+        let cmdline = fs::read(dir.join("cmdline")).unwrap_or_default();
+        let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+        String::from_utf8_lossy(argv0)
+            .rsplit(['/', '\\'])
+            .next()
+            .is_some_and(|file| file.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Where a step of a running recipe is at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+/// Live state of one step, for the progress view. Steps inside a parallel block
+/// follow the block, with their label indented.
+#[derive(Debug, Clone)]
+pub struct StepProgress {
+    pub label: String,
+    pub state: StepState,
+    started: Option<Instant>,
+    pub duration: Option<Duration>,
+    /// Why the step failed, empty otherwise
+    pub error: String,
+}
+
+/// Every step of a recipe in the order the progress view lists them, all pending
+pub fn pending_progress(steps: &[GuardedStep]) -> Vec<StepProgress> {
+    to_text(steps)
+        .lines()
+        .filter(|line| line.trim() != "end")
+        .map(|line| StepProgress {
+            label: line.to_string(),
+            state: StepState::Pending,
+            started: None,
+            duration: None,
+            error: String::new(),
+        })
+        .collect()
+}
+
+/// Number of progress rows a step takes up
+fn row_count(guarded: &GuardedStep) -> usize {
+    match &guarded.step {
+        Step::Parallel { steps } => 1 + steps.len(),
+        _ => 1,
+    }
+}
+
+struct Runner<'a> {
+    app_id: &'a str,
+    injector: &'a Injector,
+    prefix: Option<&'a Path>,
+    tracker: &'a ProcessTracker,
+    progress: Mutex<Vec<StepProgress>>,
+    on_progress: &'a (dyn Fn(Vec<StepProgress>) + Sync),
+}
+
+impl Runner<'_> {
+    /// Move a step to `state` and hand the new snapshot to the UI
+    fn set_state(&self, row: usize, state: StepState, error: Option<&str>) {
+        let snapshot = {
+            let mut progress = self.progress.lock().unwrap();
+            let Some(step) = progress.get_mut(row) else {
+                return;
+            };
+            match state {
+                StepState::Running => step.started = Some(Instant::now()),
+                StepState::Succeeded | StepState::Failed => {
+                    step.duration = step.started.map(|started| started.elapsed());
+                }
+                _ => {}
+            }
+            step.state = state;
+            step.error = error.unwrap_or_default().to_string();
+            progress.clone()
+        };
+        (self.on_progress)(snapshot);
+    }
+
+    fn run_step(&self, guarded: &GuardedStep, row: usize) -> Result<(), String> {
+        if !guarded.should_run(self.prefix)? {
+            println!("Recipe step skipped: {}", guarded.to_line());
+            self.set_state(row, StepState::Skipped, None);
+            return Ok(());
+        }
+        println!("Recipe step: {}", guarded.step.to_line());
+        self.set_state(row, StepState::Running, None);
+        let mut attempt = 0;
+        loop {
+            let Err(e) = self.execute(&guarded.step, row) else {
+                self.set_state(row, StepState::Succeeded, None);
+                return Ok(());
+            };
+            attempt += 1;
+            match guarded.on_failure {
+                FailurePolicy::Retry(times) if attempt <= times => {
+                    println!(
+                        "Warning: Recipe step failed ({}), retrying ({} of {})",
+                        e, attempt, times
+                    );
+                    thread::sleep(RETRY_DELAY);
+                }
+                FailurePolicy::Continue => {
+                    println!("Warning: Recipe step failed ({}), continuing", e);
+                    self.set_state(row, StepState::Failed, Some(&e));
+                    return Ok(());
+                }
+                _ => {
+                    self.set_state(row, StepState::Failed, Some(&e));
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    fn execute(&self, step: &Step, row: usize) -> Result<(), String> {
+        match step {
+            Step::RunExe { path } => {
+                self.tracker
+                    .spawn(
+                        &mut self.injector.run_command(self.app_id, path),
+                        &crate::exe_display_name(path),
+                        self.app_id,
+                    )
+                    .map_err(|e| format!("Could not launch {}: {}", path, e))?;
+            }
+            Step::WaitForWindow {
+                title,
+                timeout_secs,
+            } => {
+                let deadline = Instant::now() + Duration::from_secs(*timeout_secs);
+                while !window_exists(title)? {
+                    if Instant::now() >= deadline {
+                        return Err(format!(
+                            "No window called '{}' after {} s",
+                            title, timeout_secs
+                        ));
+                    }
+                    thread::sleep(WINDOW_POLL_INTERVAL);
+                }
+            }
+            Step::RunHook { command } => {
+                let status = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .status()
+                    .map_err(|e| format!("Could not run '{}': {}", command, e))?;
+                if !status.success() {
+                    return Err(format!("'{}' failed ({})", command, status));
+                }
+            }
+            Step::Sleep { millis } => thread::sleep(Duration::from_millis(*millis)),
+            Step::SendKeys { keys } => crate::keystroke::send_keys(keys)?,
+            Step::TypeText { text } => crate::keystroke::type_text(text)?,
+            Step::Parallel { steps } => {
+                // Every branch runs to the end, even when another one fails
+                let errors: Vec<String> = thread::scope(|scope| {
+                    let branches: Vec<_> = steps
+                        .iter()
+                        .enumerate()
+                        .map(|(index, inner)| {
+                            scope.spawn(move || self.run_step(inner, row + 1 + index))
+                        })
+                        .collect();
+                    branches
+                        .into_iter()
+                        .filter_map(|branch| match branch.join() {
+                            Ok(result) => result.err(),
+                            Err(_) => Some("A parallel step panicked".to_string()),
+                        })
+                        .collect()
+                });
+                if !errors.is_empty() {
+                    return Err(format!(
+                        "{} of {} parallel steps failed: {}",
+                        errors.len(),
+                        steps.len(),
+                        errors.join("; ")
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run a recipe's steps in order, stopping at the first one that fails (unless
+/// its failure policy says otherwise).
+/// Programs are started in the game's prefix by `injector`, through `tracker` so they
+/// can be stopped later.
+/// `prefix` is the game's compatdata directory, for `exists` conditions.
+/// `on_progress` gets the state of every step (see `pending_progress`) whenever one changes.
+pub fn run(
+    steps: &[GuardedStep],
+    app_id: &str,
+    injector: &Injector,
+    prefix: Option<&Path>,
+    tracker: &ProcessTracker,
+    on_progress: &(dyn Fn(Vec<StepProgress>) + Sync),
+) -> Result<(), String> {
+    let runner = Runner {
+        app_id,
+        injector,
+        prefix,
+        tracker,
+        progress: Mutex::new(pending_progress(steps)),
+        on_progress,
+    };
+    let mut row = 0;
+    for guarded in steps {
+        runner.run_step(guarded, row)?;
+        row += row_count(guarded);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE: &str = "\
+run C:\\Tools\\trainer.exe
+[retry 3] wait_window 30 Cheat Engine
+parallel
+  if exists drive_c/mods/loader.dll: run C:\\mods\\loader.exe
+  [continue] unless running overlay.exe: hook notify-send started
+end
+sleep 500
+keys ctrl+shift+s
+type hello world";
+
+    #[test]
+    fn round_trips_through_text() {
+        let steps = parse(RECIPE).unwrap();
+        assert_eq!(steps.len(), 6);
+        assert_eq!(to_text(&steps), RECIPE);
+        assert_eq!(parse(&to_text(&steps)), Ok(steps));
+    }
+
+    #[test]
+    fn reads_guards_and_policies() {
+        let steps = parse(RECIPE).unwrap();
+        assert_eq!(steps[1].on_failure, FailurePolicy::Retry(3));
+        assert_eq!(
+            steps[1].step,
+            Step::WaitForWindow {
+                title: "Cheat Engine".to_string(),
+                timeout_secs: 30,
+            }
+        );
+        let Step::Parallel { steps: inner } = &steps[2].step else {
+            panic!("step 3 isn't a parallel block");
+        };
+        assert_eq!(
+            inner[0].when,
+            Some(Condition::FileExists {
+                path: "drive_c/mods/loader.dll".to_string(),
+            })
+        );
+        assert_eq!(inner[1].on_failure, FailurePolicy::Continue);
+        assert_eq!(
+            inner[1].unless,
+            Some(Condition::ProcessRunning {
+                name: "overlay.exe".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn skips_blank_lines_and_comments() {
+        let steps = parse("# warm up\n\n  sleep 10  \n").unwrap();
+        assert_eq!(to_text(&steps), "sleep 10");
+    }
+
+    #[test]
+    fn reports_the_failing_line() {
+        assert_eq!(
+            parse("sleep 1\nsleep soon"),
+            Err("Line 2: 'soon' isn't a number of milliseconds".to_string())
+        );
+        assert_eq!(
+            parse("parallel\nsleep 1"),
+            Err("Line 1: 'parallel' is missing its 'end'".to_string())
+        );
+        assert!(parse("parallel\nparallel\nend\nend").is_err());
+        assert!(parse("end").is_err());
+        assert!(parse("[retry x] sleep 1").is_err());
+        assert!(parse("if exists: run a.exe").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Screen recorders started as a companion of the game: gpu-screen-recorder keeping a
+//! replay buffer of the game's window, or OBS with its replay buffer running. The
+//! recorder is stopped again once the game ends.
+
+use crate::backend::Backend;
+use crate::process::ProcessTracker;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+// Seconds of gameplay the replay buffer keeps
+const REPLAY_SECONDS: u32 = 60;
+const FRAME_RATE: u32 = 60;
+// How often we check whether the game is still running
+const SESSION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Recorders need a moment to finish writing the file they have open
+const STOP_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Recorder {
+    #[default]
+    None,
+    #[serde(rename = "gpu-screen-recorder")]
+    GpuScreen,
+    Obs,
+}
+
+impl Recorder {
+    pub const ALL: [Recorder; 3] = [Recorder::None, Recorder::GpuScreen, Recorder::Obs];
+
+    /// Name shown in the recorder picker
+    pub fn label(self) -> &'static str {
+        match self {
+            Recorder::None => "None",
+            Recorder::GpuScreen => "gpu-screen-recorder",
+            Recorder::Obs => "OBS replay buffer",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Recorder {
+        Recorder::ALL
+            .into_iter()
+            .find(|recorder| recorder.label() == label)
+            .unwrap_or_default()
+    }
+
+    fn program(self) -> Option<&'static str> {
+        match self {
+            Recorder::None => None,
+            Recorder::GpuScreen => Some("gpu-screen-recorder"),
+            Recorder::Obs => Some("obs"),
+        }
+    }
+}
+
+/// Where replays are saved: a Protonic folder in the user's videos directory
+fn replay_dir(game_name: &str) -> PathBuf {
+    let videos = Command::new("xdg-user-dir")
+        .arg("VIDEOS")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env::var("HOME").unwrap_or_default()).join("Videos"));
+    let folder: String = game_name
+        .chars()
+        .map(|c| if c == '/' { '_' } else { c })
+        .collect();
+    videos.join("Protonic").join(folder)
+}
+
+fn command(recorder: Recorder, window: Option<&str>, game_name: &str) -> Option<Command> {
+    let program = recorder.program()?;
+    let mut command = Command::new(program);
+    match recorder {
+        Recorder::None => return None,
+        Recorder::GpuScreen => {
+            let dir = replay_dir(game_name);
+            if let Err(e) = fs::create_dir_all(&dir) {
+                println!("Warning: Could not create {}: {}", dir.display(), e);
+            }
+            // Without an X window ID (Wayland-native sessions) record the whole screen
+            command
+                .arg("-w")
+                .arg(window.unwrap_or("screen"))
+                .args(["-f", &FRAME_RATE.to_string()])
+                .args(["-r", &REPLAY_SECONDS.to_string()])
+                .args(["-a", "default_output", "-c", "mkv", "-o"])
+                .arg(dir);
+        }
+        // OBS records whatever the user's current scene shows
+        Recorder::Obs => {
+            command.args([
+                "--startreplaybuffer",
+                "--minimize-to-tray",
+                "--disable-shutdown-check",
+            ]);
+        }
+    }
+    Some(command)
+}
+
+/// Start the recorder for a game whose window is `window` (an X11 window ID, if known),
+/// and stop it when the game ends
+pub fn start(
+    recorder: Recorder,
+    window: Option<&str>,
+    app_id: &str,
+    game_name: &str,
+    backend: Backend,
+    tracker: &ProcessTracker,
+) {
+    let Some(mut command) = command(recorder, window, game_name) else {
+        return;
+    };
+    let program = recorder.program().unwrap_or_default();
+    if !crate::command_in_path(program) {
+        println!("Warning: {} is not installed, not recording", program);
+        return;
+    }
+    println!("Starting {}", recorder.label());
+    let pid = match tracker.spawn(&mut command, recorder.label(), app_id) {
+        Ok(pid) => pid,
+        Err(e) => {
+            println!("Warning: Could not start {}: {}", program, e);
+            return;
+        }
+    };
+
+    let app_id = app_id.to_string();
+    let tracker = tracker.clone();
+    thread::spawn(move || {
+        while backend.is_running(&app_id) {
+            thread::sleep(SESSION_POLL_INTERVAL);
+        }
+        if tracker.kill_pid(pid, STOP_GRACE) {
+            println!("Game {} exited, stopped the recorder", app_id);
+        }
+    });
+}
+
+/// Save the last minute of gameplay; gpu-screen-recorder writes its replay buffer on SIGUSR1
+pub fn save_replay(tracker: &ProcessTracker, app_id: &str) -> Result<(), String> {
+    let label = Recorder::GpuScreen.label();
+    if tracker.signal_named(app_id, label, libc::SIGUSR1) {
+        Ok(())
+    } else {
+        Err(format!("{} isn't running for this game", label))
+    }
+}
@@ -0,0 +1,149 @@
+//! Wine registry values a game's programs need in its prefix (DLL overrides, DPI),
+//! set with `reg add` through the game's backend before the programs start. Each value
+//! is set once per prefix: what was applied is logged in the prefix's compatdata
+//! folder, like winetricks keeps winetricks.log, so a new prefix gets them again.
+
+use crate::backend::Injector;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Named sets of tweaks that companion tools commonly need, as editor lines
+pub const PRESETS: [(&str, &str); 4] = [
+    (
+        "High DPI",
+        r"HKCU\Control Panel\Desktop\LogPixels = dword:144",
+    ),
+    (
+        "Native dinput8",
+        r"HKCU\Software\Wine\DllOverrides\dinput8 = native,builtin",
+    ),
+    (
+        "Native dxgi",
+        r"HKCU\Software\Wine\DllOverrides\dxgi = native,builtin",
+    ),
+    (
+        "Native winmm",
+        r"HKCU\Software\Wine\DllOverrides\winmm = native,builtin",
+    ),
+];
+
+const DWORD_PREFIX: &str = "dword:";
+// Kept next to the prefix's pfx folder
+const LOG_FILE: &str = "protonic-registry.log";
+
+/// A registry value, e.g. `HKCU\Software\Wine\DllOverrides\dinput8 = native,builtin`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Tweak {
+    // e.g. HKCU\Software\Wine\DllOverrides
+    pub key: String,
+    pub name: String,
+    // Set as a REG_DWORD when it was written as dword:<number>, else as a REG_SZ
+    pub data: String,
+}
+
+impl Tweak {
+    /// The editor line, also what the apply log records
+    pub fn to_line(&self) -> String {
+        format!(r"{}\{} = {}", self.key, self.name, self.data)
+    }
+
+    fn reg_type(&self) -> (&'static str, &str) {
+        match self.data.strip_prefix(DWORD_PREFIX) {
+            Some(number) => ("REG_DWORD", number),
+            None => ("REG_SZ", &self.data),
+        }
+    }
+}
+
+/// Parse the editor, one `key\name = data` per line
+pub fn parse(text: &str) -> Result<Vec<Tweak>, String> {
+    let mut tweaks = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |what: &str| format!("Line {}: {}", number + 1, what);
+        let (path, data) = line
+            .split_once('=')
+            .ok_or_else(|| error(r"expected key\name = value"))?;
+        let (key, name) = path
+            .trim()
+            .rsplit_once('\\')
+            .ok_or_else(|| error(r"expected a key like HKCU\Software\Wine before the name"))?;
+        let data = data.trim();
+        if let Some(number) = data.strip_prefix(DWORD_PREFIX)
+            && number.parse::<u32>().is_err()
+        {
+            return Err(error("dword: needs a number"));
+        }
+        tweaks.push(Tweak {
+            key: key.to_string(),
+            name: name.trim().to_string(),
+            data: data.to_string(),
+        });
+    }
+    Ok(tweaks)
+}
+
+pub fn to_text(tweaks: &[Tweak]) -> String {
+    tweaks
+        .iter()
+        .map(Tweak::to_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn log_path(prefix: &Path) -> PathBuf {
+    prefix.join(LOG_FILE)
+}
+
+/// Tweaks already applied to the prefix (compatdata/<appid>)
+fn applied(prefix: &Path) -> HashSet<String> {
+    fs::read_to_string(log_path(prefix))
+        .map(|log| log.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Set the tweaks the prefix doesn't have yet. Returns how many were set; without a
+/// prefix to keep the log in, all of them are set every time.
+pub fn apply(
+    tweaks: &[Tweak],
+    injector: &Injector,
+    app_id: &str,
+    prefix: Option<&Path>,
+) -> Result<usize, String> {
+    let done = prefix.map(applied).unwrap_or_default();
+    let mut count = 0;
+    for tweak in tweaks
+        .iter()
+        .filter(|tweak| !done.contains(&tweak.to_line()))
+    {
+        let (reg_type, data) = tweak.reg_type();
+        let output = injector
+            .run_command(app_id, "reg")
+            .args(["add", &tweak.key, "/v", &tweak.name])
+            .args(["/t", reg_type, "/d", data, "/f"])
+            .output()
+            .map_err(|e| format!("Failed to run reg: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "reg add failed for {} ({})",
+                tweak.to_line(),
+                output.status
+            ));
+        }
+        println!("Set registry value {}", tweak.to_line());
+        if let Some(prefix) = prefix {
+            let mut log = fs::read_to_string(log_path(prefix)).unwrap_or_default();
+            log.push_str(&tweak.to_line());
+            log.push('\n');
+            fs::write(log_path(prefix), log)
+                .map_err(|e| format!("Failed to write {}: {}", LOG_FILE, e))?;
+        }
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,124 @@
+//! Path remapping for configs that came from another computer (a synced config, or one
+//! copied over from a Steam Deck): `/home/deck/...` on the Deck is `/home/luna/...` here,
+//! and a library on the SD card is somewhere else on the desktop. Rules are applied when
+//! a game's programs are started, so the config keeps working on both computers.
+
+use crate::steam;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Paths starting with `from` are looked for under `to` instead
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PathRemap {
+    pub from: String,
+    pub to: String,
+    // Declined suggestions are kept switched off, so they aren't offered again
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// The rest of `path` after `prefix`, if `prefix` is a whole-component prefix of it
+fn strip<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = path.strip_prefix(prefix.trim_end_matches('/'))?;
+    (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+}
+
+/// `path` with the first matching enabled rule applied, or unchanged
+pub fn apply(rules: &[PathRemap], path: &str) -> String {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .find_map(|rule| {
+            strip(path, &rule.from).map(|rest| format!("{}{}", rule.to.trim_end_matches('/'), rest))
+        })
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Where a missing path's home directory or Steam library might be on this computer
+fn candidates(path: &str, home: &Path, libraries: &[PathBuf]) -> Vec<PathRemap> {
+    let components: Vec<&str> = path.split('/').collect();
+    let mut found = Vec::new();
+
+    // /home/<user>/... (or /var/home/<user>/... on image-based distros)
+    let user_at = match components.as_slice() {
+        ["", "home", _, ..] => Some(3),
+        ["", "var", "home", _, ..] => Some(4),
+        _ => None,
+    };
+    if let Some(end) = user_at {
+        let from = components[..end].join("/");
+        if Path::new(&from) != home {
+            found.push(PathRemap {
+                from,
+                to: home.to_string_lossy().into_owned(),
+                enabled: true,
+            });
+        }
+    }
+
+    // <library>/steamapps/...
+    if let Some(index) = components.iter().position(|c| *c == "steamapps")
+        && index > 0
+    {
+        let from = components[..index].join("/");
+        for library in libraries
+            .iter()
+            .filter(|library| **library != Path::new(&from))
+        {
+            found.push(PathRemap {
+                from: from.clone(),
+                to: library.to_string_lossy().into_owned(),
+                enabled: true,
+            });
+        }
+    }
+    found
+}
+
+/// Rules that would make missing paths in the config exist again, with how many paths
+/// each one fixes, most first. Paths an existing rule (on or off) already covers are
+/// left alone.
+pub fn suggest(paths: &[String], rules: &[PathRemap]) -> Vec<(PathRemap, usize)> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let libraries: Vec<PathBuf> = steam::locate()
+        .ok()
+        .and_then(|steam_dir| steam_dir.library_paths().ok())
+        .unwrap_or_default();
+
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    for path in paths {
+        if !path.starts_with('/')
+            || Path::new(path).exists()
+            || rules.iter().any(|rule| strip(path, &rule.from).is_some())
+        {
+            continue;
+        }
+        let fix = candidates(path, &home, &libraries)
+            .into_iter()
+            .find(|rule| Path::new(&apply(std::slice::from_ref(rule), path)).exists());
+        if let Some(rule) = fix {
+            *counts.entry((rule.from, rule.to)).or_default() += 1;
+        }
+    }
+
+    let mut suggestions: Vec<(PathRemap, usize)> = counts
+        .into_iter()
+        .map(|((from, to), count)| {
+            let rule = PathRemap {
+                from,
+                to,
+                enabled: true,
+            };
+            (rule, count)
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.from.cmp(&b.0.from)));
+    suggestions
+}
@@ -0,0 +1,210 @@
+//! Export of a game's launch setup as a standalone bash script, for machines without Protonic.
+//! The script starts the game through Steam, waits for its protonhax session and then
+//! runs the same programs (or recipe) Protonic would on the hotkey.
+
+use crate::recipe::{Condition, FailurePolicy, GuardedStep, Step};
+use crate::shortcuts;
+use std::path::Path;
+
+// Shell helpers, only included when the recipe needs them
+const WAIT_WINDOW_FN: &str = r#"wait_window() {
+    local deadline=$((SECONDS + $2))
+    until xdotool search --name "$1" >/dev/null 2>&1; do
+        if (( SECONDS >= deadline )); then
+            echo "No window called '$1' after $2 s" >&2
+            return 1
+        fi
+        sleep 0.25
+    done
+}"#;
+const RETRY_FN: &str = r#"retry() {
+    local times=$1 attempt=0
+    shift
+    until "$@"; do
+        attempt=$((attempt + 1))
+        if (( attempt > times )); then
+            return 1
+        fi
+        echo "Step failed, retrying ($attempt of $times)" >&2
+        sleep 1
+    done
+}"#;
+
+/// Quote a word for bash
+fn quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+/// The step as a simple command; None for steps that need more than one line
+fn step_command(step: &Step) -> Option<String> {
+    let command = match step {
+        Step::RunExe { .. } | Step::Parallel { .. } => return None,
+        Step::WaitForWindow {
+            title,
+            timeout_secs,
+        } => format!("wait_window {} {}", quote(title), timeout_secs),
+        Step::RunHook { command } => format!("sh -c {}", quote(command)),
+        Step::Sleep { millis } => format!("sleep {}.{:03}", millis / 1000, millis % 1000),
+        Step::SendKeys { keys } => format!("xdotool key --clearmodifiers {}", quote(keys)),
+        Step::TypeText { text } => format!("xdotool type --clearmodifiers -- {}", quote(text)),
+    };
+    Some(command)
+}
+
+fn condition_test(condition: &Condition) -> String {
+    match condition {
+        Condition::FileExists { path } => format!(
+            "[ -e \"$PREFIX/pfx/\"{} ]",
+            quote(path.trim_start_matches('/'))
+        ),
+        Condition::ProcessRunning { name } => {
+            format!("pgrep -if -- {} >/dev/null", quote(name))
+        }
+    }
+}
+
+fn push_step(lines: &mut Vec<String>, guarded: &GuardedStep, indent: &str) {
+    lines.push(format!("{}# {}", indent, guarded.to_line()));
+    let (test, inner_indent) = match (&guarded.when, &guarded.unless) {
+        (Some(condition), _) => (Some(condition_test(condition)), format!("{}    ", indent)),
+        (None, Some(condition)) => (
+            Some(format!("! {}", condition_test(condition))),
+            format!("{}    ", indent),
+        ),
+        (None, None) => (None, indent.to_string()),
+    };
+    if let Some(test) = &test {
+        lines.push(format!("{}if {}; then", indent, test));
+    }
+
+    match &guarded.step {
+        Step::RunExe { path } => lines.push(format!(
+            "{}protonhax run \"$APP_ID\" {} &",
+            inner_indent,
+            quote(path)
+        )),
+        Step::Parallel { steps } => {
+            lines.push(format!("{}pids=()", inner_indent));
+            for inner in steps {
+                lines.push(format!("{}{{", inner_indent));
+                push_step(lines, inner, &format!("{}    ", inner_indent));
+                lines.push(format!("{}}} &", inner_indent));
+                lines.push(format!("{}pids+=($!)", inner_indent));
+            }
+            lines.push(format!(
+                "{}for pid in \"${{pids[@]}}\"; do wait \"$pid\"; done",
+                inner_indent
+            ));
+        }
+        step => {
+            let command = step_command(step).unwrap_or_default();
+            lines.push(match guarded.on_failure {
+                FailurePolicy::Abort => format!("{}{}", inner_indent, command),
+                FailurePolicy::Continue => format!(
+                    "{}{} || echo \"Step failed, continuing\" >&2",
+                    inner_indent, command
+                ),
+                FailurePolicy::Retry(times) => {
+                    format!("{}retry {} {}", inner_indent, times, command)
+                }
+            });
+        }
+    }
+
+    if test.is_some() {
+        lines.push(format!("{}fi", indent));
+    }
+}
+
+/// Build the script. `programs` are the game's programs (already resolved to real paths);
+/// a non-empty recipe replaces them, like it does in Protonic.
+pub fn launch_script(
+    app_id: &str,
+    game_name: &str,
+    programs: &[crate::ExeEntry],
+    recipe: &[GuardedStep],
+    template: &str,
+    prefix: Option<&Path>,
+) -> String {
+    let mut body = Vec::new();
+    if recipe.is_empty() {
+        for (index, program) in programs.iter().filter(|p| !p.path.is_empty()).enumerate() {
+            if index > 0 {
+                body.push("sleep 0.5".to_string());
+            }
+            // VAR=value in front of the command (or its exec) only applies to it
+            let env: String = program
+                .env
+                .iter()
+                .filter(|var| var.is_valid())
+                .map(|var| format!("{}={} ", var.name, quote(&var.value)))
+                .collect();
+            let mut command = format!("protonhax run \"$APP_ID\" {}", quote(&program.path));
+            for arg in &program.args {
+                command.push(' ');
+                command.push_str(&quote(arg));
+            }
+            body.push(match &program.working_dir {
+                Some(dir) => format!(
+                    "(cd {} && {}exec {}) &",
+                    quote(&dir.to_string_lossy()),
+                    env,
+                    command
+                ),
+                None => format!("{}{} &", env, command),
+            });
+        }
+    } else {
+        for guarded in recipe {
+            push_step(&mut body, guarded, "");
+        }
+    }
+    let body = body.join("\n");
+
+    let mut lines = vec![
+        "#!/usr/bin/env bash".to_string(),
+        format!(
+            "# Launch setup for {} ({}), exported from Protonic.",
+            game_name, app_id
+        ),
+        "# The game's launch options in Steam need to contain:".to_string(),
+        format!("#   {}", template),
+        "set -euo pipefail".to_string(),
+        String::new(),
+        format!("APP_ID={}", app_id),
+    ];
+    if let Some(prefix) = prefix {
+        lines.push(format!("PREFIX={}", quote(&prefix.to_string_lossy())));
+    }
+    lines.push(
+        "SESSION_DIR=\"${XDG_RUNTIME_DIR:-/run/user/$(id -u)}/protonhax/$APP_ID\"".to_string(),
+    );
+    lines.push(String::new());
+    if body.contains("wait_window ") {
+        lines.push(WAIT_WINDOW_FN.to_string());
+        lines.push(String::new());
+    }
+    if body.contains("retry ") {
+        lines.push(RETRY_FN.to_string());
+        lines.push(String::new());
+    }
+    // Non-Steam games are started by their 64-bit game ID
+    let run_url = match shortcuts::run_url(app_id) {
+        Some(url) => quote(&url),
+        None => "\"steam://run/$APP_ID\"".to_string(),
+    };
+    lines.extend([
+        format!("echo {}", quote(&format!("Launching {}...", game_name))),
+        // In a subshell so the final `wait` doesn't wait for Steam itself
+        format!("(steam {} >/dev/null 2>&1 &)", run_url),
+        "until [ -d \"$SESSION_DIR\" ]; do sleep 1; done".to_string(),
+        "read -r -p \"Press Enter once the game is at its menu... \"".to_string(),
+        String::new(),
+        body,
+        String::new(),
+        "# Stay around until the programs exit, so Ctrl+C stops them".to_string(),
+        "wait".to_string(),
+        String::new(),
+    ]);
+    lines.join("\n")
+}
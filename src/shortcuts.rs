@@ -0,0 +1,384 @@
+//! Non-Steam games added as shortcuts (GOG, Epic, itch... games run through Proton),
+//! from the binary `userdata/<account>/config/shortcuts.vdf`. Their launch options live
+//! in that file rather than in localconfig.vdf, so it has to be written back as well.
+
+use crate::{backup, steam};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Binary KeyValues type tags used in shortcuts.vdf
+const TYPE_SECTION: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT32: u8 = 0x02;
+const TYPE_FLOAT32: u8 = 0x03;
+const TYPE_UINT64: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+
+// Steam sets the top bit on the IDs it generates for shortcuts, which keeps them
+// apart from real app IDs
+const SHORTCUT_ID_BIT: u32 = 0x8000_0000;
+
+/// A value in the file, with its type kept so it's written back exactly as read. Keys
+/// and strings stay bytes: names and paths aren't always UTF-8, and only the entry being
+/// changed may differ when the file is written back.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Section(Vec<(Vec<u8>, Value)>),
+    String(Vec<u8>),
+    Int32(u32),
+    Float32([u8; 4]),
+    UInt64(u64),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Section(pairs) => pairs
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key.as_bytes()))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// A string value decoded for display
+    fn string(&self, key: &str) -> String {
+        match self.get(key) {
+            Some(Value::String(text)) => String::from_utf8_lossy(text).into_owned(),
+            _ => String::new(),
+        }
+    }
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| format!("Unexpected end of shortcuts.vdf at offset {}", self.pos))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn cstring(&mut self) -> Result<Vec<u8>, String> {
+        let rest = self.data.get(self.pos..).unwrap_or_default();
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| "Unterminated string in shortcuts.vdf".to_string())?;
+        let text = rest[..len].to_vec();
+        self.pos += len + 1;
+        Ok(text)
+    }
+
+    fn pairs(&mut self) -> Result<Vec<(Vec<u8>, Value)>, String> {
+        let mut pairs = Vec::new();
+        loop {
+            let tag = self.take(1)?[0];
+            if tag == TYPE_END {
+                return Ok(pairs);
+            }
+            let key = self.cstring()?;
+            let value = match tag {
+                TYPE_SECTION => Value::Section(self.pairs()?),
+                TYPE_STRING => Value::String(self.cstring()?),
+                TYPE_INT32 => Value::Int32(u32::from_le_bytes(self.take(4)?.try_into().unwrap())),
+                TYPE_FLOAT32 => Value::Float32(self.take(4)?.try_into().unwrap()),
+                TYPE_UINT64 => Value::UInt64(u64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+                other => return Err(format!("Unknown value type {:#x} in shortcuts.vdf", other)),
+            };
+            pairs.push((key, value));
+        }
+    }
+}
+
+fn parse(data: &[u8]) -> Result<Value, String> {
+    let mut parser = Parser { data, pos: 0 };
+    let root = parser.pairs()?;
+    Ok(Value::Section(root))
+}
+
+fn write_pairs(out: &mut Vec<u8>, pairs: &[(Vec<u8>, Value)]) {
+    for (key, value) in pairs {
+        let tag = match value {
+            Value::Section(_) => TYPE_SECTION,
+            Value::String(_) => TYPE_STRING,
+            Value::Int32(_) => TYPE_INT32,
+            Value::Float32(_) => TYPE_FLOAT32,
+            Value::UInt64(_) => TYPE_UINT64,
+        };
+        out.push(tag);
+        out.extend_from_slice(key);
+        out.push(0);
+        match value {
+            Value::Section(children) => write_pairs(out, children),
+            Value::String(text) => {
+                out.extend_from_slice(text);
+                out.push(0);
+            }
+            Value::Int32(number) => out.extend_from_slice(&number.to_le_bytes()),
+            Value::Float32(bytes) => out.extend_from_slice(bytes),
+            Value::UInt64(number) => out.extend_from_slice(&number.to_le_bytes()),
+        }
+    }
+    out.push(TYPE_END);
+}
+
+fn serialize(root: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    if let Value::Section(pairs) = root {
+        write_pairs(&mut out, pairs);
+    }
+    out
+}
+
+/// A non-Steam game
+#[derive(Debug, Clone)]
+pub struct Shortcut {
+    pub app_id: u32,
+    pub name: String,
+    pub exe: String,
+    // Steam quotes both paths
+    pub start_dir: PathBuf,
+    pub launch_options: String,
+}
+
+/// Whether an app ID belongs to a non-Steam shortcut
+pub fn is_shortcut(app_id: &str) -> bool {
+    app_id
+        .parse::<u32>()
+        .is_ok_and(|id| id & SHORTCUT_ID_BIT != 0)
+}
+
+/// What to open to start a shortcut: steam://rungameid takes the 64-bit game ID.
+/// None for Steam games.
+pub fn run_url(app_id: &str) -> Option<String> {
+    if !is_shortcut(app_id) {
+        return None;
+    }
+    let id: u64 = app_id.parse().ok()?;
+    Some(format!("steam://rungameid/{}", (id << 32) | 0x0200_0000))
+}
+
+/// Artwork picked for a shortcut in Steam (the tall capsule, else the wide one)
+pub fn grid_image(app_id: &str) -> Option<PathBuf> {
+    let grid = steam::userdata_path()?.join("config").join("grid");
+    ["p.png", "p.jpg", ".png", ".jpg"]
+        .iter()
+        .map(|suffix| grid.join(format!("{}{}", app_id, suffix)))
+        .find(|path| path.is_file())
+}
+
+/// shortcuts.vdf of the Steam account in use
+fn shortcuts_path() -> Option<PathBuf> {
+    let path = steam::userdata_path()?.join("config").join("shortcuts.vdf");
+    path.exists().then_some(path)
+}
+
+fn read(path: &Path) -> Result<Value, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read shortcuts.vdf: {}", e))?;
+    parse(&data).map_err(|e| format!("Could not parse shortcuts.vdf: {}", e))
+}
+
+/// Every non-Steam game of the account in use
+pub fn load() -> Result<Vec<Shortcut>, String> {
+    let Some(path) = shortcuts_path() else {
+        return Ok(Vec::new());
+    };
+    let root = read(&path)?;
+    let Some(Value::Section(entries)) = root.get("shortcuts") else {
+        return Ok(Vec::new());
+    };
+    Ok(entries
+        .iter()
+        .filter_map(|(_, entry)| {
+            let Some(Value::Int32(app_id)) = entry.get("appid") else {
+                return None;
+            };
+            Some(Shortcut {
+                app_id: *app_id,
+                name: entry.string("AppName"),
+                exe: entry.string("Exe"),
+                start_dir: PathBuf::from(entry.string("StartDir").trim_matches('"')),
+                launch_options: entry.string("LaunchOptions"),
+            })
+        })
+        .collect())
+}
+
+/// A shortcut's launch options, None if it has none
+pub fn launch_options(app_id: &str) -> Option<String> {
+    load()
+        .ok()?
+        .into_iter()
+        .find(|shortcut| shortcut.app_id.to_string() == app_id)
+        .map(|shortcut| shortcut.launch_options)
+        .filter(|options| !options.is_empty())
+}
+
+/// Replace the launch options of one shortcut in the parsed file, returning the old ones
+fn replace_launch_options(
+    root: &mut Value,
+    app_id: &str,
+    value: &str,
+) -> Result<Option<String>, String> {
+    let Value::Section(top) = root else {
+        return Err("shortcuts.vdf is empty".to_string());
+    };
+    let id = app_id
+        .parse()
+        .map_err(|_| format!("{} is not a non-Steam game", app_id))?;
+    let entry = top
+        .iter_mut()
+        .filter(|(key, _)| key.eq_ignore_ascii_case(b"shortcuts"))
+        .find_map(|(_, list)| match list {
+            Value::Section(entries) => entries
+                .iter_mut()
+                .map(|(_, entry)| entry)
+                .find(|entry| entry.get("appid") == Some(&Value::Int32(id))),
+            _ => None,
+        })
+        .ok_or_else(|| format!("Non-Steam game {} not found in shortcuts.vdf", app_id))?;
+    let Value::Section(fields) = entry else {
+        return Err(format!(
+            "Non-Steam game {} is damaged in shortcuts.vdf",
+            app_id
+        ));
+    };
+    let old_value = match fields
+        .iter_mut()
+        .find(|(key, _)| key.eq_ignore_ascii_case(b"LaunchOptions"))
+    {
+        Some((_, existing)) => {
+            let old = std::mem::replace(existing, Value::String(value.as_bytes().to_vec()));
+            match old {
+                Value::String(text) if !text.is_empty() => {
+                    Some(String::from_utf8_lossy(&text).into_owned())
+                }
+                _ => None,
+            }
+        }
+        None => {
+            fields.push((
+                b"LaunchOptions".to_vec(),
+                Value::String(value.as_bytes().to_vec()),
+            ));
+            None
+        }
+    };
+    Ok(old_value)
+}
+
+/// Replace a shortcut's launch options, returning the old ones. Steam only reads
+/// shortcuts.vdf when it starts (and rewrites it from memory), so it has to be closed.
+pub fn set_launch_options(app_id: &str, value: &str) -> Result<Option<String>, String> {
+    if steam::is_running() {
+        return Err(
+            "Close Steam first, it would overwrite a non-Steam game's launch options".to_string(),
+        );
+    }
+    let path = shortcuts_path().ok_or_else(|| "Could not find shortcuts.vdf".to_string())?;
+    let mut root = read(&path)?;
+    let old_value = replace_launch_options(&mut root, app_id, value)?;
+
+    // Same safety net as for localconfig.vdf: it must read back as what we meant
+    let data = serialize(&root);
+    if parse(&data).as_ref() != Ok(&root) {
+        return Err("Refusing to write shortcuts.vdf, it would not parse back".to_string());
+    }
+    let snapshot = backup::snapshot_shortcuts(&path)?;
+    let written = fs::write(&path, &data)
+        .map_err(|e| e.to_string())
+        .and_then(|()| read(&path));
+    if written.as_ref() != Ok(&root) {
+        backup::restore_snapshot(&snapshot, &path)?;
+        return Err(format!(
+            "Writing shortcuts.vdf failed ({}), restored the previous version",
+            written
+                .err()
+                .unwrap_or_else(|| "contents differ".to_string())
+        ));
+    }
+    Ok(old_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A shortcuts.vdf with one game whose name isn't UTF-8 (Latin-1 "Jeu é")
+    fn sample(launch_options: &[u8]) -> Vec<u8> {
+        let mut data = vec![TYPE_SECTION];
+        data.extend_from_slice(b"shortcuts\0");
+        data.push(TYPE_SECTION);
+        data.extend_from_slice(b"0\0");
+        data.push(TYPE_INT32);
+        data.extend_from_slice(b"appid\0");
+        data.extend_from_slice(&0x8000_1234u32.to_le_bytes());
+        data.push(TYPE_STRING);
+        data.extend_from_slice(b"AppName\0Jeu \xe9\0");
+        data.push(TYPE_STRING);
+        data.extend_from_slice(b"Exe\0\"/games/jeu.exe\"\0");
+        data.push(TYPE_STRING);
+        data.extend_from_slice(b"LaunchOptions\0");
+        data.extend_from_slice(launch_options);
+        data.push(0);
+        data.push(TYPE_UINT64);
+        data.extend_from_slice(b"LastPlayTime\0");
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&[TYPE_END, TYPE_END, TYPE_END]);
+        data
+    }
+
+    #[test]
+    fn round_trips_exactly() {
+        let data = sample(b"-dx11");
+        let root = parse(&data).unwrap();
+        assert_eq!(serialize(&root), data);
+    }
+
+    #[test]
+    fn changing_launch_options_keeps_other_bytes() {
+        let mut root = parse(&sample(b"-dx11")).unwrap();
+        let id = 0x8000_1234u32.to_string();
+        let old = replace_launch_options(&mut root, &id, "%command% -vulkan").unwrap();
+        assert_eq!(old.as_deref(), Some("-dx11"));
+        assert_eq!(serialize(&root), sample(b"%command% -vulkan"));
+    }
+
+    #[test]
+    fn names_are_decoded_for_display_only() {
+        let root = parse(&sample(b"")).unwrap();
+        let entry = root
+            .get("shortcuts")
+            .and_then(|list| list.get("0"))
+            .unwrap();
+        assert_eq!(entry.string("AppName"), "Jeu \u{fffd}");
+        assert_eq!(
+            entry.get("AppName"),
+            Some(&Value::String(b"Jeu \xe9".to_vec()))
+        );
+    }
+
+    #[test]
+    fn adds_missing_launch_options() {
+        let mut root = parse(&sample(b"")).unwrap();
+        let id = 0x8000_1234u32.to_string();
+        assert_eq!(
+            replace_launch_options(&mut root, &id, "-fullscreen"),
+            Ok(None)
+        );
+        assert!(replace_launch_options(&mut root, "123", "").is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let data = sample(b"");
+        assert!(parse(&data[..data.len() - 12]).is_err());
+    }
+}
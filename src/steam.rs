@@ -0,0 +1,306 @@
+//! Finding Steam: native installs, the Flatpak (com.valvesoftware.Steam) and the Snap,
+//! which steamlocate doesn't look for, and starting games through the one in use.
+//! Also which of the accounts that have logged in to it we're working with, and whether
+//! the client is running (it rewrites its config files when it exits).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use steamlocate::SteamDir;
+
+use crate::vdf;
+
+const FLATPAK_ID: &str = "com.valvesoftware.Steam";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packaging {
+    Native,
+    Flatpak,
+    Snap,
+}
+
+#[derive(Debug, Clone)]
+pub struct Installation {
+    pub packaging: Packaging,
+    pub path: PathBuf,
+}
+
+impl Installation {
+    /// Name shown in the Steam picker, e.g. "Flatpak: ~/.var/app/com.valvesoftware.Steam/..."
+    pub fn label(&self) -> String {
+        let kind = match self.packaging {
+            Packaging::Native => "Native",
+            Packaging::Flatpak => "Flatpak",
+            Packaging::Snap => "Snap",
+        };
+        let home = dirs::home_dir().unwrap_or_default();
+        let path = match self.path.strip_prefix(&home) {
+            Ok(relative) => format!("~/{}", relative.display()),
+            Err(_) => self.path.display().to_string(),
+        };
+        format!("{}: {}", kind, path)
+    }
+
+    /// `steam <url>`, run the way this installation has to be started
+    pub fn open_command(&self, url: &str) -> Command {
+        let mut command = match self.packaging {
+            Packaging::Native => Command::new("steam"),
+            Packaging::Flatpak => {
+                let mut command = Command::new("flatpak");
+                command.arg("run").arg(FLATPAK_ID);
+                command
+            }
+            Packaging::Snap => {
+                let mut command = Command::new("snap");
+                command.arg("run").arg("steam");
+                command
+            }
+        };
+        // Steam keeps running after Protonic exits
+        command.arg(url);
+        crate::logs::quiet(&mut command);
+        command
+    }
+}
+
+// Where each kind of Steam keeps its files, relative to the home directory
+const CANDIDATES: [(Packaging, &str); 5] = [
+    (Packaging::Native, ".steam/steam"),
+    (Packaging::Native, ".local/share/Steam"),
+    (
+        Packaging::Flatpak,
+        ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+    ),
+    (
+        Packaging::Flatpak,
+        ".var/app/com.valvesoftware.Steam/data/Steam",
+    ),
+    (Packaging::Snap, "snap/steam/common/.local/share/Steam"),
+];
+
+/// Every Steam installation in the home directory. ~/.steam/steam is usually a
+/// symlink to ~/.local/share/Steam, so each one is only listed once.
+pub fn installations() -> Vec<Installation> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let mut found: Vec<(PathBuf, Installation)> = Vec::new();
+    for (packaging, relative) in CANDIDATES {
+        let path = home.join(relative);
+        if !path.join("steamapps").is_dir() {
+            continue;
+        }
+        let real = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !found.iter().any(|(seen, _)| *seen == real) {
+            found.push((real, Installation { packaging, path }));
+        }
+    }
+    found
+        .into_iter()
+        .map(|(_, installation)| installation)
+        .collect()
+}
+
+// Installation picked in the settings, set once at startup
+static PREFERRED: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use the installation at `path` from now on (if it's still there). Takes effect once.
+pub fn prefer(path: &Path) {
+    let _ = PREFERRED.set(path.to_path_buf());
+}
+
+/// The installation in use: the one picked in the settings, else the first one found
+pub fn current() -> Option<Installation> {
+    let all = installations();
+    PREFERRED
+        .get()
+        .and_then(|preferred| all.iter().find(|i| i.path == *preferred).cloned())
+        .or_else(|| all.into_iter().next())
+}
+
+/// Look for the Steam directory in use, falling back to steamlocate for layouts we
+/// don't know
+fn find() -> Result<SteamDir, String> {
+    match current() {
+        Some(installation) => SteamDir::from_dir(&installation.path).map_err(|e| e.to_string()),
+        None => SteamDir::locate().map_err(|e| e.to_string()),
+    }
+}
+
+/// The Steam directory in use. It's only searched for once; it's asked for on every
+/// game selection.
+pub fn locate() -> Result<SteamDir, String> {
+    match path() {
+        Some(path) => SteamDir::from_dir(&path).map_err(|e| e.to_string()),
+        // Search again for the reason
+        None => find(),
+    }
+}
+
+// Path of the installation in use, looked up the first time it's needed
+static PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Path of the Steam directory in use
+pub fn path() -> Option<PathBuf> {
+    PATH.get_or_init(|| find().ok().map(|steam_dir| steam_dir.path().to_path_buf()))
+        .clone()
+}
+
+/// Open a steam:// URL (e.g. steam://run/<app id>) with the installation in use
+pub fn open_command(url: &str) -> Command {
+    current()
+        .unwrap_or(Installation {
+            packaging: Packaging::Native,
+            path: PathBuf::new(),
+        })
+        .open_command(url)
+}
+
+// The client's process name, inside the Flatpak and Snap sandboxes too
+const CLIENT_PROCESS: &str = "steam";
+// Steam writes its config files before exiting, which can take a while
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Whether the Steam client is running. It writes its pid to ~/.steam/steam.pid, but
+/// doesn't remove the file when it exits, so the pid is checked against the process.
+pub fn is_running() -> bool {
+    let from_pid_file = dirs::home_dir()
+        .and_then(|home| fs::read_to_string(home.join(".steam").join("steam.pid")).ok())
+        .and_then(|pid| fs::read_to_string(format!("/proc/{}/comm", pid.trim())).ok())
+        .is_some_and(|comm| comm.trim() == CLIENT_PROCESS);
+    from_pid_file || crate::recipe::process_running(CLIENT_PROCESS)
+}
+
+/// Ask Steam to exit and wait until it has
+pub fn shutdown() -> Result<(), String> {
+    let child = open_command("steam://exit")
+        .spawn()
+        .map_err(|e| format!("Could not ask Steam to exit: {}", e))?;
+    crate::process::reap_in_background(child);
+    let started = Instant::now();
+    while is_running() {
+        if started.elapsed() >= SHUTDOWN_TIMEOUT {
+            return Err("Steam didn't exit within a minute".to_string());
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+    Ok(())
+}
+
+/// Start Steam again after shutdown()
+pub fn start() {
+    match open_command("steam://open/main").spawn() {
+        Ok(child) => crate::process::reap_in_background(child),
+        Err(e) => println!("Warning: Could not start Steam: {}", e),
+    }
+}
+
+// SteamID64 of account ID 0; userdata directories are named after the account ID
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+/// An account that has logged in to Steam on this machine, from config/loginusers.vdf
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub steam_id64: u64,
+    pub account_name: String,
+    pub persona_name: String,
+    // The account Steam logged in with last
+    pub most_recent: bool,
+}
+
+impl Account {
+    /// Name shown in the account picker, e.g. "Luna (lunabaloona)"
+    pub fn label(&self) -> String {
+        if self.persona_name.is_empty() || self.persona_name == self.account_name {
+            self.account_name.clone()
+        } else {
+            format!("{} ({})", self.persona_name, self.account_name)
+        }
+    }
+
+    /// The account's directory in Steam's userdata
+    fn userdata(&self, steam_dir: &Path) -> PathBuf {
+        steam_dir
+            .join("userdata")
+            .join((self.steam_id64 - STEAM_ID64_BASE).to_string())
+    }
+}
+
+/// Accounts listed in `<steam>/config/loginusers.vdf`, in the order Steam keeps them
+pub fn accounts(steam_dir: &Path) -> Result<Vec<Account>, String> {
+    let path = steam_dir.join("config").join("loginusers.vdf");
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let root = vdf::parse(&text)?;
+    let Some(vdf::Node::Section(users)) = root.get("users") else {
+        return Err(format!("No users in {}", path.display()));
+    };
+    let value = |user: &vdf::Node, key: &str| match user.get(key) {
+        Some(vdf::Node::Value(value)) => value.clone(),
+        _ => String::new(),
+    };
+    Ok(users
+        .iter()
+        .filter_map(|(id, user)| {
+            let steam_id64 = id.parse().ok().filter(|id| *id > STEAM_ID64_BASE)?;
+            Some(Account {
+                steam_id64,
+                account_name: value(user, "AccountName"),
+                persona_name: value(user, "PersonaName"),
+                most_recent: value(user, "MostRecent") == "1",
+            })
+        })
+        .collect())
+}
+
+// Account picked in the settings, None to follow whoever logged in last
+static ACCOUNT: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Work with the account with this SteamID64 from now on, None for the most recent one
+pub fn select_account(steam_id64: Option<u64>) {
+    *ACCOUNT.lock().unwrap() = steam_id64;
+}
+
+/// Userdata directory of the account in use: the one picked in the settings, else the
+/// one Steam logged in with last, else the first one there is
+pub fn userdata_path() -> Option<PathBuf> {
+    let steam_dir = locate().ok()?;
+    let steam_dir = steam_dir.path();
+    // Older or freshly installed Steams may not have loginusers.vdf yet
+    let accounts = accounts(steam_dir).unwrap_or_default();
+    let selected = *ACCOUNT.lock().unwrap();
+    let account = match selected {
+        Some(id) => accounts.iter().find(|account| account.steam_id64 == id),
+        None => accounts.iter().find(|account| account.most_recent),
+    };
+    if let Some(path) = account
+        .map(|account| account.userdata(steam_dir))
+        .filter(|path| path.is_dir())
+    {
+        return Some(path);
+    }
+    if let Some(id) = selected {
+        println!("Warning: Steam has no userdata for account {}", id);
+    }
+
+    fs::read_dir(steam_dir.join("userdata"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// SteamID64 of the account whose userdata we're using
+pub fn current_steam_id64() -> Option<u64> {
+    let userdata = userdata_path()?;
+    let account_id: u64 = userdata.file_name()?.to_str()?.parse().ok()?;
+    Some(STEAM_ID64_BASE + account_id)
+}
@@ -0,0 +1,216 @@
+//! Optional store metadata (description, capsule art) for the details panel.
+//! Local data always wins: Steam's own library cache is used for art, and anything
+//! fetched from the Steam Web API is cached on disk so each game is only fetched once.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const GET_ITEMS_URL: &str = "https://api.steampowered.com/IStoreBrowseService/GetItems/v1/";
+const ASSET_BASE_URL: &str = "https://shared.cloudflare.steamstatic.com/store_item_assets/";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Capsules are a few hundred KB; anything much bigger isn't what we asked for
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// What the details panel shows for a game
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StoreDetails {
+    pub description: String,
+    pub capsule_path: Option<PathBuf>,
+}
+
+/// Where fetched store data is kept
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("protonic").join("store"))
+}
+
+/// Capsule art Steam has already downloaded for its library view.
+/// Older clients use flat `<id>_library_600x900.jpg` names, newer ones a folder per app.
+pub fn local_capsule(steam_path: &Path, app_id: &str) -> Option<PathBuf> {
+    let cache = steam_path.join("appcache").join("librarycache");
+    [
+        cache.join(app_id).join("library_600x900.jpg"),
+        cache.join(format!("{}_library_600x900.jpg", app_id)),
+        cache.join(app_id).join("header.jpg"),
+        cache.join(format!("{}_header.jpg", app_id)),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+}
+
+/// Wide header art for the library grid, or the capsule when there's none
+pub fn local_header(steam_path: &Path, app_id: &str) -> Option<PathBuf> {
+    let cache = steam_path.join("appcache").join("librarycache");
+    [
+        cache.join(app_id).join("header.jpg"),
+        cache.join(format!("{}_header.jpg", app_id)),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .or_else(|| local_capsule(steam_path, app_id))
+}
+
+/// The small square icon Steam shows next to a game in its library list.
+/// Older clients keep it as `<id>_icon.jpg`; newer ones store it in the app's folder
+/// under its content hash, next to the named artwork.
+pub fn local_icon(steam_path: &Path, app_id: &str) -> Option<PathBuf> {
+    let cache = steam_path.join("appcache").join("librarycache");
+    let flat = cache.join(format!("{}_icon.jpg", app_id));
+    if flat.is_file() {
+        return Some(flat);
+    }
+    fs::read_dir(cache.join(app_id))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            path.extension().is_some_and(|ext| ext == "jpg")
+                && stem.len() == 40
+                && stem.chars().all(|c| c.is_ascii_hexdigit())
+        })
+}
+
+/// Details from our own cache, without touching the network
+pub fn cached_details(app_id: &str) -> Option<StoreDetails> {
+    let path = cache_dir()?.join(format!("{}.json", app_id));
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[derive(Serialize)]
+struct ItemsRequest<'a> {
+    ids: [ItemId; 1],
+    context: Context<'a>,
+    data_request: DataRequest,
+}
+
+#[derive(Serialize)]
+struct ItemId {
+    appid: u32,
+}
+
+#[derive(Serialize)]
+struct Context<'a> {
+    language: &'a str,
+    country_code: &'a str,
+}
+
+#[derive(Serialize)]
+struct DataRequest {
+    include_basic_info: bool,
+    include_assets: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ItemsResponse {
+    response: ItemsBody,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ItemsBody {
+    store_items: Vec<StoreItem>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct StoreItem {
+    basic_info: BasicInfo,
+    assets: Assets,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct BasicInfo {
+    short_description: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Assets {
+    // e.g. "steam/apps/620/${FILENAME}?t=1234"
+    asset_url_format: String,
+    main_capsule: String,
+}
+
+/// Fetch a game's store entry with the user's Web API key and cache the result
+pub fn fetch_details(app_id: &str, api_key: &str) -> Result<StoreDetails, String> {
+    let appid: u32 = app_id
+        .parse()
+        .map_err(|_| format!("'{}' is not a numeric app ID", app_id))?;
+    let request = ItemsRequest {
+        ids: [ItemId { appid }],
+        context: Context {
+            language: "english",
+            country_code: "US",
+        },
+        data_request: DataRequest {
+            include_basic_info: true,
+            include_assets: true,
+        },
+    };
+    let input_json = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+
+    let response: ItemsResponse = ureq::get(GET_ITEMS_URL)
+        .timeout(REQUEST_TIMEOUT)
+        .query("key", api_key)
+        .query("input_json", &input_json)
+        .call()
+        .map_err(|e| format!("Steam Web API request failed: {}", e))?
+        .into_json()
+        .map_err(|e| format!("Unexpected Steam Web API response: {}", e))?;
+    let item = response
+        .response
+        .store_items
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No store entry for app {}", app_id))?;
+
+    let dir = cache_dir().ok_or_else(|| "Could not find the cache directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let mut capsule_path = None;
+    if !item.assets.main_capsule.is_empty() && !item.assets.asset_url_format.is_empty() {
+        let url = format!(
+            "{}{}",
+            ASSET_BASE_URL,
+            item.assets
+                .asset_url_format
+                .replace("${FILENAME}", &item.assets.main_capsule)
+        );
+        let path = dir.join(format!("{}.jpg", app_id));
+        match download(&url, &path) {
+            Ok(()) => capsule_path = Some(path),
+            Err(e) => println!("Warning: Could not download capsule for {}: {}", app_id, e),
+        }
+    }
+
+    let details = StoreDetails {
+        description: item.basic_info.short_description,
+        capsule_path,
+    };
+    let json = serde_json::to_string_pretty(&details).map_err(|e| e.to_string())?;
+    fs::write(dir.join(format!("{}.json", app_id)), json).map_err(|e| e.to_string())?;
+    Ok(details)
+}
+
+fn download(url: &str, path: &Path) -> Result<(), String> {
+    let response = ureq::get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()
+        .map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_IMAGE_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    fs::write(path, bytes).map_err(|e| e.to_string())
+}
@@ -0,0 +1,172 @@
+//! Mirroring the config into a folder another program keeps in sync between computers
+//! (Syncthing, Nextcloud, Dropbox...). Every save writes a copy there; on startup the
+//! copy is merged back in. The last copy we wrote or merged is kept next to the config
+//! as the common base, so a setting changed on only one computer simply wins, and only
+//! settings changed differently on both are conflicts. Those keep the local value.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+// Settings that belong to this computer: its Steam, its screen, its terminal, where
+// other computers' paths are here, and state that changes all the time
+const LOCAL_ONLY: [&str; 9] = [
+    "sync_dir",
+    "steam_path",
+    "steam_id",
+    "ui_scale",
+    "terminal",
+    "last_game_name",
+    "last_app_id",
+    "recent_programs",
+    "path_remaps",
+];
+
+/// The synced copy, named after the profile so profiles don't overwrite each other
+fn remote_path(dir: &Path) -> PathBuf {
+    dir.join(format!("{}.toml", crate::config_name()))
+}
+
+/// What the synced copy looked like the last time we wrote or merged it
+fn base_path() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|d| d.join(crate::config_name()).join("sync-base.toml"))
+        .ok_or_else(|| "Could not find the config directory".to_string())
+}
+
+fn read(path: &Path) -> Result<Option<Value>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => text
+            .parse::<Table>()
+            .map(|table| Some(Value::Table(table)))
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn write(path: &Path, value: &Value) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let text = toml::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Split a config into the settings that are synced and the ones that stay here
+fn split(config: Value) -> (Value, Table) {
+    let Value::Table(mut shared) = config else {
+        return (config, Table::new());
+    };
+    let local = LOCAL_ONLY
+        .iter()
+        .filter_map(|key| shared.remove(*key).map(|value| (key.to_string(), value)))
+        .collect();
+    (Value::Table(shared), local)
+}
+
+/// Three-way merge of one value. Tables are merged key by key, anything else is
+/// taken from whichever side changed it; `path` names it in the conflict list.
+fn merge(
+    base: Option<&Value>,
+    local: &Value,
+    remote: &Value,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Value {
+    if local == remote || base == Some(remote) {
+        return local.clone();
+    }
+    if base == Some(local) {
+        return remote.clone();
+    }
+    let (Value::Table(local_table), Value::Table(remote_table)) = (local, remote) else {
+        conflicts.push(path.to_string());
+        return local.clone();
+    };
+
+    let base_table = base.and_then(Value::as_table);
+    let keys: BTreeSet<&String> = local_table.keys().chain(remote_table.keys()).collect();
+    let mut merged = Table::new();
+    for key in keys {
+        let base_value = base_table.and_then(|table| table.get(key));
+        let child = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        let value = match (local_table.get(key), remote_table.get(key)) {
+            (Some(local), Some(remote)) => {
+                Some(merge(base_value, local, remote, &child, conflicts))
+            }
+            // Removed on one side: gone, unless the other side changed it meanwhile
+            (Some(kept), None) | (None, Some(kept)) => match base_value {
+                Some(base) if base == kept => None,
+                Some(_) => {
+                    conflicts.push(child);
+                    Some(kept.clone())
+                }
+                None => Some(kept.clone()),
+            },
+            (None, None) => None,
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+    Value::Table(merged)
+}
+
+/// Whether `dir` already holds a synced copy, e.g. from another computer
+pub fn has_copy(dir: &Path) -> bool {
+    remote_path(dir).exists()
+}
+
+/// The local config with the synced copy in `dir` merged in, and the settings that
+/// were changed differently on both sides (the local value is kept for those; the
+/// synced copy is saved next to the config so nothing is lost)
+pub fn pull(dir: &Path, local: Value) -> Result<(Value, Vec<String>), String> {
+    let Some(remote) = read(&remote_path(dir))? else {
+        return Ok((local, Vec::new()));
+    };
+    let base_path = base_path()?;
+    let base = read(&base_path)?;
+    let (shared, local_only) = split(local);
+    let (remote, _) = split(remote);
+
+    let mut conflicts = Vec::new();
+    let mut merged = merge(base.as_ref(), &shared, &remote, "", &mut conflicts);
+    if !conflicts.is_empty() {
+        let copy =
+            base_path.with_file_name(format!("sync-conflict-{}.toml", crate::journal::now()));
+        write(&copy, &remote)?;
+        println!(
+            "Warning: Kept the synced settings that conflicted in {}",
+            copy.display()
+        );
+    }
+    // The synced copy is part of the local config now, so it's the new base
+    write(&base_path, &remote)?;
+    if let Value::Table(table) = &mut merged {
+        table.extend(local_only);
+    }
+    Ok((merged, conflicts))
+}
+
+/// Write the config's synced settings to `dir`, unless another computer changed the
+/// copy there since we last read it; that gets merged at the next start instead.
+pub fn push(dir: &Path, local: Value) -> Result<(), String> {
+    let remote_path = remote_path(dir);
+    let base_path = base_path()?;
+    let remote = read(&remote_path)?;
+    if remote.is_some() && remote != read(&base_path)? {
+        return Err(format!(
+            "{} was changed on another computer, restart Protonic to merge it",
+            remote_path.display()
+        ));
+    }
+    let (shared, _) = split(local);
+    write(&remote_path, &shared)?;
+    write(&base_path, &shared)
+}
@@ -0,0 +1,119 @@
+//! The Windows processes inside a game's prefix, as its wineserver sees them, so you can
+//! tell whether a program started in it is still alive. Listed with Wine's `tasklist`
+//! (or `winedbg` on Wine builds older than tasklist) and stopped with `taskkill`, both
+//! run through the game's backend like any other program.
+
+use crate::backend::Injector;
+use std::process::Command;
+
+/// A process in the prefix
+#[derive(Debug, Clone)]
+pub struct WineProcess {
+    pub name: String,
+    // Windows process ID, what taskkill takes
+    pub pid: u32,
+    // e.g. "52,148 K", empty when winedbg listed it
+    pub memory: String,
+}
+
+fn output_of(mut command: Command, what: &str) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", what, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed ({})", what, output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The column spans of tasklist's table, from the "===== ===" line under its header
+fn columns(separator: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in separator.chars().chain([' ']).enumerate() {
+        match (c, start) {
+            ('=', None) => start = Some(i),
+            (' ', Some(from)) => {
+                spans.push((from, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Parse tasklist's default table output
+fn parse_tasklist(output: &str) -> Option<Vec<WineProcess>> {
+    let mut lines = output.lines();
+    let separator = lines.find(|line| line.trim_start().starts_with("==="))?;
+    let spans = columns(separator);
+    if spans.len() < 2 {
+        return None;
+    }
+    let cell = |chars: &[char], (from, to): (usize, usize)| -> String {
+        chars
+            .get(from.min(chars.len())..to.min(chars.len()))
+            .map(|cell| cell.iter().collect::<String>().trim().to_string())
+            .unwrap_or_default()
+    };
+    let processes = lines
+        .filter_map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            Some(WineProcess {
+                name: cell(&chars, spans[0]),
+                pid: cell(&chars, spans[1]).parse().ok()?,
+                memory: if spans.len() > 2 {
+                    cell(&chars, spans[spans.len() - 1])
+                } else {
+                    String::new()
+                },
+            })
+        })
+        .collect();
+    Some(processes)
+}
+
+/// Parse the output of winedbg's `info proc`, e.g. " 0000003c 2        \_ 'cmd.exe'"
+fn parse_winedbg(output: &str) -> Vec<WineProcess> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let pid = line.split_whitespace().next()?;
+            let pid = u32::from_str_radix(pid, 16).ok()?;
+            let name = line.split('\'').nth(1)?;
+            Some(WineProcess {
+                name: name.to_string(),
+                pid,
+                memory: String::new(),
+            })
+        })
+        .collect()
+}
+
+/// The processes in the game's prefix, which has to be running
+pub fn list(injector: &Injector, app_id: &str) -> Result<Vec<WineProcess>, String> {
+    let output = output_of(injector.run_command(app_id, "tasklist"), "tasklist");
+    if let Ok(output) = &output
+        && let Some(processes) = parse_tasklist(output)
+    {
+        return Ok(processes);
+    }
+    let mut winedbg = injector.run_command(app_id, "winedbg");
+    winedbg.args(["--command", "info proc"]);
+    let processes = parse_winedbg(&output_of(winedbg, "winedbg")?);
+    if processes.is_empty() {
+        return Err(match output {
+            Err(e) => e,
+            Ok(_) => "Wine listed no processes".to_string(),
+        });
+    }
+    Ok(processes)
+}
+
+/// Stop a process in the game's prefix
+pub fn kill(injector: &Injector, app_id: &str, pid: u32) -> Result<(), String> {
+    let mut command = injector.run_command(app_id, "taskkill");
+    command.args(["/f", "/pid", &pid.to_string()]);
+    output_of(command, "taskkill").map(|_| ())
+}
@@ -0,0 +1,54 @@
+//! Opening commands in the user's terminal emulator.
+
+use std::process::{Command, Stdio};
+
+// Terminals we know how to drive, in auto-detect order, with the arguments
+// that come before the command to run
+const KNOWN_TERMINALS: [(&str, &[&str]); 9] = [
+    ("foot", &[]),
+    ("kitty", &[]),
+    ("alacritty", &["-e"]),
+    ("wezterm", &["start", "--"]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("x-terminal-emulator", &["-e"]),
+    ("xterm", &["-e"]),
+];
+
+/// First known terminal found in PATH
+pub fn detect() -> Option<&'static str> {
+    KNOWN_TERMINALS
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| crate::command_in_path(name))
+}
+
+/// Run `command` in a terminal window. `preference` is the configured terminal
+/// command (e.g. "kitty" or "alacritty -e"); empty means auto-detect.
+/// A bare known terminal name gets its usual "run this" arguments added.
+pub fn spawn(preference: &str, command: &Command) -> Result<(), String> {
+    let mut words: Vec<String> = preference.split_whitespace().map(String::from).collect();
+    if words.is_empty() {
+        let detected = detect().ok_or_else(|| {
+            "No terminal emulator found; set one in the terminal setting".to_string()
+        })?;
+        words.push(detected.to_string());
+    }
+    if words.len() == 1
+        && let Some((_, args)) = KNOWN_TERMINALS.iter().find(|(name, _)| *name == words[0])
+    {
+        words.extend(args.iter().map(|a| a.to_string()));
+    }
+
+    let child = Command::new(&words[0])
+        .args(&words[1..])
+        .arg(command.get_program())
+        .args(command.get_args())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", words[0], e))?;
+    crate::process::reap_in_background(child);
+    Ok(())
+}
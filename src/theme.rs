@@ -0,0 +1,63 @@
+//! The accent color and corner radius picked in the settings. Slint gives every window
+//! its own copy of the Theme global (theme.slint), so the current theme is kept here and
+//! set on each window that uses it: when the window is created and whenever it changes.
+
+use crate::Theme;
+use slint::{Color, ComponentHandle, Global};
+use std::cell::Cell;
+
+pub const DEFAULT_ACCENT: &str = "#3498db";
+pub const DEFAULT_RADIUS: u32 = 4;
+
+/// Accent colors offered as swatches, as (name, hex). Orange and red are left out, the
+/// window uses them for warnings and errors.
+pub const ACCENTS: [(&str, &str); 6] = [
+    ("Blue", DEFAULT_ACCENT),
+    ("Teal", "#1abc9c"),
+    ("Green", "#2ecc71"),
+    ("Purple", "#9b59b6"),
+    ("Pink", "#e84393"),
+    ("Gold", "#f1c40f"),
+];
+
+/// Corner radii offered in the settings, in pixels
+pub const RADII: [u32; 5] = [0, 2, 4, 8, 12];
+
+thread_local! {
+    // Windows are made on the UI thread, and so are theme changes
+    static CURRENT: Cell<(Color, u32)> = const {
+        Cell::new((Color::from_rgb_u8(0x34, 0x98, 0xdb), DEFAULT_RADIUS))
+    };
+}
+
+/// A "#rrggbb" color
+pub fn parse_color(text: &str) -> Result<Color, String> {
+    let hex = text.trim().trim_start_matches('#');
+    let value = u32::from_str_radix(hex, 16)
+        .ok()
+        .filter(|_| hex.len() == 6)
+        .ok_or_else(|| format!("'{}' isn't a color like #3498db", text.trim()))?;
+    let [_, red, green, blue] = value.to_be_bytes();
+    Ok(Color::from_rgb_u8(red, green, blue))
+}
+
+/// Make this the theme windows get from now on; the config's accent falls back to the
+/// default if it can't be read
+pub fn set(accent: &str, radius: u32) {
+    let color = parse_color(accent).unwrap_or_else(|e| {
+        println!("Warning: {}, using the default accent color", e);
+        parse_color(DEFAULT_ACCENT).unwrap_or_default()
+    });
+    CURRENT.with(|current| current.set((color, radius)));
+}
+
+/// Give a window the current theme
+pub fn apply<T: ComponentHandle>(window: &T)
+where
+    for<'a> Theme<'a>: Global<'a, T>,
+{
+    let (accent, radius) = CURRENT.with(Cell::get);
+    let theme = window.global::<Theme>();
+    theme.set_accent(accent);
+    theme.set_radius(radius as f32);
+}
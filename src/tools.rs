@@ -0,0 +1,339 @@
+//! Library of downloadable companion tools, stored once under ~/.local/share/protonic/tools
+//! and referenced from any game as `tool:<name>`.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Prefix marking a program path as a reference into the tool library
+pub const TOOL_REF_PREFIX: &str = "tool:";
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const API_TIMEOUT: Duration = Duration::from_secs(10);
+const GITHUB_API: &str = "https://api.github.com/repos";
+
+/// How often tools are checked for new releases
+pub const UPDATE_CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60;
+// Trainers and injectors are small; refuse anything absurd
+const MAX_DOWNLOAD_BYTES: u64 = 512 * 1024 * 1024;
+
+/// A registered tool
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Tool {
+    pub name: String,
+    pub version: String,
+    pub url: String,
+    /// Expected SHA-256 of the download, hex encoded
+    pub sha256: String,
+    /// Program to run inside a downloaded .zip; empty when the download is the program itself
+    pub executable: String,
+}
+
+impl Tool {
+    /// Directory this version is stored in
+    pub fn install_dir(&self) -> Option<PathBuf> {
+        tools_dir().map(|d| d.join(sanitize(&self.name)).join(sanitize(&self.version)))
+    }
+
+    fn file_name(&self) -> String {
+        self.url
+            .split(['?', '#'])
+            .next()
+            .and_then(|url| url.rsplit('/').next())
+            .filter(|name| !name.is_empty())
+            .map(sanitize)
+            .unwrap_or_else(|| format!("{}.exe", sanitize(&self.name)))
+    }
+
+    /// The program to launch, if this version has been downloaded
+    pub fn program_path(&self) -> Option<PathBuf> {
+        let dir = self.install_dir()?;
+        let path = if self.executable.is_empty() {
+            dir.join(self.file_name())
+        } else {
+            dir.join(&self.executable)
+        };
+        path.is_file().then_some(path)
+    }
+}
+
+fn tools_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join("tools"))
+}
+
+/// Keep names usable as a single path component
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+/// Turn a `tool:<name>` program path into the downloaded file; other paths pass through
+pub fn resolve_program(tools: &[Tool], path: &str) -> Result<String, String> {
+    let Some(name) = path.strip_prefix(TOOL_REF_PREFIX) else {
+        return Ok(path.to_string());
+    };
+    let tool = tools
+        .iter()
+        .find(|tool| tool.name == name)
+        .ok_or_else(|| format!("Tool '{}' isn't in the tool library", name))?;
+    tool.program_path()
+        .map(|p| p.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("Tool '{}' hasn't been downloaded yet", name))
+}
+
+/// Download a tool version, verify its checksum and unpack it if it's a .zip
+pub fn download(tool: &Tool) -> Result<PathBuf, String> {
+    if tool.name.is_empty() || tool.url.is_empty() {
+        return Err("A tool needs a name and a URL".to_string());
+    }
+    let dir = tool
+        .install_dir()
+        .ok_or_else(|| "Could not find the data directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let response = ureq::get(&tool.url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .call()
+        .map_err(|e| format!("Download failed: {}", e))?;
+    // One byte over the limit tells a download that's too big from one that fits exactly
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Download failed: {}", e))?;
+    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+        return Err(format!(
+            "Download failed: {} is bigger than {} MB",
+            tool.url,
+            MAX_DOWNLOAD_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let path = dir.join(tool.file_name());
+    fs::write(&path, &bytes).map_err(|e| e.to_string())?;
+
+    if tool.sha256.is_empty() {
+        println!(
+            "Warning: {} has no checksum, so its download isn't verified",
+            tool.name
+        );
+    } else {
+        let actual = sha256(&path)?;
+        if !actual.eq_ignore_ascii_case(tool.sha256.trim()) {
+            let _ = fs::remove_file(&path);
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                tool.name, tool.sha256, actual
+            ));
+        }
+    }
+
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        unzip(&path, &dir)?;
+    }
+    tool.program_path().ok_or_else(|| {
+        format!(
+            "Downloaded {}, but '{}' wasn't in it",
+            tool.name, tool.executable
+        )
+    })
+}
+
+pub fn sha256(path: &Path) -> Result<String, String> {
+    let output = Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run sha256sum: {}", e))?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(String::from)
+        .ok_or_else(|| "sha256sum gave no output".to_string())
+}
+
+fn unzip(archive: &Path, dir: &Path) -> Result<(), String> {
+    let status = Command::new("unzip")
+        .arg("-o")
+        .arg("-q")
+        .arg(archive)
+        .arg("-d")
+        .arg(dir)
+        .status()
+        .map_err(|e| format!("Failed to run unzip: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("unzip failed ({})", status))
+    }
+}
+
+/// A newer release of a tool
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub version: String,
+    pub url: String,
+    /// Empty when GitHub doesn't publish a digest for the asset
+    pub sha256: String,
+}
+
+/// Owner, repository and release tag of a GitHub release asset URL
+/// (https://github.com/<owner>/<repo>/releases/download/<tag>/<file>)
+fn github_release(url: &str) -> Option<(&str, &str, &str)> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        [owner, repo, "releases", "download", tag, _file, ..] => Some((owner, repo, tag)),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+/// Whether two release tags name the same version, with or without a leading "v"
+fn same_version(a: &str, b: &str) -> bool {
+    let number = |tag: &str| tag.trim().trim_start_matches(['v', 'V']).to_string();
+    number(a) == number(b)
+}
+
+/// Ask GitHub for the latest release of a tool downloaded from GitHub releases.
+/// Returns None when the tool is up to date or isn't hosted there.
+pub fn check_for_update(tool: &Tool) -> Result<Option<Release>, String> {
+    let Some((owner, repo, tag)) = github_release(&tool.url) else {
+        return Ok(None);
+    };
+    let latest: GithubRelease = ureq::get(&format!(
+        "{}/{}/{}/releases/latest",
+        GITHUB_API, owner, repo
+    ))
+    .timeout(API_TIMEOUT)
+    .set("Accept", "application/vnd.github+json")
+    .set(
+        "User-Agent",
+        concat!("protonic/", env!("CARGO_PKG_VERSION")),
+    )
+    .call()
+    .map_err(|e| format!("GitHub request for {} failed: {}", tool.name, e))?
+    .into_json()
+    .map_err(|e| format!("Unexpected GitHub response for {}: {}", tool.name, e))?;
+
+    let current = if tool.version.is_empty() {
+        tag
+    } else {
+        tool.version.as_str()
+    };
+    if same_version(&latest.tag_name, current) {
+        return Ok(None);
+    }
+
+    // Asset names usually carry the version, so match on the name with it swapped out
+    let file_name = tool.file_name();
+    let expected = file_name.replace(tag, &latest.tag_name);
+    let asset = latest
+        .assets
+        .iter()
+        .find(|asset| sanitize(&asset.name) == expected)
+        .or_else(|| {
+            latest
+                .assets
+                .iter()
+                .find(|asset| sanitize(&asset.name) == file_name)
+        })
+        .or(match latest.assets.as_slice() {
+            [only] => Some(only),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            format!(
+                "{} {} has no asset matching {}",
+                tool.name, latest.tag_name, file_name
+            )
+        })?;
+
+    Ok(Some(Release {
+        version: latest.tag_name.clone(),
+        url: asset.browser_download_url.clone(),
+        sha256: asset
+            .digest
+            .as_deref()
+            .and_then(|digest| digest.strip_prefix("sha256:"))
+            .unwrap_or_default()
+            .to_string(),
+    }))
+}
+
+/// Whether a tool can be called this: its files live in a folder named after it, so the
+/// name needs something left after sanitizing
+pub fn check_name(name: &str) -> Result<(), String> {
+    if sanitize(name).is_empty() {
+        Err(format!("'{}' can't be used as a tool's name", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// Tools added by hand need a SHA-256 checksum, so their downloads are always verified
+pub fn check_sha256(sha256: &str) -> Result<(), String> {
+    if sha256.len() == 64 && sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err("Enter the download's SHA-256 checksum (64 hex digits)".to_string())
+    }
+}
+
+/// Delete every downloaded version of a tool
+pub fn remove(tool: &Tool) {
+    let folder = sanitize(&tool.name);
+    // An empty folder name would be the whole tools directory
+    if folder.is_empty() {
+        println!(
+            "Warning: Not deleting files for the tool '{}', its name has no folder",
+            tool.name
+        );
+        return;
+    }
+    if let Some(dir) = tools_dir() {
+        let _ = fs::remove_dir_all(dir.join(folder));
+    }
+}
+
+/// Delete one downloaded version of a tool, e.g. after its download failed
+pub fn remove_version(tool: &Tool) {
+    // Without both folder names this would be the tool's or the library's directory
+    if sanitize(&tool.name).is_empty() || sanitize(&tool.version).is_empty() {
+        return;
+    }
+    if let Some(dir) = tool.install_dir() {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
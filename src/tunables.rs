@@ -0,0 +1,142 @@
+//! Timings that depend on how fast the system is, kept together in the `[tunables]` section
+//! of the config and editable from the advanced settings window.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct Tunables {
+    /// How often the keyboard is checked for the hotkey while waiting
+    pub key_poll_ms: u64,
+    /// Pause between starting one program and the next (games can override it)
+    pub program_delay_ms: u64,
+    /// How long stopped programs get to exit after SIGTERM before SIGKILL
+    pub kill_grace_ms: u64,
+    /// Don't rewrite a game's launch options again this soon after the last change.
+    /// Steam rewrites localconfig.vdf when it exits, and repeatedly fighting it just
+    /// stacks up journal entries.
+    pub auto_configure_cooldown_secs: u64,
+    /// How long after launching the game window is kept focused
+    pub refocus_secs: u64,
+    /// How often running sessions are refreshed in the window (applies after a restart)
+    pub session_refresh_ms: u64,
+    /// How many background disk jobs (prefix sizes, hashing, artwork) run at once
+    pub disk_jobs: u64,
+}
+
+impl Default for Tunables {
+    fn default() -> Self {
+        Self {
+            key_poll_ms: 100,
+            program_delay_ms: 500,
+            kill_grace_ms: 3000,
+            auto_configure_cooldown_secs: 30,
+            refocus_secs: 15,
+            session_refresh_ms: 2000,
+            disk_jobs: 2,
+        }
+    }
+}
+
+/// A row of the advanced settings window
+pub struct Setting {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub unit: &'static str,
+    pub min: u64,
+    pub max: u64,
+}
+
+pub const SETTINGS: [Setting; 7] = [
+    Setting {
+        key: "key_poll_ms",
+        label: "Hotkey check interval",
+        unit: "ms",
+        min: 10,
+        max: 1000,
+    },
+    Setting {
+        key: "program_delay_ms",
+        label: "Delay between programs",
+        unit: "ms",
+        min: 0,
+        max: 60_000,
+    },
+    Setting {
+        key: "kill_grace_ms",
+        label: "Time to exit before force-killing",
+        unit: "ms",
+        min: 0,
+        max: 60_000,
+    },
+    Setting {
+        key: "auto_configure_cooldown_secs",
+        label: "Auto-configure cooldown",
+        unit: "s",
+        min: 0,
+        max: 3600,
+    },
+    Setting {
+        key: "refocus_secs",
+        label: "Keep the game focused for",
+        unit: "s",
+        min: 1,
+        max: 600,
+    },
+    Setting {
+        key: "session_refresh_ms",
+        label: "Session refresh interval (after restart)",
+        unit: "ms",
+        min: 250,
+        max: 60_000,
+    },
+    Setting {
+        key: "disk_jobs",
+        label: "Background disk jobs at once",
+        unit: "",
+        min: 1,
+        max: 16,
+    },
+];
+
+impl Tunables {
+    fn field(&mut self, key: &str) -> Option<&mut u64> {
+        match key {
+            "key_poll_ms" => Some(&mut self.key_poll_ms),
+            "program_delay_ms" => Some(&mut self.program_delay_ms),
+            "kill_grace_ms" => Some(&mut self.kill_grace_ms),
+            "auto_configure_cooldown_secs" => Some(&mut self.auto_configure_cooldown_secs),
+            "refocus_secs" => Some(&mut self.refocus_secs),
+            "session_refresh_ms" => Some(&mut self.session_refresh_ms),
+            "disk_jobs" => Some(&mut self.disk_jobs),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<u64> {
+        let mut copy = *self;
+        copy.field(key).copied()
+    }
+
+    /// Set a value typed into the advanced settings window
+    pub fn set(&mut self, key: &str, text: &str) -> Result<(), String> {
+        let setting = SETTINGS
+            .iter()
+            .find(|setting| setting.key == key)
+            .ok_or_else(|| format!("Unknown setting '{}'", key))?;
+        let value: u64 = text
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} needs a whole number", setting.label))?;
+        if !(setting.min..=setting.max).contains(&value) {
+            return Err(format!(
+                "{} must be between {} and {} {}",
+                setting.label, setting.min, setting.max, setting.unit
+            ));
+        }
+        if let Some(field) = self.field(key) {
+            *field = value;
+        }
+        Ok(())
+    }
+}
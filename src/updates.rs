@@ -0,0 +1,76 @@
+//! Noticing finished game updates. Steam writes a game's new build ID into its
+//! appmanifest (steamapps/appmanifest_<app id>.acf) and marks it fully installed
+//! (StateFlags 4) once an update is done, so a build ID that differs from the last one
+//! seen means whatever updates tend to undo (copied files, patched configs) can be put back.
+
+use crate::vdf;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// StateFlags bit for a fully installed game, not queued or in the middle of an update
+const FULLY_INSTALLED: u32 = 4;
+
+/// The installed build of a game in `library`, None while an update is in progress or
+/// for games without an appmanifest (non-Steam games)
+pub fn build_id(library: &Path, app_id: &str) -> Option<String> {
+    let path = library
+        .join("steamapps")
+        .join(format!("appmanifest_{}.acf", app_id));
+    let manifest = vdf::parse(&fs::read_to_string(path).ok()?).ok()?;
+    let state = manifest.get("AppState")?;
+    let value = |key: &str| match state.get(key) {
+        Some(vdf::Node::Value(value)) => Some(value.as_str()),
+        _ => None,
+    };
+    let flags: u32 = value("StateFlags")?.parse().ok()?;
+    if flags != FULLY_INSTALLED {
+        return None;
+    }
+    value("buildid")
+        .filter(|build| !build.is_empty())
+        .map(str::to_string)
+}
+
+/// The last build seen of each watched game, kept outside the config so it isn't synced
+fn builds_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("protonic").join("builds.json"))
+}
+
+/// The games (app ID and library) that finished an update since they were last checked.
+/// A game seen for the first time is only recorded.
+pub fn updated(games: &[(String, PathBuf)]) -> Vec<String> {
+    let Some(path) = builds_path() else {
+        return Vec::new();
+    };
+    let mut builds: BTreeMap<String, String> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    let mut updated = Vec::new();
+    let mut changed = false;
+    for (app_id, library) in games {
+        let Some(build) = build_id(library, app_id) else {
+            continue;
+        };
+        match builds.insert(app_id.clone(), build.clone()) {
+            Some(seen) if seen == build => continue,
+            Some(_) => {
+                println!("Game {} was updated to build {}", app_id, build);
+                updated.push(app_id.clone());
+            }
+            None => {}
+        }
+        changed = true;
+    }
+    if changed {
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, serde_json::to_string_pretty(&builds)?));
+        if let Err(e) = written {
+            println!("Warning: Could not save {}: {}", path.display(), e);
+        }
+    }
+    updated
+}
@@ -0,0 +1,105 @@
+//! CPU and memory use of the programs Protonic started, read from /proc. A program is
+//! counted together with everything in its process group, which is where the Wine
+//! processes doing the actual work end up (see ProcessTracker::spawn).
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+
+/// What a program and its helpers use right now
+pub struct Usage {
+    // Of one core, so a program keeping two cores busy is at 200
+    pub cpu_percent: f64,
+    // Resident memory in bytes
+    pub memory: u64,
+}
+
+/// CPU time and resident pages of a process group
+#[derive(Default, Clone, Copy)]
+struct Totals {
+    ticks: u64,
+    pages: u64,
+}
+
+/// Remembers the last sample, since CPU use is CPU time spent between two samples
+#[derive(Default)]
+pub struct Sampler {
+    last: HashMap<u32, (u64, Instant)>,
+}
+
+impl Sampler {
+    /// Usage of each process group in `groups` (the programs' pids). A group shows up
+    /// from its second sample on, once there is something to compare with.
+    pub fn sample(&mut self, groups: &[u32]) -> HashMap<u32, Usage> {
+        let mut totals: HashMap<u32, Totals> = groups
+            .iter()
+            .map(|&pgid| (pgid, Totals::default()))
+            .collect();
+        if !totals.is_empty() {
+            add_processes(&mut totals);
+        }
+        // SAFETY: sysconf only reads system constants
+        let (ticks_per_sec, page_size) = unsafe {
+            (
+                libc::sysconf(libc::_SC_CLK_TCK),
+                libc::sysconf(libc::_SC_PAGESIZE),
+            )
+        };
+        let now = Instant::now();
+        let mut usage = HashMap::new();
+        for (pgid, total) in &totals {
+            if let Some((ticks, at)) = self.last.get(pgid) {
+                let elapsed = now.duration_since(*at).as_secs_f64();
+                if elapsed > 0.0 && ticks_per_sec > 0 {
+                    // Helpers that exited take their CPU time with them
+                    let spent = total.ticks.saturating_sub(*ticks) as f64 / ticks_per_sec as f64;
+                    usage.insert(
+                        *pgid,
+                        Usage {
+                            cpu_percent: spent / elapsed * 100.0,
+                            memory: total.pages * page_size.max(0) as u64,
+                        },
+                    );
+                }
+            }
+        }
+        self.last = totals
+            .into_iter()
+            .map(|(pgid, total)| (pgid, (total.ticks, now)))
+            .collect();
+        usage
+    }
+}
+
+/// Add every process in /proc to its group's totals, if it's one of `totals`
+fn add_processes(totals: &mut HashMap<u32, Totals>) {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if !name.to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        // The command name can contain spaces and parentheses, the fields after it can't
+        let Some((_, fields)) = stat.rsplit_once(')') else {
+            continue;
+        };
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        // stat(5) numbers fields from 1, and these start at field 3 (state)
+        let field = |number: usize| -> u64 {
+            fields
+                .get(number - 3)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0)
+        };
+        if let Some(total) = totals.get_mut(&(field(5) as u32)) {
+            // utime + stime
+            total.ticks += field(14) + field(15);
+            total.pages += field(24);
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! Minimal reader and writer for Valve's text KeyValues (VDF) format, as used by
+//! localconfig.vdf, config.vdf, libraryfolders.vdf and friends.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A VDF value: either a plain string or a section of ordered key/value pairs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Value(String),
+    Section(Vec<(String, Node)>),
+}
+
+impl Node {
+    /// Look up a direct child by key. VDF keys are case-insensitive.
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        match self {
+            Node::Section(pairs) => pairs
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Node::Value(_) => None,
+        }
+    }
+
+    /// Follow a chain of keys, e.g. `["UserLocalConfigStore", "Software", "Valve"]`
+    pub fn get_path(&self, path: &[&str]) -> Option<&Node> {
+        path.iter().try_fold(self, |node, key| node.get(key))
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Node> {
+        match self {
+            Node::Section(pairs) => pairs
+                .iter_mut()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Node::Value(_) => None,
+        }
+    }
+
+    pub fn get_path_mut(&mut self, path: &[&str]) -> Option<&mut Node> {
+        path.iter().try_fold(self, |node, key| node.get_mut(key))
+    }
+
+    /// Replace the value of a child, keeping its position and the key's spelling,
+    /// or append it if the section doesn't have it yet. Does nothing on a plain value.
+    pub fn set(&mut self, key: &str, value: Node) {
+        if let Some(existing) = self.get_mut(key) {
+            *existing = value;
+        } else if let Node::Section(pairs) = self {
+            pairs.push((key.to_string(), value));
+        }
+    }
+}
+
+/// Parse a whole VDF document. The result is a section holding the top-level pairs.
+pub fn parse(text: &str) -> Result<Node, String> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+        line: 1,
+    };
+    parser.parse_pairs(false).map(Node::Section)
+}
+
+/// Escape a string for writing between quotes in a VDF file
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+/// Write a document back out the way Steam formats it: tab indentation, section
+/// braces on their own lines and two tabs between a key and its value
+pub fn serialize(root: &Node) -> String {
+    let mut text = String::new();
+    if let Node::Section(pairs) = root {
+        write_pairs(&mut text, pairs, 0);
+    }
+    text
+}
+
+fn write_pairs(text: &mut String, pairs: &[(String, Node)], depth: usize) {
+    let indent = "\t".repeat(depth);
+    for (key, value) in pairs {
+        match value {
+            Node::Value(value) => {
+                text.push_str(&format!(
+                    "{}\"{}\"\t\t\"{}\"\n",
+                    indent,
+                    escape(key),
+                    escape(value)
+                ));
+            }
+            Node::Section(children) => {
+                text.push_str(&format!("{}\"{}\"\n{}{{\n", indent, escape(key), indent));
+                write_pairs(text, children, depth + 1);
+                text.push_str(&format!("{}}}\n", indent));
+            }
+        }
+    }
+}
+
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn parse_pairs(&mut self, nested: bool) -> Result<Vec<(String, Node)>, String> {
+        let mut pairs = Vec::new();
+        loop {
+            let key = match self.next_token()? {
+                Some(Token::Text(key)) => key,
+                Some(Token::Close) if nested => return Ok(pairs),
+                None if !nested => return Ok(pairs),
+                None => return Err("Unexpected end of file, missing '}'".to_string()),
+                Some(_) => return Err(format!("Expected a key on line {}", self.line)),
+            };
+            let value = match self.next_token()? {
+                Some(Token::Text(value)) => Node::Value(value),
+                Some(Token::Open) => Node::Section(self.parse_pairs(true)?),
+                _ => {
+                    return Err(format!(
+                        "Missing value for key '{}' on line {}",
+                        key, self.line
+                    ));
+                }
+            };
+            pairs.push((key, value));
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, String> {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                '\n' => {
+                    self.line += 1;
+                    self.chars.next();
+                }
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                '/' => {
+                    self.chars.next();
+                    if self.chars.peek() != Some(&'/') {
+                        return Err(format!("Unexpected '/' on line {}", self.line));
+                    }
+                    // Comment: skip the rest of the line
+                    while self.chars.peek().is_some_and(|&c| c != '\n') {
+                        self.chars.next();
+                    }
+                }
+                '[' => {
+                    // Platform conditionals like [$WIN32] don't matter to us
+                    for c in self.chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                '{' => {
+                    self.chars.next();
+                    return Ok(Some(Token::Open));
+                }
+                '}' => {
+                    self.chars.next();
+                    return Ok(Some(Token::Close));
+                }
+                '"' => {
+                    self.chars.next();
+                    return self.quoted_string().map(|s| Some(Token::Text(s)));
+                }
+                _ => return Ok(Some(Token::Text(self.unquoted_string()))),
+            }
+        }
+        Ok(None)
+    }
+
+    fn quoted_string(&mut self) -> Result<String, String> {
+        let start_line = self.line;
+        let mut text = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return Ok(text),
+                '\\' => match self.chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('\\') => text.push('\\'),
+                    Some('"') => text.push('"'),
+                    Some(other) => {
+                        text.push('\\');
+                        text.push(other);
+                    }
+                    None => break,
+                },
+                '\n' => {
+                    self.line += 1;
+                    text.push(c);
+                }
+                _ => text.push(c),
+            }
+        }
+        Err(format!(
+            "Unterminated string starting on line {}",
+            start_line
+        ))
+    }
+
+    fn unquoted_string(&mut self) -> String {
+        let mut text = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                break;
+            }
+            text.push(c);
+            self.chars.next();
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALCONFIG: &str = r#""UserLocalConfigStore"
+{
+	// Written by Steam
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"apps"
+				{
+					"620"
+					{
+						"LastPlayed"		"1700000000"
+						"LaunchOptions"		"DXVK_HUD=\"fps\" %command% -path C:\\Games\\x"
+					}
+					"440"
+					{
+						"LastPlayed"		"1700000001"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+
+    const APPS: [&str; 5] = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"];
+
+    fn app<'a>(root: &'a Node, id: &str) -> &'a Node {
+        root.get_path(&APPS).and_then(|apps| apps.get(id)).unwrap()
+    }
+
+    #[test]
+    fn unescapes_values() {
+        let root = parse(LOCALCONFIG).unwrap();
+        assert_eq!(
+            app(&root, "620").get("LaunchOptions"),
+            Some(&Node::Value(
+                r#"DXVK_HUD="fps" %command% -path C:\Games\x"#.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn round_trips_through_serialize() {
+        let root = parse(LOCALCONFIG).unwrap();
+        let text = serialize(&root);
+        assert_eq!(parse(&text), Ok(root));
+        // Comments aren't kept, everything else is written the way Steam does
+        assert_eq!(text, LOCALCONFIG.replace("\t// Written by Steam\n", ""));
+    }
+
+    #[test]
+    fn escapes_survive_a_round_trip() {
+        let value = "quote \" backslash \\ newline \n tab \t unknown \\x";
+        let root = Node::Section(vec![("key".to_string(), Node::Value(value.to_string()))]);
+        assert_eq!(parse(&serialize(&root)), Ok(root));
+    }
+
+    #[test]
+    fn skips_comments_and_conditionals() {
+        let root = parse(
+            "\"root\" // trailing comment\n{\n\t\"a\"\t\t\"1\" [$WIN32]\n\t\"b\" [$LINUX] { }\n}\n",
+        )
+        .unwrap();
+        let section = root.get("root").unwrap();
+        assert_eq!(section.get("a"), Some(&Node::Value("1".to_string())));
+        assert_eq!(section.get("b"), Some(&Node::Section(Vec::new())));
+    }
+
+    #[test]
+    fn keeps_duplicate_keys() {
+        let mut root = parse("\"k\" \"first\"\n\"K\" \"second\"\n").unwrap();
+        assert_eq!(root.get("k"), Some(&Node::Value("first".to_string())));
+        root.set("k", Node::Value("changed".to_string()));
+        assert_eq!(
+            serialize(&root),
+            "\"k\"\t\t\"changed\"\n\"K\"\t\t\"second\"\n"
+        );
+    }
+
+    #[test]
+    fn adds_missing_launch_options() {
+        let mut root = parse(LOCALCONFIG).unwrap();
+        assert_eq!(app(&root, "440").get("LaunchOptions"), None);
+        let mut path = APPS.to_vec();
+        path.push("440");
+        root.get_path_mut(&path)
+            .unwrap()
+            .set("LaunchOptions", Node::Value("%command% -novid".to_string()));
+        let reparsed = parse(&serialize(&root)).unwrap();
+        let Node::Section(pairs) = app(&reparsed, "440") else {
+            panic!("440 isn't a section");
+        };
+        assert_eq!(pairs.last().unwrap().0, "LaunchOptions");
+        assert_eq!(
+            app(&reparsed, "620"),
+            app(&parse(LOCALCONFIG).unwrap(), "620")
+        );
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        assert!(parse("\"a\"\n{\n\t\"b\" \"c\"\n").is_err());
+        assert!(parse("\"a\" \"unterminated").is_err());
+        assert!(parse("\"a\"").is_err());
+    }
+}
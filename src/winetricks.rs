@@ -0,0 +1,81 @@
+//! Winetricks verbs that companion programs need inside a game's prefix, and installing
+//! them with protontricks or winetricks.
+
+use crate::proton;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Verbs companion tools ask for most, offered in the picker
+pub const COMMON_VERBS: [&str; 10] = [
+    "vcrun2022",
+    "vcrun2019",
+    "vcrun2010",
+    "dotnet48",
+    "dotnetdesktop6",
+    "d3dx9",
+    "d3dcompiler_47",
+    "xact",
+    "corefonts",
+    "mfc140",
+];
+
+/// Verbs already installed in a Proton prefix (compatdata/<appid>), per its winetricks.log
+pub fn installed_verbs(prefix: &Path) -> HashSet<String> {
+    fs::read_to_string(prefix.join("pfx").join("winetricks.log"))
+        .map(|log| {
+            log.lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Required verbs that aren't in the prefix yet, in the order they were declared
+pub fn missing_verbs(prefix: &Path, required: &[String]) -> Vec<String> {
+    let installed = installed_verbs(prefix);
+    let mut missing: Vec<String> = Vec::new();
+    for verb in required {
+        if !installed.contains(verb) && !missing.contains(verb) {
+            missing.push(verb.clone());
+        }
+    }
+    missing
+}
+
+/// Split a user-entered verb list ("dotnet48, vcrun2019") into verbs
+pub fn parse_verbs(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|verb| !verb.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Command that installs verbs into a game's prefix. protontricks knows which Proton
+/// a game uses, so it's preferred; plain winetricks is pointed at the prefix directly,
+/// and at the Wine in the game's Proton when that can be found.
+pub fn install_command(app_id: &str, prefix: &Path, verbs: &[String]) -> Result<Command, String> {
+    if crate::command_in_path("protontricks") {
+        let mut command = Command::new("protontricks");
+        command.arg(app_id).arg("-q").args(verbs);
+        Ok(command)
+    } else if crate::command_in_path("winetricks") {
+        let mut command = Command::new("env");
+        command.arg(format!("WINEPREFIX={}", prefix.join("pfx").display()));
+        if let Some(wine) = proton::Runtime::find(app_id, prefix)
+            .ok()
+            .and_then(|runtime| runtime.wine_dir())
+        {
+            let bin = wine.join("bin");
+            command
+                .arg(format!("WINE={}", bin.join("wine").display()))
+                .arg(format!("WINESERVER={}", bin.join("wineserver").display()));
+        }
+        command.arg("winetricks").arg("-q").args(verbs);
+        Ok(command)
+    } else {
+        Err("Neither protontricks nor winetricks is installed".to_string())
+    }
+}
@@ -0,0 +1,68 @@
+//! Finding program files inside Wine prefixes the way Wine itself does: drive letters
+//! through the prefix's dosdevices symlinks, and path components regardless of case.
+//! Proton sometimes recreates a prefix with different casing ("Program Files" vs
+//! "program files"), and configs should keep working when it does.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The drive letter and the rest of a Windows path like `C:\Games\Tool.exe`
+fn split_drive(path: &str) -> Option<(char, &str)> {
+    let mut chars = path.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str().strip_prefix(':')?;
+    (rest.is_empty() || rest.starts_with(['\\', '/'])).then_some((letter, rest))
+}
+
+/// The entry of `dir` called `name`, ignoring case
+fn find_entry(dir: &Path, name: &str) -> Option<PathBuf> {
+    let exact = dir.join(name);
+    if exact.symlink_metadata().is_ok() {
+        return Some(exact);
+    }
+    let wanted = name.to_lowercase();
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .find(|entry| entry.file_name().to_string_lossy().to_lowercase() == wanted)
+        .map(|entry| entry.path())
+}
+
+/// Where a program path really is. Paths that exist are returned as they are; otherwise
+/// a Windows path is looked up through `<prefix>/pfx/dosdevices` (`prefix` being the
+/// game's compatdata directory) and missing components are matched ignoring case.
+/// None if nothing matches.
+pub fn resolve(path: &str, prefix: Option<&Path>) -> Option<PathBuf> {
+    if Path::new(path).exists() {
+        return Some(PathBuf::from(path));
+    }
+
+    let (mut found, rest) = match split_drive(path) {
+        Some((letter, rest)) => {
+            let drive = format!("{}:", letter.to_ascii_lowercase());
+            (prefix?.join("pfx").join("dosdevices").join(drive), rest)
+        }
+        None if path.starts_with('/') => (PathBuf::from("/"), path),
+        None => return None,
+    };
+    for component in rest.split(['\\', '/']).filter(|c| !c.is_empty()) {
+        found = find_entry(&found, component)?;
+    }
+    found.exists().then_some(found)
+}
+
+/// Where a Windows path like `C:\Games\Mods` is in the prefix, for writing to it: the
+/// components that exist are matched ignoring case, the rest are taken as written.
+/// None if `path` has no drive letter or the drive isn't mapped.
+pub fn to_host(path: &str, prefix: &Path) -> Option<PathBuf> {
+    let (letter, rest) = split_drive(path)?;
+    let drive = format!("{}:", letter.to_ascii_lowercase());
+    let mut found = prefix.join("pfx").join("dosdevices").join(drive);
+    if !found.exists() {
+        return None;
+    }
+    for component in rest.split(['\\', '/']).filter(|c| !c.is_empty()) {
+        found = find_entry(&found, component).unwrap_or_else(|| found.join(component));
+    }
+    Some(found)
+}
@@ -0,0 +1,113 @@
+//! A bound on background disk work (measuring prefixes, hashing copied files, decoding
+//! artwork), so going through a big library doesn't saturate a laptop's disk while a
+//! game is running. Jobs wait in a queue for one of a few worker threads; how many is
+//! the `disk_jobs` tunable. In low-resource mode, background jobs don't start at all
+//! while a game is running; ones the user clicked for still do, ahead of the rest.
+
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct Pool {
+    limit: usize,
+    // Worker threads started so far; ones above the limit exit once they're done
+    workers: usize,
+    // Held back until the game exits
+    paused: bool,
+    background: VecDeque<Job>,
+    requested: VecDeque<Job>,
+}
+
+impl Pool {
+    /// The next job allowed to run
+    fn next(&mut self) -> Option<Job> {
+        if let Some(job) = self.requested.pop_front() {
+            return Some(job);
+        }
+        if self.paused {
+            return None;
+        }
+        self.background.pop_front()
+    }
+
+    /// Start workers for queued jobs, up to the limit
+    fn grow(&mut self) {
+        let queued = self.requested.len() + self.background.len();
+        let wanted = self.limit.min(self.workers + queued);
+        while self.workers < wanted {
+            self.workers += 1;
+            thread::spawn(work);
+        }
+    }
+}
+
+static POOL: Mutex<Pool> = Mutex::new(Pool {
+    limit: 2,
+    workers: 0,
+    paused: false,
+    background: VecDeque::new(),
+    requested: VecDeque::new(),
+});
+static QUEUED: Condvar = Condvar::new();
+
+pub fn set_limit(limit: u64) {
+    let mut pool = POOL.lock().unwrap();
+    pool.limit = (limit as usize).max(1);
+    pool.grow();
+    // Workers above a lower limit exit
+    QUEUED.notify_all();
+}
+
+/// Whether background work is held back for a running game
+pub fn paused() -> bool {
+    POOL.lock().unwrap().paused
+}
+
+pub fn set_paused(paused: bool) {
+    POOL.lock().unwrap().paused = paused;
+    QUEUED.notify_all();
+}
+
+/// A worker thread: runs queued jobs until there are more workers than the limit
+fn work() {
+    let mut pool = POOL.lock().unwrap();
+    loop {
+        if pool.workers > pool.limit {
+            pool.workers -= 1;
+            return;
+        }
+        match pool.next() {
+            Some(job) => {
+                drop(pool);
+                // A job that panics is reported by the panic hook; the worker carries on
+                let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                pool = POOL.lock().unwrap();
+            }
+            None => pool = QUEUED.wait(pool).unwrap(),
+        }
+    }
+}
+
+fn queue(job: Job, background: bool) {
+    let mut pool = POOL.lock().unwrap();
+    if background {
+        pool.background.push_back(job);
+    } else {
+        pool.requested.push_back(job);
+    }
+    pool.grow();
+    QUEUED.notify_one();
+}
+
+/// Run a job in the background once a worker is free
+pub fn spawn(job: impl FnOnce() + Send + 'static) {
+    queue(Box::new(job), true);
+}
+
+/// Run a job the user is waiting on once a worker is free, even while a game is running
+pub fn spawn_requested(job: impl FnOnce() + Send + 'static) {
+    queue(Box::new(job), false);
+}
@@ -0,0 +1,601 @@
+//! Launch option wrapper templates (protonhax, MangoHud, gamescope...) and how they combine.
+
+use std::fmt;
+
+/// Steam's placeholder for the game's own command line
+const PLACEHOLDER: &str = "%command%";
+
+/// What auto-configure adds when a game has no wrapper chosen
+pub const DEFAULT_TEMPLATE: &str = "protonhax init %command%";
+
+/// Templates offered in the wrapper dropdown
+pub const PRESETS: [&str; 3] = [
+    DEFAULT_TEMPLATE,
+    "mangohud %command%",
+    "gamescope -- %command%",
+];
+
+/// MangoHud overlay layouts offered per game, as (name, MANGOHUD_CONFIG value)
+pub const MANGOHUD_PRESETS: [(&str, &str); 3] = [
+    ("FPS only", "fps_only"),
+    ("Full metrics", "full"),
+    (
+        "Frametime graph",
+        "fps,frametime,frame_timing,gpu_temp,cpu_temp",
+    ),
+];
+
+const MANGOHUD_CONFIG_VAR: &str = "MANGOHUD_CONFIG";
+
+/// Variables the launch options editor offers, as (name, value it's set to when turned
+/// on, what it does)
+pub const COMMON_ENV: [(&str, &str, &str); 5] = [
+    (
+        "PROTON_LOG",
+        "1",
+        "Write a Proton log to ~/steam-<app id>.log",
+    ),
+    (
+        "DXVK_HUD",
+        "fps",
+        "DXVK's own overlay, e.g. fps,frametimes,gpuload",
+    ),
+    (
+        "PROTON_ENABLE_NVAPI",
+        "1",
+        "DLSS and Reflex on NVIDIA cards",
+    ),
+    (
+        "PROTON_USE_WINED3D",
+        "1",
+        "OpenGL instead of Vulkan for DirectX 9-11",
+    ),
+    (
+        "PROTON_NO_ESYNC",
+        "1",
+        "Turn off esync, for games that hang or crash",
+    ),
+];
+
+// Wrappers we know about when looking for conflicts
+const KNOWN_WRAPPERS: [&str; 7] = [
+    "protonhax",
+    "mangohud",
+    "gamescope",
+    "gamemoderun",
+    "obs-gamecapture",
+    "strangle",
+    "prime-run",
+];
+
+/// Byte offset of %command% (Steam accepts any case)
+fn find_placeholder(text: &str) -> Option<usize> {
+    text.to_ascii_lowercase().find(PLACEHOLDER)
+}
+
+fn replace_placeholder(template: &str, with: &str) -> String {
+    match find_placeholder(template) {
+        Some(pos) => format!(
+            "{}{}{}",
+            &template[..pos],
+            with,
+            &template[pos + PLACEHOLDER.len()..]
+        ),
+        None => template.to_string(),
+    }
+}
+
+/// The program a template runs, skipping leading VAR=value assignments
+pub fn program(template: &str) -> &str {
+    template
+        .split_whitespace()
+        .find(|word| !word.contains('='))
+        .unwrap_or_default()
+}
+
+/// A template with "protonhax init" taken out, e.g. "mangohud %command%" for
+/// "mangohud protonhax init %command%"
+pub fn without_protonhax(template: &str) -> String {
+    let words: Vec<&str> = template.split_whitespace().collect();
+    match words
+        .windows(2)
+        .position(|pair| pair == ["protonhax", "init"])
+    {
+        Some(index) => [&words[..index], &words[index + 2..]].concat().join(" "),
+        None => template.to_string(),
+    }
+}
+
+/// Launch options with protonhax taken back out, keeping everything else; None if it
+/// isn't in them. Options that were only "protonhax init %command%" become empty,
+/// which is what Steam has for a game nobody changed.
+pub fn remove_protonhax(options: &str) -> Option<String> {
+    let stripped = without_protonhax(options);
+    if stripped == options {
+        return None;
+    }
+    Some(if stripped.eq_ignore_ascii_case(PLACEHOLDER) {
+        String::new()
+    } else {
+        stripped
+    })
+}
+
+/// The VAR=value assignments in front of a template's program
+fn assignments(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split_whitespace()
+        .take_while(|word| word.contains('='))
+}
+
+/// Whether the wrapper from `template` (and the variables it sets) is already part of `options`
+pub fn is_configured(template: &str, options: &str) -> bool {
+    let program = program(template);
+    !program.is_empty()
+        && options.split_whitespace().any(|word| word == program)
+        && assignments(template)
+            .all(|assignment| options.split_whitespace().any(|w| w == assignment))
+}
+
+/// Whether a word is a VAR=value environment assignment
+pub fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Put VAR=value pairs in front of a template, skipping variables it already sets
+pub fn with_env(template: &str, env: &[String]) -> String {
+    let set: Vec<&str> = assignments(template)
+        .filter_map(|assignment| assignment.split('=').next())
+        .collect();
+    let added: Vec<&str> = env
+        .iter()
+        .map(String::as_str)
+        .filter(|pair| is_assignment(pair))
+        .filter(|pair| {
+            pair.split('=')
+                .next()
+                .is_some_and(|name| !set.contains(&name))
+        })
+        .collect();
+    if added.is_empty() {
+        template.to_string()
+    } else {
+        format!("{} {}", added.join(" "), template)
+    }
+}
+
+/// Whether a template runs the game under MangoHud
+pub fn uses_mangohud(template: &str) -> bool {
+    template
+        .split_whitespace()
+        .any(|word| word == "mangohud" || word == "--mangoapp")
+}
+
+/// The MANGOHUD_CONFIG a template sets, empty if none
+pub fn mangohud_config(template: &str) -> &str {
+    assignments(template)
+        .find_map(|assignment| {
+            assignment
+                .strip_prefix(MANGOHUD_CONFIG_VAR)?
+                .strip_prefix('=')
+        })
+        .unwrap_or_default()
+}
+
+/// Set MANGOHUD_CONFIG in front of a template, or remove it when `config` is empty.
+/// MangoHud's options are comma separated, so spaces are dropped.
+pub fn with_mangohud_config(template: &str, config: &str) -> String {
+    let config: String = config.split_whitespace().collect();
+    let prefix = format!("{}=", MANGOHUD_CONFIG_VAR);
+    let rest: Vec<&str> = template
+        .split_whitespace()
+        .filter(|word| !word.starts_with(&prefix))
+        .collect();
+    if config.is_empty() {
+        rest.join(" ")
+    } else {
+        format!("{}{} {}", prefix, config, rest.join(" "))
+    }
+}
+
+/// Combine a wrapper template with a game's existing launch options.
+/// protonhax has to sit right in front of the game, so other wrappers go outside it.
+pub fn apply(template: &str, existing: &str) -> Result<String, String> {
+    if find_placeholder(template).is_none() {
+        return Err(format!("Wrapper '{}' is missing %command%", template));
+    }
+    let existing = existing.trim();
+    if existing.is_empty() {
+        return Ok(template.to_string());
+    }
+
+    // The wrapper is already there and only its variables changed (e.g. a new
+    // MANGOHUD_CONFIG): replace those instead of wrapping the game twice
+    let wrapper_program = program(template);
+    if existing
+        .split_whitespace()
+        .any(|word| word == wrapper_program)
+    {
+        let names: Vec<&str> = assignments(template)
+            .filter_map(|assignment| assignment.split('=').next())
+            .collect();
+        let kept = existing.split_whitespace().filter(|word| {
+            !word
+                .split_once('=')
+                .is_some_and(|(name, _)| names.contains(&name))
+        });
+        return Ok(assignments(template)
+            .chain(kept)
+            .collect::<Vec<_>>()
+            .join(" "));
+    }
+
+    let Some(pos) = find_placeholder(existing) else {
+        // Options without %command% are just extra arguments for the game
+        return Ok(format!("{} {}", template, existing));
+    };
+    let end = pos + PLACEHOLDER.len();
+
+    // Wrap around an existing "protonhax init %command%" rather than inside it
+    let prefix = &existing[..pos];
+    let inner_start = match prefix.rfind("protonhax") {
+        Some(protonhax_pos) if program(template) != "protonhax" => protonhax_pos,
+        _ => pos,
+    };
+    Ok(format!(
+        "{}{}{}",
+        &existing[..inner_start],
+        replace_placeholder(template, &existing[inner_start..end]),
+        &existing[end..]
+    ))
+}
+
+/// Problems with how wrappers are combined in a set of launch options
+pub fn conflicts(options: &str) -> Vec<String> {
+    let prefix = match find_placeholder(options) {
+        Some(pos) => &options[..pos],
+        None => return Vec::new(),
+    };
+    let wrappers: Vec<&str> = prefix
+        .split_whitespace()
+        .filter(|word| KNOWN_WRAPPERS.contains(word))
+        .collect();
+
+    let mut problems = Vec::new();
+    let mut repeated: Vec<&str> = Vec::new();
+    for (index, wrapper) in wrappers.iter().enumerate() {
+        if wrappers[..index].contains(wrapper) && !repeated.contains(wrapper) {
+            repeated.push(wrapper);
+            problems.push(format!("{} is added more than once", wrapper));
+        }
+    }
+    if wrappers.contains(&"mangohud") && wrappers.contains(&"gamescope") {
+        problems.push(
+            "MangoHud can't draw inside gamescope; use gamescope's --mangoapp option instead"
+                .to_string(),
+        );
+    }
+    if let Some(protonhax_index) = wrappers.iter().position(|w| *w == "protonhax")
+        && let Some(after) = wrappers[protonhax_index + 1..]
+            .iter()
+            .find(|w| **w != "protonhax")
+    {
+        problems.push(format!(
+            "protonhax needs to start the game directly; move {} in front of it",
+            after
+        ));
+    }
+    problems
+}
+
+/// Split launch options into words the way the shell Steam runs them with does: quotes
+/// keep spaces in a word, e.g. MANGOHUD_CONFIG="fps, gpu_stats" or -profile "My Save"
+fn split(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            // Inside double quotes a backslash only escapes what the shell would expand
+            (Some('"'), '\\') => match chars.next() {
+                Some(next @ ('"' | '\\' | '$' | '`')) => current.push(next),
+                Some(next) => current.extend(['\\', next]),
+                None => current.push('\\'),
+            },
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                current.extend(chars.next());
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+/// Quote a word for the shell if it needs it; the reverse of split
+fn quote(word: &str) -> String {
+    let special = |c: char| c.is_whitespace() || "\"'\\$`;&|<>()*?[]{}#~!".contains(c);
+    if !word.is_empty() && !word.contains(special) {
+        word.to_string()
+    } else if word.contains(['"', '\\', '$', '`']) {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    } else {
+        format!("\"{}\"", word)
+    }
+}
+
+/// A VAR=value assignment with only its value quoted, as it's usually written
+fn quote_assignment(assignment: &str) -> String {
+    match assignment.split_once('=') {
+        Some((name, value)) => format!("{}={}", name, quote(value)),
+        None => quote(assignment),
+    }
+}
+
+/// Launch options taken apart, so wrappers and variables can be turned on and off
+/// without disturbing the rest
+#[derive(Default)]
+pub struct Parts {
+    // VAR=value assignments in front of everything
+    pub env: Vec<String>,
+    // Wrappers in front of %command%, outermost first, each followed by its arguments
+    pub wrappers: Vec<Vec<String>>,
+    // Arguments for the game itself
+    pub args: Vec<String>,
+}
+
+impl Parts {
+    pub fn parse(options: &str) -> Parts {
+        let Some(pos) = find_placeholder(options) else {
+            // Options without %command% are just extra arguments for the game
+            return Parts {
+                args: split(options),
+                ..Parts::default()
+            };
+        };
+        let mut parts = Parts {
+            args: split(&options[pos + PLACEHOLDER.len()..]),
+            ..Parts::default()
+        };
+        let mut prefix = split(&options[..pos]).into_iter().peekable();
+        while let Some(assignment) = prefix.next_if(|word| is_assignment(word)) {
+            parts.env.push(assignment);
+        }
+        // A known wrapper starts a new one; anything else is an argument of the last
+        for word in prefix {
+            match parts.wrappers.last_mut() {
+                Some(wrapper) if !KNOWN_WRAPPERS.contains(&word.as_str()) => wrapper.push(word),
+                _ => parts.wrappers.push(vec![word]),
+            }
+        }
+        parts
+    }
+
+    /// The arguments a wrapper runs with, None if it isn't used
+    pub fn wrapper(&self, program: &str) -> Option<&[String]> {
+        self.wrappers
+            .iter()
+            .find(|wrapper| wrapper[0] == program)
+            .map(|wrapper| &wrapper[1..])
+    }
+
+    /// Use a wrapper with these arguments, or stop using it (None). A new wrapper goes
+    /// outside the others, except protonhax, which has to start the game itself.
+    pub fn set_wrapper(&mut self, program: &str, args: Option<&[String]>) {
+        let position = self
+            .wrappers
+            .iter()
+            .position(|wrapper| wrapper[0] == program);
+        match (position, args) {
+            (Some(index), Some(args)) => {
+                self.wrappers[index].truncate(1);
+                self.wrappers[index].extend_from_slice(args);
+            }
+            (Some(index), None) => {
+                self.wrappers.remove(index);
+            }
+            (None, Some(args)) => {
+                let mut wrapper = vec![program.to_string()];
+                wrapper.extend_from_slice(args);
+                if program == "protonhax" {
+                    self.wrappers.push(wrapper);
+                } else {
+                    self.wrappers.insert(0, wrapper);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// The value a variable is set to, None if it isn't
+    pub fn env(&self, name: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .find_map(|assignment| assignment.strip_prefix(name)?.strip_prefix('='))
+    }
+
+    /// Set a variable, or remove it (None); one that's already set keeps its place
+    pub fn set_env(&mut self, name: &str, value: Option<&str>) {
+        let position = self.env.iter().position(|assignment| {
+            assignment
+                .split_once('=')
+                .is_some_and(|(set, _)| set == name)
+        });
+        let assignment = value.map(|value| format!("{}={}", name, value));
+        match (position, assignment) {
+            (Some(index), Some(assignment)) => self.env[index] = assignment,
+            (Some(index), None) => {
+                self.env.remove(index);
+            }
+            (None, Some(assignment)) => self.env.push(assignment),
+            (None, None) => {}
+        }
+    }
+}
+
+/// Put the options back together around %command%; empty when nothing is left, which is
+/// what Steam has for a game nobody changed
+impl fmt::Display for Parts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.env.is_empty() && self.wrappers.is_empty() && self.args.is_empty() {
+            return Ok(());
+        }
+        let words: Vec<String> = self
+            .env
+            .iter()
+            .map(|assignment| quote_assignment(assignment))
+            .chain(self.wrappers.iter().flatten().map(|word| quote(word)))
+            .chain([PLACEHOLDER.to_string()])
+            .chain(self.args.iter().map(|word| quote(word)))
+            .collect();
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+/// gamescope's output resolution and refresh rate, as typed in the editor (empty for
+/// gamescope's own default)
+#[derive(Default)]
+pub struct Gamescope {
+    pub width: String,
+    pub height: String,
+    pub refresh: String,
+    pub fullscreen: bool,
+}
+
+impl Gamescope {
+    pub fn from_args(args: &[String]) -> Gamescope {
+        let mut gamescope = Gamescope::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let value = match arg.as_str() {
+                "-W" | "--output-width" => &mut gamescope.width,
+                "-H" | "--output-height" => &mut gamescope.height,
+                "-r" | "--nested-refresh" => &mut gamescope.refresh,
+                "-f" | "--fullscreen" => {
+                    gamescope.fullscreen = true;
+                    continue;
+                }
+                _ => continue,
+            };
+            *value = args.next().cloned().unwrap_or_default();
+        }
+        gamescope
+    }
+
+    /// gamescope's arguments with these settings in place of the ones in `args`, keeping
+    /// any others
+    pub fn apply(&self, args: &[String]) -> Vec<String> {
+        let mut kept = Vec::new();
+        let mut old = args.iter();
+        while let Some(arg) = old.next() {
+            match arg.as_str() {
+                "-W" | "--output-width" | "-H" | "--output-height" | "-r" | "--nested-refresh" => {
+                    old.next();
+                }
+                "-f" | "--fullscreen" | "--" => {}
+                _ => kept.push(arg.clone()),
+            }
+        }
+        let mut args = Vec::new();
+        for (flag, value) in [
+            ("-W", &self.width),
+            ("-H", &self.height),
+            ("-r", &self.refresh),
+        ] {
+            if !value.trim().is_empty() {
+                args.extend([flag.to_string(), value.trim().to_string()]);
+            }
+        }
+        if self.fullscreen {
+            args.push("-f".to_string());
+        }
+        args.extend(kept);
+        // Everything after -- is the command gamescope runs
+        args.push("--".to_string());
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_like_the_shell() {
+        assert_eq!(
+            split(
+                r#"MANGOHUD_CONFIG="fps, gpu_stats" gamemoderun %command% -profile 'My Save' a\ b"#
+            ),
+            [
+                "MANGOHUD_CONFIG=fps, gpu_stats",
+                "gamemoderun",
+                "%command%",
+                "-profile",
+                "My Save",
+                "a b",
+            ]
+        );
+        assert_eq!(split(r#""a\"b\\c\d""#), [r#"a"b\c\d"#]);
+        assert_eq!(split(r#"'it''s' "" x"#), ["its", "", "x"]);
+        assert!(split("  \t ").is_empty());
+    }
+
+    #[test]
+    fn quotes_only_when_needed() {
+        assert_eq!(quote("-novid"), "-novid");
+        assert_eq!(quote(""), "\"\"");
+        assert_eq!(quote("My Save"), "\"My Save\"");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+        assert_eq!(quote("it's \"here\""), r#"'it'\''s "here"'"#);
+        assert_eq!(quote_assignment("A=b c"), "A=\"b c\"");
+    }
+
+    #[test]
+    fn quote_is_the_reverse_of_split() {
+        for word in [
+            "plain",
+            "",
+            "two words",
+            "$VAR `cmd` \\ \"q\"",
+            "it's",
+            "tab\there",
+            "semi;colon",
+        ] {
+            assert_eq!(split(&quote(word)), [word], "{:?}", word);
+        }
+    }
+
+    #[test]
+    fn parts_round_trip() {
+        let options = "DXVK_HUD=fps MANGOHUD_CONFIG=\"fps, gpu_stats\" mangohud gamemoderun %command% -novid \"My Save\"";
+        let parts = Parts::parse(options);
+        assert_eq!(parts.env("MANGOHUD_CONFIG"), Some("fps, gpu_stats"));
+        assert_eq!(parts.wrapper("gamemoderun"), Some(&[][..]));
+        assert_eq!(parts.to_string(), options);
+        assert_eq!(Parts::parse("").to_string(), "");
+    }
+}